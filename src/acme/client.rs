@@ -1,34 +1,326 @@
-use base64ct::Encoding;
-use jose_jwk::Jwk;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use http::HeaderMap;
 use p256::ecdsa::SigningKey;
-use reqwest::Client;
-use serde::{Deserialize, Serialize};
+use reqwest::header::{CONTENT_TYPE, LOCATION, RETRY_AFTER};
+use reqwest::{Client, Response};
+use serde::de::DeserializeOwned;
+use snafu::ResultExt;
+
+use crate::acme::request::{
+  AcmeFinalizeReq, AcmeNewAccountReq, AcmeNewAuthzReq, AcmeNewOrderReq, JsonWebHeader, JsonWebKey,
+  JsonWebObject,
+};
+use crate::acme::{
+  AcmeAccount, AcmeAuthorization, AcmeChallenge, AcmeDirectory, AcmeError, AcmeIdentifier,
+  AcmeOrder,
+};
+use crate::errors::{PlainTextSnafu, ReqwestClientSnafu, Result};
+use crate::util::{base64_url_unpadded_str, json_serialize};
 
-use crate::{acme::AcmeDirectory, errors::Result};
+const REPLAY_NONCE: &str = "Replay-Nonce";
+const BAD_NONCE: &str = "urn:ietf:params:acme:error:badNonce";
+const BAD_NONCE_RETRIES: usize = 3;
 
-// #[derive(Debug)]
+/// Client of ACME server, requests are signed by ES256 account key
 pub struct AcmeClient {
   client: Client,
-  director: AcmeDirectory,
+  directory: AcmeDirectory,
+  key: SigningKey,
+  jwk: JsonWebKey,
+  account_url: Option<String>,
+  nonce: Mutex<Option<String>>,
+  pub(crate) poll_interval: Duration,
+  pub(crate) poll_timeout: Duration,
+  pub(crate) concurrency: usize,
 }
 
 impl AcmeClient {
-  pub fn new_with_dir(directory: AcmeDirectory) -> Self {
+  /// Create client with directory fetched from `directory_url`, for example,
+  /// `https://acme-v02.api.letsencrypt.org/directory`
+  pub async fn new(directory_url: &str, key: SigningKey) -> Result<Self> {
+    let client = Client::new();
+    let directory = client
+      .get(directory_url)
+      .send()
+      .await
+      .and_then(|res| res.error_for_status())
+      .context(ReqwestClientSnafu)?
+      .json::<AcmeDirectory>()
+      .await
+      .context(ReqwestClientSnafu)?;
+    Ok(Self::new_with_dir(directory, key).client(client))
+  }
+
+  /// Create client with `directory` fetched already, requests after the directory are signed by
+  /// account `key`
+  pub fn new_with_dir(directory: AcmeDirectory, key: SigningKey) -> Self {
     Self {
       client: Client::new(),
-      director: directory,
+      directory,
+      jwk: JsonWebKey::new(&key),
+      key,
+      account_url: None,
+      nonce: Mutex::new(None),
+      poll_interval: Duration::from_secs(2),
+      poll_timeout: Duration::from_secs(120),
+      concurrency: 8,
     }
   }
 
+  /// Set http client, for example, with proxy or custom root certificates
+  pub fn client(mut self, client: Client) -> Self {
+    self.client = client;
+    self
+  }
+
+  /// Use existing account at `url`, instead of [`Self::new_account`]
+  pub fn account(mut self, url: impl Into<String>) -> Self {
+    self.account_url = Some(url.into());
+    self
+  }
+
+  /// Interval of polling authorization and order, default is 2 seconds
+  pub fn poll_interval(mut self, interval: Duration) -> Self {
+    self.poll_interval = interval;
+    self
+  }
+
+  /// Timeout of waiting authorization or order to change status, default is 120 seconds
+  pub fn poll_timeout(mut self, timeout: Duration) -> Self {
+    self.poll_timeout = timeout;
+    self
+  }
+
+  /// Max number of challenges presented, cleaned up or polled at the same time, to respect rate
+  /// limit of DNS provider api, default is 8
+  pub fn concurrency(mut self, limit: usize) -> Self {
+    self.concurrency = limit.max(1);
+    self
+  }
+
+  pub fn directory(&self) -> &AcmeDirectory {
+    &self.directory
+  }
+
+  /// Url of account, used as `kid` of requests
+  pub fn account_url(&self) -> Option<&str> {
+    self.account_url.as_deref()
+  }
+
   pub async fn new_nonce(&self) -> Result<String> {
     let res = self
       .client
-      .head(&self.director.new_nonce)
+      .head(&self.directory.new_nonce)
       .send()
       .await
-      .unwrap();
-    let nonce = res.headers().get("Replay-Nonce");
-    Ok(nonce.unwrap().to_str().unwrap().to_string())
+      .context(ReqwestClientSnafu)?;
+    match replay_nonce(res.headers()) {
+      Some(nonce) => Ok(nonce),
+      None => PlainTextSnafu {
+        message: format!("ACME Error: no nonce in response of {}", self.directory.new_nonce),
+      }
+      .fail(),
+    }
+  }
+
+  /// Create account of the key, or find the existing one, its url is used by later requests
+  pub async fn new_account(&mut self, contact: &[String], terms_agreed: bool) -> Result<AcmeAccount> {
+    let req = AcmeNewAccountReq {
+      contact,
+      terms_of_service_agreed: terms_agreed,
+    };
+    let url = self.directory.new_account.clone();
+    let (account, location) = self.post_json(&url, &json_serialize(&req)?).await?;
+    match location {
+      Some(location) => self.account_url = Some(location),
+      None => {
+        return PlainTextSnafu {
+          message: "ACME Error: no account url in response of newAccount".to_string(),
+        }
+        .fail();
+      }
+    }
+    Ok(account)
+  }
+
+  /// Create order of `identifiers`
+  ///
+  /// `return`: url of order and order
+  pub async fn new_order(&self, identifiers: &[AcmeIdentifier]) -> Result<(String, AcmeOrder)> {
+    let req = AcmeNewOrderReq { identifiers };
+    let (order, location) = self
+      .post_json(&self.directory.new_order, &json_serialize(&req)?)
+      .await?;
+    match location {
+      Some(location) => Ok((location, order)),
+      None => PlainTextSnafu {
+        message: "ACME Error: no order url in response of newOrder".to_string(),
+      }
+      .fail(),
+    }
+  }
+
+  /// Create authorization of `identifier` ahead of order, by newAuthz of directory, wildcard
+  /// identifier is not allowed, see [RFC 8555](https://www.rfc-editor.org/rfc/rfc8555#section-7.4.1)
+  ///
+  /// `return`: url of authorization and authorization
+  pub async fn new_authorization(
+    &self,
+    identifier: &AcmeIdentifier,
+  ) -> Result<(String, AcmeAuthorization)> {
+    let Some(new_authz) = &self.directory.new_authz else {
+      return PlainTextSnafu {
+        message: "ACME Error: pre-authorization (newAuthz) is not supported by the CA".to_string(),
+      }
+      .fail();
+    };
+    if identifier.value.starts_with("*.") {
+      return PlainTextSnafu {
+        message: format!(
+          "ACME Error: wildcard identifier {} can not be pre-authorized, it is authorized by order",
+          identifier.value
+        ),
+      }
+      .fail();
+    }
+    let req = AcmeNewAuthzReq { identifier };
+    let (authz, location) = self.post_json(new_authz, &json_serialize(&req)?).await?;
+    match location {
+      Some(location) => Ok((location, authz)),
+      None => PlainTextSnafu {
+        message: "ACME Error: no authorization url in response of newAuthz".to_string(),
+      }
+      .fail(),
+    }
+  }
+
+  pub async fn order(&self, url: &str) -> Result<AcmeOrder> {
+    Ok(self.post_json(url, "").await?.0)
+  }
+
+  /// Order at `url` and `Retry-After` of response, the interval to poll it again
+  pub(crate) async fn poll_order(&self, url: &str) -> Result<(AcmeOrder, Option<Duration>)> {
+    let res = self.post(url, "").await?;
+    let retry_after = retry_after(res.headers());
+    let order = res.json::<AcmeOrder>().await.context(ReqwestClientSnafu)?;
+    Ok((order, retry_after))
+  }
+
+  pub async fn authorization(&self, url: &str) -> Result<AcmeAuthorization> {
+    Ok(self.post_json(url, "").await?.0)
+  }
+
+  /// Tell server the challenge at `url` is ready to be validated
+  pub async fn respond_challenge(&self, url: &str) -> Result<AcmeChallenge> {
+    Ok(self.post_json(url, "{}").await?.0)
+  }
+
+  /// Finalize ready order with DER of CSR, see [`certificate_request`](crate::acme::certificate_request)
+  pub async fn finalize(&self, order: &AcmeOrder, csr: &[u8]) -> Result<AcmeOrder> {
+    let req = AcmeFinalizeReq {
+      csr: base64_url_unpadded_str(csr),
+    };
+    Ok(self.post_json(&order.finalize, &json_serialize(&req)?).await?.0)
+  }
+
+  /// Download certificate chain in PEM
+  pub async fn certificate(&self, url: &str) -> Result<String> {
+    let res = self.post(url, "").await?;
+    res.text().await.context(ReqwestClientSnafu)
+  }
+
+  /// Key authorization of challenge `token`, `{token}.{thumbprint of account key}`
+  pub fn key_authorization(&self, token: &str) -> Result<String> {
+    Ok(format!("{}.{}", token, self.jwk.thumbprint()?))
+  }
+}
+
+impl AcmeClient {
+  async fn post_json<R: DeserializeOwned>(
+    &self,
+    url: &str,
+    payload: &str,
+  ) -> Result<(R, Option<String>)> {
+    let res = self.post(url, payload).await?;
+    let location = res
+      .headers()
+      .get(LOCATION)
+      .and_then(|v| v.to_str().ok())
+      .map(|v| v.to_string());
+    let data = res.json::<R>().await.context(ReqwestClientSnafu)?;
+    Ok((data, location))
+  }
+
+  /// Signed POST request, `payload` is empty for POST-as-GET. Requests are signed with `kid` of
+  /// account, except newAccount with `jwk`. Request rejected by `badNonce` is retried
+  async fn post(&self, url: &str, payload: &str) -> Result<Response> {
+    let mut retries = 0;
+    loop {
+      let nonce = match self.take_nonce() {
+        Some(nonce) => nonce,
+        None => self.new_nonce().await?,
+      };
+      let header = JsonWebHeader::new("ES256", nonce, url);
+      let header = match &self.account_url {
+        _ if url == self.directory.new_account => header.jwk(self.jwk.clone()),
+        Some(account_url) => header.kid(account_url),
+        None => {
+          return PlainTextSnafu {
+            message: format!("ACME Error: no account to sign request of {}", url),
+          }
+          .fail();
+        }
+      };
+      let body = json_serialize(&JsonWebObject::new(&header, payload, &self.key)?)?;
+      let res = self
+        .client
+        .post(url)
+        .header(CONTENT_TYPE, "application/jose+json")
+        .body(body)
+        .send()
+        .await
+        .context(ReqwestClientSnafu)?;
+      if let Some(nonce) = replay_nonce(res.headers()) {
+        *self.nonce.lock().unwrap_or_else(|e| e.into_inner()) = Some(nonce);
+      }
+      let status = res.status();
+      if status.is_success() {
+        return Ok(res);
+      }
+      let message = match res.json::<AcmeError>().await {
+        Ok(problem) if problem.etype == BAD_NONCE && retries < BAD_NONCE_RETRIES => {
+          retries += 1;
+          continue;
+        }
+        Ok(problem) => format!("ACME Error: request of {} failed, {}", url, problem),
+        Err(_) => format!("ACME Error: request of {} failed, status: {}", url, status),
+      };
+      return PlainTextSnafu { message }.fail();
+    }
+  }
+
+  fn take_nonce(&self) -> Option<String> {
+    self.nonce.lock().unwrap_or_else(|e| e.into_inner()).take()
   }
 }
 
+fn replay_nonce(headers: &HeaderMap) -> Option<String> {
+  headers
+    .get(REPLAY_NONCE)
+    .and_then(|v| v.to_str().ok())
+    .map(|v| v.to_string())
+}
+
+/// `Retry-After` in seconds or HTTP date, see
+/// [RFC 9110](https://www.rfc-editor.org/rfc/rfc9110#section-10.2.3)
+fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+  let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+  if let Ok(seconds) = value.parse() {
+    return Some(Duration::from_secs(seconds));
+  }
+  let date = jiff::fmt::rfc2822::parse(value).ok()?;
+  let seconds = date.timestamp().as_second() - jiff::Timestamp::now().as_second();
+  Some(Duration::from_secs(seconds.max(0) as u64))
+}
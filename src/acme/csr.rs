@@ -0,0 +1,62 @@
+use rcgen::{CertificateParams, DistinguishedName, KeyPair, SanType};
+use snafu::ResultExt;
+
+use crate::{
+  acme::{AcmeIdentifier, AcmeIdentifierType},
+  errors::{GenerateCertificateSnafu, PlainTextSnafu, Result},
+};
+
+/// DER of certificate signing request for `identifiers`, sent to `finalize` of order. Domain
+/// names are encoded as `dNSName`, and IP addresses as `iPAddress` of subject alternative names.
+pub fn certificate_request(identifiers: &[AcmeIdentifier], key_pair: &KeyPair) -> Result<Vec<u8>> {
+  let mut params = CertificateParams::default();
+  // subject is optional, CA takes names from subject alternative names
+  params.distinguished_name = DistinguishedName::new();
+  for identifier in identifiers {
+    identifier.validate()?;
+    let san = match (identifier.itype, identifier.ip_addr()) {
+      (AcmeIdentifierType::IP, Some(ip)) => SanType::IpAddress(ip),
+      (AcmeIdentifierType::DNS, _) => SanType::DnsName(
+        identifier
+          .value
+          .clone()
+          .try_into()
+          .context(GenerateCertificateSnafu)?,
+      ),
+      _ => {
+        return PlainTextSnafu {
+          message: format!("Error: invalid identifier {}", identifier.value),
+        }
+        .fail();
+      }
+    };
+    params.subject_alt_names.push(san);
+  }
+  let csr = params
+    .serialize_request(key_pair)
+    .context(GenerateCertificateSnafu)?;
+  Ok(csr.der().to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn ip_address_san() {
+    let key_pair = KeyPair::generate().unwrap();
+    let identifiers = [
+      AcmeIdentifier::parse("example.com").unwrap(),
+      AcmeIdentifier::parse("192.0.2.1").unwrap(),
+      AcmeIdentifier::parse("2001:db8::1").unwrap(),
+    ];
+    let csr = certificate_request(&identifiers, &key_pair).unwrap();
+    // iPAddress is [7] IMPLICIT OCTET STRING of 4 or 16 bytes
+    assert!(csr.windows(6).any(|w| w == [0x87, 0x04, 192, 0, 2, 1]));
+    let mut ipv6 = vec![0x87, 0x10, 0x20, 0x01, 0x0d, 0xb8];
+    ipv6.extend_from_slice(&[0; 11]);
+    ipv6.push(1);
+    assert!(csr.windows(ipv6.len()).any(|w| w == ipv6));
+    assert!(csr.windows(13).any(|w| w == b"\x82\x0bexample.com"));
+  }
+}
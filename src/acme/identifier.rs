@@ -0,0 +1,146 @@
+use std::net::IpAddr;
+
+use crate::{
+  acme::{AcmeChallengeType, AcmeIdentifier, AcmeIdentifierType},
+  challenge::tls_alpn::reverse_dns_name,
+  errors::{PlainTextSnafu, Result},
+};
+
+impl AcmeIdentifier {
+  /// Identifier of domain name, for example, `example.com` or `*.example.com`
+  pub fn dns(domain: &str) -> Result<Self> {
+    let domain = domain.trim_end_matches('.').to_lowercase();
+    if !is_valid_domain(&domain) {
+      return PlainTextSnafu {
+        message: format!("Error: invalid domain name {}", domain),
+      }
+      .fail();
+    }
+    Ok(AcmeIdentifier {
+      itype: AcmeIdentifierType::DNS,
+      value: domain,
+    })
+  }
+
+  /// Identifier of IP address, IPv6 is formatted as RFC 5952, for example, `2001:db8::1`
+  pub fn ip(ip: IpAddr) -> Self {
+    AcmeIdentifier {
+      itype: AcmeIdentifierType::IP,
+      value: ip.to_string(),
+    }
+  }
+
+  /// Identifier of IP address if `value` is IPv4 or IPv6 (brackets are allowed), or domain name
+  pub fn parse(value: &str) -> Result<Self> {
+    match value.trim_matches(['[', ']']).parse::<IpAddr>() {
+      Ok(ip) => Ok(Self::ip(ip)),
+      Err(_) => Self::dns(value),
+    }
+  }
+
+  /// Check value of identifier, for example, received from server
+  pub fn validate(&self) -> Result<()> {
+    let valid = match self.itype {
+      AcmeIdentifierType::DNS => is_valid_domain(&self.value),
+      AcmeIdentifierType::IP => self.ip_addr().is_some(),
+    };
+    if valid {
+      return Ok(());
+    }
+    PlainTextSnafu {
+      message: format!("Error: invalid {:?} identifier {}", self.itype, self.value),
+    }
+    .fail()
+  }
+
+  /// IP address of identifier, `None` if it is a domain name
+  pub fn ip_addr(&self) -> Option<IpAddr> {
+    match self.itype {
+      AcmeIdentifierType::IP => self.value.parse().ok(),
+      AcmeIdentifierType::DNS => None,
+    }
+  }
+
+  /// SNI of TLS-ALPN-01 challenge, reverse DNS name for IP address, for example,
+  /// `4.3.2.1.in-addr.arpa` of `1.2.3.4`
+  pub fn tls_alpn_name(&self) -> String {
+    match self.ip_addr() {
+      Some(ip) => reverse_dns_name(ip),
+      None => self.value.clone(),
+    }
+  }
+
+  /// Whether challenge of `ctype` can validate this identifier, DNS based challenges are not
+  /// allowed for IP address, and only DNS based challenges are allowed for wildcard domain
+  pub fn supports(&self, ctype: AcmeChallengeType) -> bool {
+    let dns_based = matches!(
+      ctype,
+      AcmeChallengeType::DNS | AcmeChallengeType::DnsPersist
+    );
+    match self.itype {
+      AcmeIdentifierType::IP => !dns_based,
+      AcmeIdentifierType::DNS if self.value.starts_with("*.") => dns_based,
+      AcmeIdentifierType::DNS => true,
+    }
+  }
+
+  /// Error if challenge of `ctype` can not validate this identifier, see [`Self::supports`]
+  pub fn check_challenge(&self, ctype: AcmeChallengeType) -> Result<()> {
+    if self.supports(ctype) {
+      return Ok(());
+    }
+    let message = match self.itype {
+      AcmeIdentifierType::IP => format!(
+        "Error: {} challenge can not validate IP address {}",
+        ctype.name(),
+        self.value
+      ),
+      AcmeIdentifierType::DNS => format!(
+        "Error: wildcard identifier {} can only be validated by dns-01 or dns-persist-01 \
+         challenge, not {}",
+        self.value,
+        ctype.name()
+      ),
+    };
+    PlainTextSnafu { message }.fail()
+  }
+}
+
+/// Letters, digits and hyphens, each label is at most 63 bytes, and the first label may be `*`
+fn is_valid_domain(domain: &str) -> bool {
+  let name = domain.strip_prefix("*.").unwrap_or(domain);
+  !name.is_empty()
+    && name.len() <= 253
+    && name.parse::<IpAddr>().is_err()
+    && name.split('.').all(|label| {
+      !label.is_empty()
+        && label.len() <= 63
+        && !label.starts_with('-')
+        && !label.ends_with('-')
+        && label
+          .bytes()
+          .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_')
+    })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn parse_identifier() {
+    let ip = AcmeIdentifier::parse("192.0.2.1").unwrap();
+    assert_eq!(ip.itype, AcmeIdentifierType::IP);
+    assert_eq!(ip.tls_alpn_name(), "1.2.0.192.in-addr.arpa");
+    assert!(!ip.supports(AcmeChallengeType::DNS));
+
+    let ip = AcmeIdentifier::parse("[2001:DB8:0:0::1]").unwrap();
+    assert_eq!(ip.value, "2001:db8::1");
+    assert!(ip.validate().is_ok());
+
+    let domain = AcmeIdentifier::parse("*.Example.com").unwrap();
+    assert_eq!(domain.value, "*.example.com");
+    assert!(AcmeIdentifier::parse("exa mple.com").is_err());
+    assert!(AcmeIdentifier::parse("-example.com").is_err());
+  }
+}
@@ -1,6 +1,14 @@
 mod client;
+mod csr;
+mod identifier;
+mod order;
 mod request;
 
+pub use client::AcmeClient;
+pub use csr::certificate_request;
+
+use std::fmt::{Display, Formatter};
+
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -15,8 +23,9 @@ pub struct AcmeDirectory {
   #[serde(rename = "newOrder")]
   pub new_order: String,
 
+  /// Pre-authorization, not supported by all CAs, for example, Let's Encrypt
   #[serde(rename = "newAuthz")]
-  pub new_authz: String,
+  pub new_authz: Option<String>,
 
   #[serde(rename = "revokeCert")]
   pub revoke_cert: String,
@@ -53,10 +62,10 @@ pub struct AcmeAccount {
   #[serde(rename = "externalAccountBinding")]
   pub external_account_binding: Option<Vec<u8>>,
 
-  pub orders: String
+  pub orders: Option<String>
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum AcmeAccountStatus {
   #[serde(rename = "valid")]
   Valid,
@@ -77,7 +86,7 @@ pub struct AcmeOrderList {
 pub struct AcmeOrder {
   pub status: AcmeOrderStatus,
 
-  pub expire: Option<String>,
+  pub expires: Option<String>,
 
   pub wildcard: Option<bool>,
 
@@ -98,7 +107,7 @@ pub struct AcmeOrder {
   pub certificate: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum AcmeOrderStatus {
   #[serde(rename = "pending")]
   Pending,
@@ -116,7 +125,7 @@ pub enum AcmeOrderStatus {
   Invalid
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct AcmeIdentifier {
   #[serde(rename = "type")]
   pub itype: AcmeIdentifierType,
@@ -124,10 +133,14 @@ pub struct AcmeIdentifier {
   pub value: String
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum AcmeIdentifierType {
   #[serde(rename = "dns")]
-  DNS
+  DNS,
+
+  /// IP address, see [RFC 8738](https://www.rfc-editor.org/rfc/rfc8738)
+  #[serde(rename = "ip")]
+  IP
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -140,22 +153,29 @@ pub struct AcmeAuthorization {
 
   pub challenges: Vec<AcmeChallenge>,
 
+  /// Authorization of wildcard identifier, `identifier` is the domain without `*.`
   pub wildcard: Option<bool>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct AcmeChallenge {
-  ctype: AcmeIdentifierType,
+  #[serde(rename = "type")]
+  pub ctype: AcmeChallengeType,
 
-  url: String,
+  pub url: String,
 
-  status: AcmeChallengeStatus,
+  pub status: AcmeChallengeStatus,
 
-  validated: Option<String>,
+  pub validated: Option<String>,
+
+  pub error: Option<AcmeError>,
 
-  error: Option<AcmeError>,
+  /// Absent in dns-persist-01 challenge
+  pub token: Option<String>,
 
-  token: String
+  /// Issuer names accepted in dns-persist-01 record
+  #[serde(rename = "issuer-domain-names")]
+  pub issuer_domain_names: Option<Vec<String>>,
 }
 
 // #[derive(Debug, Serialize, Deserialize)]
@@ -171,16 +191,40 @@ pub struct AcmeChallenge {
 //
 // }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum AcmeChallengeType {
   #[serde(rename = "http-01")]
   HTTP,
 
   #[serde(rename = "dns-01")]
-  DNS
+  DNS,
+
+  #[serde(rename = "tls-alpn-01")]
+  TlsAlpn,
+
+  /// Persistent DNS record, see [draft](https://datatracker.ietf.org/doc/draft-ietf-acme-dns-persist/)
+  #[serde(rename = "dns-persist-01")]
+  DnsPersist,
+
+  /// Challenge type not supported by this crate
+  #[serde(other)]
+  Unknown
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+impl AcmeChallengeType {
+  /// Name of challenge type, for example, `dns-01`
+  pub fn name(&self) -> &'static str {
+    match self {
+      AcmeChallengeType::HTTP => "http-01",
+      AcmeChallengeType::DNS => "dns-01",
+      AcmeChallengeType::TlsAlpn => "tls-alpn-01",
+      AcmeChallengeType::DnsPersist => "dns-persist-01",
+      AcmeChallengeType::Unknown => "unknown",
+    }
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum AcmeChallengeStatus {
   #[serde(rename = "pending")]
   Pending,
@@ -195,7 +239,7 @@ pub enum AcmeChallengeStatus {
   Invalid,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum AcmeAuthorizationStatus {
   #[serde(rename = "pending")]
   Pending,
@@ -228,4 +272,14 @@ pub struct AcmeError {
   pub detail: Option<String>,
 
   pub instance: Option<String>,
-}
\ No newline at end of file
+}
+
+impl Display for AcmeError {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{}", self.etype)?;
+    if let Some(detail) = &self.detail {
+      write!(f, ", {}", detail)?;
+    }
+    Ok(())
+  }
+}
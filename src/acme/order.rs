@@ -0,0 +1,657 @@
+use std::{collections::BTreeMap, time::Instant};
+
+use futures_util::{StreamExt, TryStreamExt, stream};
+use rcgen::KeyPair;
+
+use crate::{
+  acme::{
+    AcmeAuthorization, AcmeAuthorizationStatus, AcmeChallengeType, AcmeClient, AcmeIdentifier,
+    AcmeOrder, AcmeOrderStatus, certificate_request,
+  },
+  challenge::ChallengeSolver,
+  errors::{PlainTextSnafu, Result},
+};
+
+/// Challenge chosen to validate an authorization
+struct PendingChallenge {
+  authz_url: String,
+  identifier: AcmeIdentifier,
+  url: String,
+  token: String,
+  key_authorization: String,
+}
+
+impl AcmeAuthorization {
+  /// Identifier as requested in order, `*.` is prefixed for wildcard authorization, for example,
+  /// `*.example.com` of authorization of `example.com` with `wildcard` set
+  pub fn requested_identifier(&self) -> AcmeIdentifier {
+    let mut identifier = self.identifier.clone();
+    if self.wildcard == Some(true) && !identifier.value.starts_with("*.") {
+      identifier.value = format!("*.{}", identifier.value);
+    }
+    identifier
+  }
+}
+
+impl AcmeClient {
+  /// Place order of `identifiers`, validate its authorizations with `solver`, and finalize it
+  /// with CSR of `key_pair`. Wildcard identifier, for example, `*.example.com`, requires a DNS
+  /// based solver
+  ///
+  /// `return`: certificate chain in PEM
+  pub async fn issue_certificate(
+    &self,
+    identifiers: &[AcmeIdentifier],
+    solver: &dyn ChallengeSolver,
+    key_pair: &KeyPair,
+  ) -> Result<String> {
+    for identifier in identifiers {
+      identifier.validate()?;
+      identifier.check_challenge(solver.challenge_type())?;
+    }
+    let (url, order) = self.new_order(identifiers).await?;
+    self
+      .validate_authorizations(&order.authorizations, solver)
+      .await?;
+    let order = self.wait_order(&url, AcmeOrderStatus::Ready).await?;
+    let csr = certificate_request(identifiers, key_pair)?;
+    self.finalize(&order, &csr).await?;
+    let order = self.wait_order(&url, AcmeOrderStatus::Valid).await?;
+    match &order.certificate {
+      Some(cert_url) => self.certificate(cert_url).await,
+      None => PlainTextSnafu {
+        message: format!("ACME Error: no certificate url in valid order {}", url),
+      }
+      .fail(),
+    }
+  }
+
+  /// Validate `identifiers` ahead of order with `solver`, for example, during a maintenance
+  /// window, later orders reuse the valid authorizations cached by the CA. Requires newAuthz of
+  /// CA, and wildcard identifier is not allowed
+  ///
+  /// `return`: urls of valid authorizations
+  pub async fn pre_authorize(
+    &self,
+    identifiers: &[AcmeIdentifier],
+    solver: &dyn ChallengeSolver,
+  ) -> Result<Vec<String>> {
+    for identifier in identifiers {
+      identifier.validate()?;
+      identifier.check_challenge(solver.challenge_type())?;
+    }
+    let urls: Vec<String> = stream::iter(identifiers)
+      .map(|identifier| async move { Ok(self.new_authorization(identifier).await?.0) })
+      .buffered(self.concurrency)
+      .try_collect()
+      .await?;
+    self.validate_authorizations(&urls, solver).await?;
+    Ok(urls)
+  }
+
+  /// Answer challenge of authorization at `url` with `solver`, and wait until it is valid, see
+  /// [`Self::validate_authorizations`]
+  pub async fn validate_authorization(
+    &self,
+    url: &str,
+    solver: &dyn ChallengeSolver,
+  ) -> Result<()> {
+    self
+      .validate_authorizations(&[url.to_string()], solver)
+      .await
+  }
+
+  /// Answer challenges of authorizations at `urls` with `solver`, and wait until they are valid.
+  ///
+  /// All challenges are presented first, at most [`Self::concurrency`] at the same time, and
+  /// challenges of the same domain (for example, `example.com` and `*.example.com` sharing a TXT
+  /// name) one after another. Then [`ChallengeSolver::wait_ready`] is called once, and all
+  /// challenges are responded and polled in parallel. Presented challenges are cleaned up
+  /// whether validation succeeds or not. Authorizations already valid, for example, cached by CA
+  /// from a previous order, are skipped
+  pub async fn validate_authorizations(
+    &self,
+    urls: &[String],
+    solver: &dyn ChallengeSolver,
+  ) -> Result<()> {
+    let ctype = solver.challenge_type();
+    let challenges: Vec<Option<PendingChallenge>> = stream::iter(urls)
+      .map(|url| async move {
+        let authz = self.authorization(url).await?;
+        self.pending_challenge(url, &authz, ctype)
+      })
+      .buffered(self.concurrency)
+      .try_collect()
+      .await?;
+    let challenges: Vec<PendingChallenge> = challenges.into_iter().flatten().collect();
+    if challenges.is_empty() {
+      return Ok(());
+    }
+
+    let (presented, res) = self.present_all(&challenges, solver).await;
+    let res = async {
+      res?;
+      solver.wait_ready().await?;
+      stream::iter(&challenges)
+        .map(|c| self.respond_challenge(&c.url))
+        .buffer_unordered(self.concurrency)
+        .try_collect::<Vec<_>>()
+        .await?;
+      stream::iter(&challenges)
+        .map(|c| self.wait_authorization(&c.authz_url))
+        .buffer_unordered(self.concurrency)
+        .try_collect::<Vec<_>>()
+        .await?;
+      Ok(())
+    }
+    .await;
+    let cleanup = self.cleanup_all(&presented, solver).await;
+    res.and(cleanup)
+  }
+
+  /// Challenge to answer of `authz`, `None` if it is valid already
+  fn pending_challenge(
+    &self,
+    url: &str,
+    authz: &AcmeAuthorization,
+    ctype: AcmeChallengeType,
+  ) -> Result<Option<PendingChallenge>> {
+    let identifier = authz.requested_identifier();
+    match authz.status {
+      AcmeAuthorizationStatus::Valid => return Ok(None),
+      AcmeAuthorizationStatus::Pending => {}
+      status => {
+        return PlainTextSnafu {
+          message: format!(
+            "ACME Error: authorization of {} is {:?}",
+            identifier.value, status
+          ),
+        }
+        .fail();
+      }
+    }
+    identifier.check_challenge(ctype)?;
+    let challenge = authz.challenges.iter().find(|c| c.ctype == ctype);
+    let Some((challenge, token)) = challenge.and_then(|c| Some((c, c.token.as_ref()?))) else {
+      return PlainTextSnafu {
+        message: format!(
+          "ACME Error: {} challenge is not offered for {}",
+          ctype.name(),
+          identifier.value
+        ),
+      }
+      .fail();
+    };
+    Ok(Some(PendingChallenge {
+      authz_url: url.to_string(),
+      identifier,
+      url: challenge.url.clone(),
+      key_authorization: self.key_authorization(token)?,
+      token: token.clone(),
+    }))
+  }
+
+  /// Present challenges, stop at the first error of each domain
+  ///
+  /// `return`: presented challenges, and the first error
+  async fn present_all<'a>(
+    &self,
+    challenges: &'a [PendingChallenge],
+    solver: &dyn ChallengeSolver,
+  ) -> (Vec<&'a PendingChallenge>, Result<()>) {
+    let results: Vec<_> = stream::iter(group_by_domain(challenges))
+      .map(|group| async move {
+        let mut presented = vec![];
+        for c in group {
+          let res = solver
+            .present(&c.identifier, &c.token, &c.key_authorization)
+            .await;
+          if res.is_err() {
+            return (presented, res);
+          }
+          presented.push(c);
+        }
+        (presented, Ok(()))
+      })
+      .buffer_unordered(self.concurrency)
+      .collect()
+      .await;
+    let mut presented = vec![];
+    let mut first = Ok(());
+    for (group, res) in results {
+      presented.extend(group);
+      first = first.and(res);
+    }
+    (presented, first)
+  }
+
+  /// Clean up all `challenges` even if some fail
+  ///
+  /// `return`: the first error
+  async fn cleanup_all(
+    &self,
+    challenges: &[&PendingChallenge],
+    solver: &dyn ChallengeSolver,
+  ) -> Result<()> {
+    let results: Vec<_> = stream::iter(group_by_domain(challenges.iter().copied()))
+      .map(|group| async move {
+        let mut first = Ok(());
+        for c in group {
+          let res = solver
+            .cleanup(&c.identifier, &c.token, &c.key_authorization)
+            .await;
+          first = first.and(res);
+        }
+        first
+      })
+      .buffer_unordered(self.concurrency)
+      .collect()
+      .await;
+    results.into_iter().collect()
+  }
+
+  /// Poll authorization at `url` until it is valid
+  pub(crate) async fn wait_authorization(&self, url: &str) -> Result<AcmeAuthorization> {
+    let deadline = Instant::now() + self.poll_timeout;
+    loop {
+      let authz = self.authorization(url).await?;
+      let message = match authz.status {
+        AcmeAuthorizationStatus::Valid => return Ok(authz),
+        AcmeAuthorizationStatus::Pending if Instant::now() + self.poll_interval <= deadline => {
+          tokio::time::sleep(self.poll_interval).await;
+          continue;
+        }
+        AcmeAuthorizationStatus::Pending => format!(
+          "ACME Error: authorization of {} is still pending after {:?}",
+          authz.identifier.value, self.poll_timeout
+        ),
+        status => {
+          let error = authz
+            .challenges
+            .iter()
+            .find_map(|c| c.error.as_ref())
+            .map(|e| format!(", {}", e))
+            .unwrap_or_default();
+          format!(
+            "ACME Error: authorization of {} is {:?}{}",
+            authz.requested_identifier().value,
+            status,
+            error
+          )
+        }
+      };
+      return PlainTextSnafu { message }.fail();
+    }
+  }
+
+  /// Poll order at `url` until it is `expected` status or past it, for example, `valid` when
+  /// `ready` is expected. `Retry-After` of response is used as interval if present
+  pub(crate) async fn wait_order(&self, url: &str, expected: AcmeOrderStatus) -> Result<AcmeOrder> {
+    let deadline = Instant::now() + self.poll_timeout;
+    loop {
+      let (order, retry_after) = self.poll_order(url).await?;
+      let interval = retry_after.unwrap_or(self.poll_interval);
+      let message = match order.status {
+        AcmeOrderStatus::Invalid => {
+          let error = order.error.map(|e| format!(", {}", e)).unwrap_or_default();
+          format!("ACME Error: order {} is invalid{}", url, error)
+        }
+        status if order_progress(status) >= order_progress(expected) => return Ok(order),
+        _ if Instant::now() + interval <= deadline => {
+          tokio::time::sleep(interval).await;
+          continue;
+        }
+        status => format!(
+          "ACME Error: order {} is {:?} after {:?}, expected {:?}",
+          url, status, self.poll_timeout, expected
+        ),
+      };
+      return PlainTextSnafu { message }.fail();
+    }
+  }
+}
+
+/// Position of order status in its lifecycle, `invalid` ends it at any position
+fn order_progress(status: AcmeOrderStatus) -> u8 {
+  match status {
+    AcmeOrderStatus::Pending => 0,
+    AcmeOrderStatus::Ready => 1,
+    AcmeOrderStatus::Processing => 2,
+    AcmeOrderStatus::Valid | AcmeOrderStatus::Invalid => 3,
+  }
+}
+
+/// Group challenges by domain without `*.`, in the order of `challenges`
+fn group_by_domain<'a>(
+  challenges: impl IntoIterator<Item = &'a PendingChallenge>,
+) -> Vec<Vec<&'a PendingChallenge>> {
+  let mut groups: BTreeMap<String, Vec<&PendingChallenge>> = BTreeMap::new();
+  for c in challenges {
+    let domain = c.identifier.value.trim_start_matches("*.").to_lowercase();
+    groups.entry(domain).or_default().push(c);
+  }
+  groups.into_values().collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+  };
+
+  use base64ct::{Base64UrlUnpadded, Encoding};
+  use futures_util::future::BoxFuture;
+  use p256::ecdsa::SigningKey;
+
+  use super::*;
+  use crate::{
+    acme::AcmeChallengeType,
+    challenge::dns::{challenge_name, challenge_value},
+    test_util::{StubResponse, stub_server},
+  };
+
+  /// Decoded payload of JWS in request body
+  fn payload(req: &str) -> String {
+    let payload = req
+      .split("\"payload\":\"")
+      .nth(1)
+      .unwrap()
+      .split('"')
+      .next()
+      .unwrap();
+    String::from_utf8(Base64UrlUnpadded::decode_vec(payload).unwrap()).unwrap()
+  }
+
+  /// Stub CA of one order with authorizations of `example.com` and `*.example.com`, challenges
+  /// become valid once responded, and the finalized order is polled once as processing with
+  /// `Retry-After: 0`
+  async fn stub_ca() -> (String, Arc<Mutex<Vec<String>>>) {
+    let requests = Arc::new(Mutex::new(vec![]));
+    let log = requests.clone();
+    let mut responded = [false; 2];
+    let mut finalized = false;
+    let mut processed = false;
+    let base = stub_server(move |req| {
+      let host = req
+        .lines()
+        .find_map(|l| l.strip_prefix("host: "))
+        .unwrap();
+      let url = format!("http://{}", host);
+      let mut path = req.split(' ').nth(1).unwrap().to_string();
+      log
+        .lock()
+        .unwrap()
+        .push(format!("{} {}", req.split(' ').next().unwrap(), path));
+      let created = path == "/new-authz";
+      if created {
+        assert!(payload(req).contains("\"example.com\""));
+        path = "/authz/0".to_string();
+      }
+      let order_status = match (finalized, responded) {
+        (true, _) => "valid",
+        (_, [true, true]) => "ready",
+        _ => "pending",
+      };
+      let order = format!(
+        r#"{{"status":"{}","identifiers":[{{"type":"dns","value":"example.com"}},{{"type":"dns","value":"*.example.com"}}],"authorizations":["{url}/authz/0","{url}/authz/1"],"finalize":"{url}/finalize","certificate":"{url}/cert"}}"#,
+        order_status
+      );
+      let (status, location, body) = match path.as_str() {
+        "/dir" => (
+          "200 OK",
+          "",
+          format!(
+            r#"{{"newNonce":"{url}/nonce","newAccount":"{url}/account","newOrder":"{url}/order","revokeCert":"{url}/revoke","keyChange":"{url}/key","newAuthz":"{url}/new-authz"}}"#
+          ),
+        ),
+        "/nonce" => ("200 OK", "", String::new()),
+        "/account" => (
+          "201 Created",
+          "/acct/1",
+          r#"{"status":"valid"}"#.to_string(),
+        ),
+        "/order" => {
+          assert!(payload(req).contains("*.example.com"));
+          ("201 Created", "/order/1", order)
+        }
+        "/order/1" if finalized && !processed => {
+          processed = true;
+          let order = order.replace("\"valid\"", "\"processing\"");
+          let res = StubResponse::ok(order).header("replay-nonce", "nonce");
+          return res.header("retry-after", "0");
+        }
+        "/order/1" => ("200 OK", "", order),
+        "/finalize" => {
+          assert!(payload(req).contains("csr"));
+          finalized = true;
+          ("200 OK", "", order.replace("\"ready\"", "\"processing\""))
+        }
+        "/cert" => ("200 OK", "", "-----BEGIN CERTIFICATE-----\n".to_string()),
+        path => {
+          let (kind, index) = path[1..].split_once('/').unwrap();
+          let index: usize = index.parse().unwrap();
+          if kind == "chall" {
+            responded[index] = true;
+          }
+          let status = if responded[index] { "valid" } else { "pending" };
+          let challenge = format!(
+            r#"{{"type":"dns-01","url":"{url}/chall/{index}","status":"{status}","token":"token{index}"}}"#
+          );
+          match kind {
+            "chall" => ("200 OK", "", challenge),
+            _ => (
+              "200 OK",
+              "",
+              format!(
+                r#"{{"identifier":{{"type":"dns","value":"example.com"}},"status":"{status}","challenges":[{{"type":"http-01","url":"{url}/http/{index}","status":"pending","token":"http{index}"}},{challenge}],"wildcard":{}}}"#,
+                index == 1
+              ),
+            ),
+          }
+        }
+      };
+      let (status, location) = match created {
+        true => ("201 Created", "/authz/0"),
+        false => (status, location),
+      };
+      let res = StubResponse::new(status, body).header("replay-nonce", "nonce");
+      match location {
+        "" => res,
+        location => res.header("location", format!("{}{}", url, location)),
+      }
+    })
+    .await;
+    (base, requests)
+  }
+
+  /// Records TXT name and value of presented challenges, presenting identifier `fail` fails
+  struct RecordingSolver {
+    ctype: AcmeChallengeType,
+    fail: Option<&'static str>,
+    records: Mutex<Vec<(String, String)>>,
+    cleaned: Mutex<usize>,
+  }
+
+  impl ChallengeSolver for RecordingSolver {
+    fn challenge_type(&self) -> AcmeChallengeType {
+      self.ctype
+    }
+
+    fn present<'a>(
+      &'a self,
+      identifier: &'a AcmeIdentifier,
+      _token: &'a str,
+      key_authorization: &'a str,
+    ) -> BoxFuture<'a, Result<()>> {
+      Box::pin(async move {
+        if self.fail == Some(identifier.value.as_str()) {
+          return PlainTextSnafu {
+            message: "Stub Error: present failed",
+          }
+          .fail();
+        }
+        let record = (
+          challenge_name(&identifier.value),
+          challenge_value(key_authorization),
+        );
+        self.records.lock().unwrap().push(record);
+        Ok(())
+      })
+    }
+
+    fn cleanup<'a>(
+      &'a self,
+      _identifier: &'a AcmeIdentifier,
+      _token: &'a str,
+      _key_authorization: &'a str,
+    ) -> BoxFuture<'a, Result<()>> {
+      Box::pin(async move {
+        *self.cleaned.lock().unwrap() += 1;
+        Ok(())
+      })
+    }
+  }
+
+  fn solver(ctype: AcmeChallengeType) -> RecordingSolver {
+    RecordingSolver {
+      ctype,
+      fail: None,
+      records: Mutex::new(vec![]),
+      cleaned: Mutex::new(0),
+    }
+  }
+
+  #[tokio::test]
+  async fn issue_wildcard_certificate() {
+    let (base, requests) = stub_ca().await;
+    let key = SigningKey::from_slice(&rand::random::<[u8; 32]>()).unwrap();
+    let mut client = AcmeClient::new(&format!("{}/dir", base), key)
+      .await
+      .unwrap()
+      .poll_interval(Duration::from_millis(10));
+    client.new_account(&[], true).await.unwrap();
+    assert_eq!(
+      client.account_url(),
+      Some(format!("{}/acct/1", base).as_str())
+    );
+
+    let identifiers = [
+      AcmeIdentifier::dns("example.com").unwrap(),
+      AcmeIdentifier::dns("*.example.com").unwrap(),
+    ];
+    let key_pair = KeyPair::generate().unwrap();
+    let http = solver(AcmeChallengeType::HTTP);
+    let err = client
+      .issue_certificate(&identifiers, &http, &key_pair)
+      .await
+      .unwrap_err();
+    assert!(
+      err
+        .to_string()
+        .contains("wildcard identifier *.example.com")
+    );
+    assert!(
+      !requests
+        .lock()
+        .unwrap()
+        .iter()
+        .any(|r| r.ends_with(" /order"))
+    );
+
+    let dns = solver(AcmeChallengeType::DNS);
+    let cert = client
+      .issue_certificate(&identifiers, &dns, &key_pair)
+      .await
+      .unwrap();
+    assert!(cert.starts_with("-----BEGIN CERTIFICATE-----"));
+    let records = dns.records.lock().unwrap();
+    assert_eq!(records.len(), 2);
+    assert!(
+      records
+        .iter()
+        .all(|(name, _)| name == "_acme-challenge.example.com")
+    );
+    assert_ne!(records[0].1, records[1].1);
+    assert_eq!(*dns.cleaned.lock().unwrap(), 2);
+  }
+
+  #[tokio::test]
+  async fn cleanup_when_present_fails() {
+    let (base, requests) = stub_ca().await;
+    let key = SigningKey::from_slice(&rand::random::<[u8; 32]>()).unwrap();
+    let client = AcmeClient::new(&format!("{}/dir", base), key)
+      .await
+      .unwrap()
+      .account(format!("{}/acct/1", base))
+      .concurrency(2);
+    let urls = [format!("{}/authz/0", base), format!("{}/authz/1", base)];
+    let mut dns = solver(AcmeChallengeType::DNS);
+    dns.fail = Some("*.example.com");
+    let err = client.validate_authorizations(&urls, &dns).await;
+    assert!(err.unwrap_err().to_string().contains("present failed"));
+    assert_eq!(dns.records.lock().unwrap().len(), 1);
+    assert_eq!(*dns.cleaned.lock().unwrap(), 1);
+    assert!(
+      !requests
+        .lock()
+        .unwrap()
+        .iter()
+        .any(|r| r.contains("/chall/"))
+    );
+  }
+
+  #[tokio::test]
+  async fn pre_authorize_and_skip_valid() {
+    let (base, requests) = stub_ca().await;
+    let key = SigningKey::from_slice(&rand::random::<[u8; 32]>()).unwrap();
+    let client = AcmeClient::new(&format!("{}/dir", base), key)
+      .await
+      .unwrap()
+      .account(format!("{}/acct/1", base))
+      .poll_interval(Duration::from_millis(10));
+    let dns = solver(AcmeChallengeType::DNS);
+    let wildcard = [AcmeIdentifier::dns("*.example.com").unwrap()];
+    let err = client.pre_authorize(&wildcard, &dns).await.unwrap_err();
+    assert!(err.to_string().contains("can not be pre-authorized"));
+
+    let apex = [AcmeIdentifier::dns("example.com").unwrap()];
+    let urls = client.pre_authorize(&apex, &dns).await.unwrap();
+    assert_eq!(urls, [format!("{}/authz/0", base)]);
+    assert_eq!(dns.records.lock().unwrap().len(), 1);
+
+    let dns = solver(AcmeChallengeType::DNS);
+    let identifiers = [apex[0].clone(), wildcard[0].clone()];
+    let key_pair = KeyPair::generate().unwrap();
+    client
+      .issue_certificate(&identifiers, &dns, &key_pair)
+      .await
+      .unwrap();
+    assert_eq!(dns.records.lock().unwrap().len(), 1);
+    let requests = requests.lock().unwrap();
+    assert_eq!(
+      requests.iter().filter(|r| r.contains("/chall/0")).count(),
+      1
+    );
+  }
+
+  #[tokio::test]
+  async fn wait_order_by_retry_after() {
+    let (base, _) = stub_ca().await;
+    let key = SigningKey::from_slice(&rand::random::<[u8; 32]>()).unwrap();
+    // polling in an hour times out at once, unless `Retry-After` is used
+    let client = AcmeClient::new(&format!("{}/dir", base), key)
+      .await
+      .unwrap()
+      .account(format!("{}/acct/1", base))
+      .poll_interval(Duration::from_secs(3600));
+    let url = format!("{}/order/1", base);
+    let order = client.order(&url).await.unwrap();
+    client.finalize(&order, b"csr").await.unwrap();
+    let order = client.wait_order(&url, AcmeOrderStatus::Valid).await;
+    assert_eq!(order.unwrap().status, AcmeOrderStatus::Valid);
+
+    let order = client.wait_order(&url, AcmeOrderStatus::Ready).await;
+    assert_eq!(order.unwrap().status, AcmeOrderStatus::Valid);
+  }
+}
@@ -1,14 +1,41 @@
-use jose_jwk::Jwk;
-use p256::ecdsa::signature::hazmat::PrehashSigner;
-use p256::ecdsa::SigningKey;
+use p256::ecdsa::signature::Signer;
+use p256::ecdsa::{Signature, SigningKey};
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use snafu::ResultExt;
-use crate::util::{base64_url_str, json_serialize};
+use crate::acme::AcmeIdentifier;
+use crate::util::{base64_url_unpadded_str, json_serialize};
 use crate::errors::{P256SignatureSnafu, Result};
 
+/// Public key of P-256 account key, members are in lexicographic order, so the serialized json
+/// is the input of thumbprint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct JsonWebKey {
+  crv: String,
+  kty: String,
+  x: String,
+  y: String,
+}
+
+impl JsonWebKey {
+  pub(crate) fn new(key: &SigningKey) -> Self {
+    let point = key.verifying_key().to_encoded_point(false);
+    Self {
+      crv: "P-256".to_string(),
+      kty: "EC".to_string(),
+      x: point.x().map(base64_url_unpadded_str).unwrap_or_default(),
+      y: point.y().map(base64_url_unpadded_str).unwrap_or_default(),
+    }
+  }
+
+  /// [RFC 7638](https://www.rfc-editor.org/rfc/rfc7638) thumbprint, used in key authorization
+  pub(crate) fn thumbprint(&self) -> Result<String> {
+    Ok(base64_url_unpadded_str(Sha256::digest(json_serialize(self)?)))
+  }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
-struct JsonWebHeader {
+pub(crate) struct JsonWebHeader {
   alg: String,
   nonce: String,
   url: String,
@@ -17,7 +44,7 @@ struct JsonWebHeader {
   kid: Option<String>,
 
   #[serde(skip_serializing_if = "Option::is_none")]
-  jwk: Option<Jwk>,
+  jwk: Option<JsonWebKey>,
 }
 
 impl JsonWebHeader {
@@ -36,35 +63,81 @@ impl JsonWebHeader {
     self
   }
 
-  pub fn jwk(mut self, jwk: Jwk) -> Self {
+  pub fn jwk(mut self, jwk: JsonWebKey) -> Self {
     self.jwk = Some(jwk);
     self
   }
 }
 
+/// Flattened JWS, signature is `r || s` of ES256
 #[derive(Debug, Serialize, Deserialize)]
-struct JsonWebObject {
+pub(crate) struct JsonWebObject {
   protected: String,
   payload: String,
   signature: String
 }
 
 impl JsonWebObject {
-  pub fn new(headers: &JsonWebHeader, payload: &impl Serialize, sign_key: SigningKey) -> Result<Self> {
-    let headers = base64_url_str(json_serialize(headers)?);
-    let payload = base64_url_str(json_serialize(payload)?);
-    let mut hasher = Sha256::default();
-    hasher.update(&headers);
-    hasher.update(".");
-    hasher.update(&payload);
-    let sha2sum = hasher.finalize();
-    let (signature, _) = sign_key.sign_prehash(&sha2sum)
+  /// `payload` is serialized json, or empty for POST-as-GET request
+  pub fn new(headers: &JsonWebHeader, payload: &str, sign_key: &SigningKey) -> Result<Self> {
+    let headers = base64_url_unpadded_str(json_serialize(headers)?);
+    let payload = base64_url_unpadded_str(payload);
+    let signing_input = format!("{}.{}", headers, payload);
+    let signature: Signature = sign_key.try_sign(signing_input.as_bytes())
       .context(P256SignatureSnafu)?;
     let object = Self {
       protected: headers,
       payload,
-      signature: signature.to_string(),
+      signature: base64_url_unpadded_str(signature.to_bytes()),
     };
     Ok(object)
   }
-}
\ No newline at end of file
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct AcmeNewAccountReq<'a> {
+  #[serde(skip_serializing_if = "<[_]>::is_empty")]
+  pub contact: &'a [String],
+
+  #[serde(rename = "termsOfServiceAgreed")]
+  pub terms_of_service_agreed: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct AcmeNewOrderReq<'a> {
+  pub identifiers: &'a [AcmeIdentifier],
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct AcmeNewAuthzReq<'a> {
+  pub identifier: &'a AcmeIdentifier,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct AcmeFinalizeReq {
+  /// DER of CSR in base64url
+  pub csr: String,
+}
+
+#[cfg(test)]
+mod tests {
+  use p256::ecdsa::signature::Verifier;
+  use base64ct::{Base64UrlUnpadded, Encoding};
+  use super::*;
+
+  #[test]
+  fn sign_json_web_object() {
+    let key = SigningKey::from_slice(&rand::random::<[u8; 32]>()).unwrap();
+    let header = JsonWebHeader::new("ES256", "nonce", "https://ca.example/order")
+      .kid("https://ca.example/acct/1");
+    let object = JsonWebObject::new(&header, "", &key).unwrap();
+    assert_eq!(object.payload, "");
+    let signature = Base64UrlUnpadded::decode_vec(&object.signature).unwrap();
+    let signature = Signature::from_slice(&signature).unwrap();
+    let signing_input = format!("{}.{}", object.protected, object.payload);
+    assert!(key.verifying_key().verify(signing_input.as_bytes(), &signature).is_ok());
+
+    let thumbprint = JsonWebKey::new(&key).thumbprint().unwrap();
+    assert_eq!(thumbprint.len(), 43);
+  }
+}
@@ -0,0 +1,221 @@
+use futures_util::future::BoxFuture;
+use http::HeaderName;
+use reqwest::{Client, ClientBuilder};
+use serde::de::DeserializeOwned;
+use snafu::ResultExt;
+
+use crate::{
+  challenge::dns::{
+    DnsChallengeClient, TxtRecord,
+    acme_dns::{
+      AcmeDnsAccount, AcmeDnsOption, AcmeDnsRegisterReq, AcmeDnsStorage,
+      request::AcmeDnsUpdateReq,
+      response::{AcmeDnsRes, UpdateData},
+    },
+  },
+  errors::{PlainTextSnafu, ReqwestClientSnafu, Result},
+  util::str_to_header_value,
+};
+
+/// Client for acme-dns api
+#[derive(Debug)]
+pub struct AcmeDnsClient {
+  base_url: String,
+  allow_from: Vec<String>,
+  storage: AcmeDnsStorage,
+  client: Client,
+}
+
+impl AcmeDnsClient {
+  /// Create client with option, see [`AcmeDnsOption`]
+  pub fn new(option: AcmeDnsOption) -> Result<Self> {
+    let AcmeDnsOption {
+      base_url,
+      storage_path,
+      allow_from,
+      proxy,
+      timeout,
+    } = option;
+    let mut client = ClientBuilder::new();
+    if let Some(timeout) = timeout {
+      client = client.timeout(timeout);
+    }
+    if let Some(proxy) = proxy {
+      let proxy = reqwest::Proxy::all(proxy).context(ReqwestClientSnafu)?;
+      client = client.proxy(proxy);
+    }
+    let client = client.build().context(ReqwestClientSnafu)?;
+    let storage = match storage_path {
+      Some(path) => AcmeDnsStorage::load(path)?,
+      None => AcmeDnsStorage::new(),
+    };
+    Ok(Self {
+      base_url: base_url.trim_end_matches('/').to_string(),
+      allow_from,
+      storage,
+      client,
+    })
+  }
+
+  /// Create client from environment variable, see [`AcmeDnsOption::new_from_env`]
+  pub fn new_from_env() -> Result<Self> {
+    Self::new(AcmeDnsOption::new_from_env()?)
+  }
+
+  /// Storage of registered accounts
+  pub fn storage(&self) -> &AcmeDnsStorage {
+    &self.storage
+  }
+
+  /// Register a new account
+  pub async fn register(&self, req: AcmeDnsRegisterReq<'_>) -> Result<AcmeDnsAccount> {
+    let url = format!("{}/register", self.base_url);
+    let builder = self.client.post(url).json(&req);
+    self.exec_request(builder).await
+  }
+
+  /// Update TXT record of account, acme-dns keeps the latest two values
+  ///
+  /// `return`: the value or error
+  pub async fn update_txt(&self, account: &AcmeDnsAccount, value: &str) -> Result<String> {
+    let url = format!("{}/update", self.base_url);
+    let req = AcmeDnsUpdateReq {
+      subdomain: &account.subdomain,
+      txt: value,
+    };
+    let builder = self
+      .client
+      .post(url)
+      .header(
+        HeaderName::from_static("x-api-user"),
+        str_to_header_value(&account.username)?,
+      )
+      .header(
+        HeaderName::from_static("x-api-key"),
+        str_to_header_value(&account.password)?,
+      )
+      .json(&req);
+    let data: UpdateData = self.exec_request(builder).await?;
+    Ok(data.txt)
+  }
+
+  /// Account of `domain` in storage, or register and persist a new one. After registering,
+  /// CNAME record [`AcmeDnsAccount::cname_record`] must be created before solving challenge.
+  pub async fn account(&self, domain: &str) -> Result<AcmeDnsAccount> {
+    if let Some(account) = self.storage.get(domain) {
+      return Ok(account);
+    }
+    let req = AcmeDnsRegisterReq::new().allow_from(&self.allow_from);
+    let account = self.register(req).await?;
+    self.storage.insert(domain, account.clone())?;
+    Ok(account)
+  }
+}
+
+impl DnsChallengeClient for AcmeDnsClient {
+  fn has_zone<'a>(&'a self, zone: &'a str) -> BoxFuture<'a, Result<bool>> {
+    Box::pin(async move {
+      let zone = zone.trim_end_matches('.');
+      let hosted = self.storage.accounts().iter().any(|(_, account)| {
+        let fulldomain = account.fulldomain.trim_end_matches('.');
+        fulldomain.eq_ignore_ascii_case(zone)
+          || fulldomain
+            .to_lowercase()
+            .ends_with(&format!(".{}", zone.to_lowercase()))
+      });
+      Ok(hosted)
+    })
+  }
+
+  fn create_txt<'a>(
+    &'a self,
+    zone: &'a str,
+    fqdn: &'a str,
+    value: &'a str,
+  ) -> BoxFuture<'a, Result<TxtRecord>> {
+    Box::pin(async move {
+      let Some(account) = self.storage.find_by_fulldomain(fqdn) else {
+        return PlainTextSnafu {
+          message: format!("AcmeDns Error: no account registered for {}", fqdn),
+        }
+        .fail();
+      };
+      self.update_txt(&account, value).await?;
+      Ok(TxtRecord::new(zone, fqdn, value, account.subdomain))
+    })
+  }
+
+  /// acme-dns has no delete api, old values are rotated out by later updates
+  fn delete_txt<'a>(&'a self, _record: &'a TxtRecord) -> BoxFuture<'a, Result<()>> {
+    Box::pin(async move { Ok(()) })
+  }
+}
+
+impl AcmeDnsClient {
+  async fn exec_request<R>(&self, builder: reqwest::RequestBuilder) -> Result<R>
+  where
+    R: DeserializeOwned,
+  {
+    builder
+      .send()
+      .await
+      .context(ReqwestClientSnafu)?
+      .json::<AcmeDnsRes<R>>()
+      .await
+      .context(ReqwestClientSnafu)?
+      .unwrap_data()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::test_util::{StubResponse, stub_server};
+
+  /// Stub acme-dns server, answers `/register` and `/update` with fixed response
+  async fn stub_acme_dns() -> String {
+    stub_server(|req| {
+      if req.starts_with("POST /register") {
+        return StubResponse::ok(
+          r#"{"username":"user","password":"pass","fulldomain":"sub.auth.example.org","subdomain":"sub","allowfrom":[]}"#,
+        );
+      }
+      if !req.contains("x-api-key: pass") {
+        return StubResponse::ok(r#"{"error":"forbidden"}"#);
+      }
+      let txt = req
+        .split("\"txt\":\"")
+        .nth(1)
+        .unwrap()
+        .split('"')
+        .next()
+        .unwrap();
+      StubResponse::ok(format!(r#"{{"txt":"{}"}}"#, txt))
+    })
+    .await
+  }
+
+  #[tokio::test]
+  async fn register_and_update() {
+    let base_url = stub_acme_dns().await;
+    let path =
+      std::env::temp_dir().join(format!("easy-acme-acmedns-{}.json", rand::random::<u64>()));
+    let client = AcmeDnsClient::new(AcmeDnsOption::new(&base_url).storage_path(&path)).unwrap();
+    let account = client.account("Example.com").await.unwrap();
+    assert_eq!(account.fulldomain, "sub.auth.example.org");
+    assert_eq!(
+      account.cname_record("example.com").0,
+      "_acme-challenge.example.com"
+    );
+
+    let client = AcmeDnsClient::new(AcmeDnsOption::new(&base_url).storage_path(&path)).unwrap();
+    assert_eq!(client.storage().get("example.com"), Some(account));
+    assert!(client.has_zone("auth.example.org").await.unwrap());
+    let record = client
+      .create_txt("auth.example.org", "sub.auth.example.org", "token")
+      .await
+      .unwrap();
+    assert_eq!(record.id, "sub");
+    std::fs::remove_file(path).unwrap();
+  }
+}
@@ -0,0 +1,21 @@
+//! Implement of [acme-dns](https://github.com/joohoi/acme-dns) DNS challenge
+//!
+//! `_acme-challenge` name of domain should be delegated to `fulldomain` of account by CNAME, so
+//! the account can only update TXT record of its own subdomain.
+//!
+//! Link: <https://github.com/joohoi/acme-dns#api>
+
+mod client;
+pub use client::AcmeDnsClient;
+
+mod option;
+pub use option::AcmeDnsOption;
+
+mod request;
+pub use request::AcmeDnsRegisterReq;
+
+mod response;
+pub use response::AcmeDnsAccount;
+
+mod storage;
+pub use storage::AcmeDnsStorage;
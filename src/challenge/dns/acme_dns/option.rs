@@ -0,0 +1,78 @@
+use std::{path::PathBuf, time::Duration};
+
+use crate::{errors::Result, util::env_single_var};
+
+/// Options for create an [`crate::challenge::dns::acme_dns::AcmeDnsClient`] instance
+#[derive(Debug)]
+pub struct AcmeDnsOption {
+  pub(crate) base_url: String,
+  pub(crate) storage_path: Option<PathBuf>,
+  pub(crate) allow_from: Vec<String>,
+  pub(crate) proxy: Option<String>,
+  pub(crate) timeout: Option<Duration>,
+}
+
+impl AcmeDnsOption {
+  /// Create option with base url of acme-dns server, for example, `https://auth.acme-dns.io`
+  pub fn new(base_url: impl Into<String>) -> Self {
+    AcmeDnsOption {
+      base_url: base_url.into(),
+      storage_path: None,
+      allow_from: vec![],
+      proxy: None,
+      timeout: None,
+    }
+  }
+
+  /// Create option from environment variable, variable name for base url is __ACMEDNS_BASE_URL__,
+  /// __ACME_DNS_API_BASE__ or __EASY_ACME_ACMEDNS_BASE_URL__, and for storage path (optional) is
+  /// __ACME_DNS_STORAGE_PATH__ or __EASY_ACME_ACMEDNS_STORAGE_PATH__
+  pub fn new_from_env() -> Result<Self> {
+    let mut option = Self::new(Self::env_base_url()?);
+    if let Ok(path) = Self::env_storage_path() {
+      option = option.storage_path(path);
+    }
+    Ok(option)
+  }
+
+  /// Set path of JSON file to persist registered accounts, default is `None` (in memory only)
+  pub fn storage_path(mut self, path: impl Into<PathBuf>) -> Self {
+    self.storage_path = Some(path.into());
+    self
+  }
+
+  /// Set CIDR ranges allowed to update TXT record of newly registered accounts, for example,
+  /// `192.168.100.1/24`, default is empty (allow all)
+  pub fn allow_from(mut self, cidr: impl Into<String>) -> Self {
+    self.allow_from.push(cidr.into());
+    self
+  }
+
+  /// Set proxy, for example, `https://127.0.0.1:8080`, `socks5://127.0.0.1:9000`, default is `None`
+  pub fn proxy(mut self, proxy: impl Into<String>) -> Self {
+    self.proxy = Some(proxy.into());
+    self
+  }
+
+  /// Set timeout, for example, `Duration::from_secs(5)`, default is `None`
+  pub fn timeout(mut self, timeout: Duration) -> Self {
+    self.timeout = Some(timeout);
+    self
+  }
+}
+
+impl AcmeDnsOption {
+  #[inline]
+  pub(crate) fn env_base_url() -> Result<String> {
+    env_single_var([
+      "ACMEDNS_BASE_URL",
+      "ACME_DNS_API_BASE",
+      "EASY_ACME_ACMEDNS_BASE_URL",
+    ])
+  }
+
+  #[inline]
+  pub(crate) fn env_storage_path() -> Result<String> {
+    env_single_var(["ACME_DNS_STORAGE_PATH", "EASY_ACME_ACMEDNS_STORAGE_PATH"])
+  }
+}
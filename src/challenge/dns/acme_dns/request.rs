@@ -0,0 +1,27 @@
+use serde::Serialize;
+
+/// Request of [`crate::challenge::dns::acme_dns::AcmeDnsClient::register`]
+#[derive(Debug, Default, Serialize)]
+pub struct AcmeDnsRegisterReq<'a> {
+  #[serde(rename = "allowfrom", skip_serializing_if = "<[_]>::is_empty")]
+  allow_from: &'a [String],
+}
+
+impl<'a> AcmeDnsRegisterReq<'a> {
+  pub fn new() -> Self {
+    Self { allow_from: &[] }
+  }
+
+  /// CIDR ranges allowed to update TXT record, for example, `192.168.100.1/24`
+  pub fn allow_from(mut self, allow_from: &'a [String]) -> Self {
+    self.allow_from = allow_from;
+    self
+  }
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct AcmeDnsUpdateReq<'a> {
+  pub(crate) subdomain: &'a str,
+
+  pub(crate) txt: &'a str,
+}
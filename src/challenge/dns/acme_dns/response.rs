@@ -0,0 +1,58 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+  challenge::dns::challenge_name,
+  errors::{PlainTextSnafu, Result},
+};
+
+/// Account returned by [`crate::challenge::dns::acme_dns::AcmeDnsClient::register`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AcmeDnsAccount {
+  pub username: String,
+
+  pub password: String,
+
+  /// Target of CNAME record, for example, `d420c923-bbd7-4056-ab64-c3ca54c9b3cf.auth.acme-dns.io`
+  pub fulldomain: String,
+
+  pub subdomain: String,
+
+  #[serde(rename = "allowfrom", default)]
+  pub allow_from: Vec<String>,
+}
+
+impl AcmeDnsAccount {
+  /// CNAME record should be created for `domain`, as (name, target)
+  pub fn cname_record(&self, domain: &str) -> (String, String) {
+    (challenge_name(domain), self.fulldomain.clone())
+  }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub(crate) enum AcmeDnsRes<T> {
+  Success(T),
+  Failure(FailureData),
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct UpdateData {
+  pub(crate) txt: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct FailureData {
+  error: String,
+}
+
+impl<T> AcmeDnsRes<T> {
+  pub fn unwrap_data(self) -> Result<T> {
+    match self {
+      AcmeDnsRes::Success(data) => Ok(data),
+      AcmeDnsRes::Failure(failure) => PlainTextSnafu {
+        message: format!("AcmeDns Error: {}", failure.error),
+      }
+      .fail(),
+    }
+  }
+}
@@ -0,0 +1,99 @@
+use std::{
+  collections::HashMap,
+  fs,
+  path::{Path, PathBuf},
+  sync::Mutex,
+};
+
+use snafu::ResultExt;
+
+use crate::{
+  challenge::dns::acme_dns::AcmeDnsAccount,
+  errors::{IoOperationSnafu, Result, SerializeJsonSnafu},
+};
+
+/// Accounts of domains registered on acme-dns server, persisted as JSON object keyed by domain,
+/// which is the same format as lego's `ACME_DNS_STORAGE_PATH`
+#[derive(Debug, Default)]
+pub struct AcmeDnsStorage {
+  path: Option<PathBuf>,
+  accounts: Mutex<HashMap<String, AcmeDnsAccount>>,
+}
+
+impl AcmeDnsStorage {
+  /// Create storage which only keeps accounts in memory
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Load accounts from JSON file, the file is created on first insertion if it does not exist
+  pub fn load(path: impl Into<PathBuf>) -> Result<Self> {
+    let path = path.into();
+    let accounts = if path.exists() {
+      let content = fs::read_to_string(&path).context(IoOperationSnafu)?;
+      serde_json::from_str(&content).context(SerializeJsonSnafu)?
+    } else {
+      HashMap::new()
+    };
+    Ok(AcmeDnsStorage {
+      path: Some(path),
+      accounts: Mutex::new(accounts),
+    })
+  }
+
+  /// Account registered for `domain`, for example, `example.com`
+  pub fn get(&self, domain: &str) -> Option<AcmeDnsAccount> {
+    self.lock().get(&normalize(domain)).cloned()
+  }
+
+  /// Account whose `fulldomain` is `fqdn`
+  pub fn find_by_fulldomain(&self, fqdn: &str) -> Option<AcmeDnsAccount> {
+    let fqdn = normalize(fqdn);
+    self
+      .lock()
+      .values()
+      .find(|a| normalize(&a.fulldomain) == fqdn)
+      .cloned()
+  }
+
+  /// All accounts, as (domain, account)
+  pub fn accounts(&self) -> Vec<(String, AcmeDnsAccount)> {
+    self
+      .lock()
+      .iter()
+      .map(|(domain, account)| (domain.clone(), account.clone()))
+      .collect()
+  }
+
+  /// Save account of `domain`, and write all accounts to file if storage has path
+  pub fn insert(&self, domain: &str, account: AcmeDnsAccount) -> Result<()> {
+    let mut accounts = self.lock();
+    accounts.insert(normalize(domain), account);
+    if let Some(path) = &self.path {
+      let content = serde_json::to_string_pretty(&*accounts).context(SerializeJsonSnafu)?;
+      write_private(path, content.as_bytes())?;
+    }
+    Ok(())
+  }
+
+  fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, AcmeDnsAccount>> {
+    self.accounts.lock().unwrap_or_else(|err| err.into_inner())
+  }
+}
+
+fn normalize(domain: &str) -> String {
+  domain.trim_end_matches('.').to_lowercase()
+}
+
+/// Write file readable by owner only, since it contains credentials
+fn write_private(path: &Path, content: &[u8]) -> Result<()> {
+  let mut options = fs::OpenOptions::new();
+  options.write(true).create(true).truncate(true);
+  #[cfg(unix)]
+  {
+    use std::os::unix::fs::OpenOptionsExt;
+    options.mode(0o600);
+  }
+  let mut file = options.open(path).context(IoOperationSnafu)?;
+  std::io::Write::write_all(&mut file, content).context(IoOperationSnafu)
+}
@@ -1,3 +1,6 @@
+use std::sync::Mutex;
+
+use futures_util::future::BoxFuture;
 use hmac::digest::Digest;
 use http::{
   HeaderMap, HeaderValue,
@@ -5,18 +8,25 @@ use http::{
 };
 use jiff::{Zoned, tz::TimeZone};
 use rand::{RngCore, SeedableRng, prelude::SmallRng};
-use serde::Serialize;
+use serde::{Serialize, de::DeserializeOwned};
 use sha2::Sha256;
 use snafu::ResultExt;
 
 use crate::{
-  challenge::dns::aliyun::{
-    option::AliyunClientOption,
-    request::{AliyunCreateRecordReq, AliyunDeleteRecordReq},
-    response::AliyunRes,
+  challenge::dns::{
+    DnsChallengeClient, TxtRecord,
+    aliyun::{
+      option::AliyunClientOption,
+      request::{
+        AliyunCreateRecordReq, AliyunDeleteRecordReq, AliyunListDomainsReq, AliyunListRecordsReq,
+        AliyunUpdateRecordReq,
+      },
+      response::{AliyunRecord, AliyunRes, DomainListData, RecordIdData, RecordListData},
+    },
+    relative_name,
   },
   errors::{ReqwestClientSnafu, Result, SerializeUrlSnafu},
-  util::{sha2_hmac, str_to_header_value},
+  util::{sha2_hmac, str_to_header_value, url_host},
 };
 
 /// Client for aliyun DNS api
@@ -24,8 +34,9 @@ use crate::{
 pub struct AliyunClient {
   access_key: String,
   access_secret: String,
+  endpoint: String,
   http_client: reqwest::Client,
-  random: SmallRng,
+  random: Mutex<SmallRng>,
 }
 
 const DEFAULT_SEED: u64 = 0;
@@ -41,8 +52,9 @@ impl AliyunClient {
     AliyunClient {
       access_key: key.into(),
       access_secret: secret.into(),
+      endpoint: DNS_ENDPOINT.to_string(),
       http_client: reqwest::Client::new(),
-      random: SmallRng::seed_from_u64(DEFAULT_SEED),
+      random: Mutex::new(SmallRng::seed_from_u64(DEFAULT_SEED)),
     }
   }
 
@@ -53,8 +65,9 @@ impl AliyunClient {
     let client = AliyunClient {
       access_key: AliyunClientOption::env_access_key()?,
       access_secret: AliyunClientOption::env_access_secret()?,
+      endpoint: DNS_ENDPOINT.to_string(),
       http_client: reqwest::Client::new(),
-      random: SmallRng::seed_from_u64(DEFAULT_SEED),
+      random: Mutex::new(SmallRng::seed_from_u64(DEFAULT_SEED)),
     };
     Ok(client)
   }
@@ -67,6 +80,7 @@ impl AliyunClient {
       proxy,
       timeout,
       seed,
+      endpoint,
     } = option;
     let mut client = reqwest::ClientBuilder::new();
     if let Some(timeout) = timeout {
@@ -80,8 +94,9 @@ impl AliyunClient {
     let client = AliyunClient {
       access_key,
       access_secret,
+      endpoint: endpoint.unwrap_or_else(|| DNS_ENDPOINT.to_string()),
       http_client: client,
-      random: SmallRng::seed_from_u64(seed.unwrap_or(DEFAULT_SEED)),
+      random: Mutex::new(SmallRng::seed_from_u64(seed.unwrap_or(DEFAULT_SEED))),
     };
     Ok(client)
   }
@@ -89,21 +104,102 @@ impl AliyunClient {
   /// Create DNS TXT record
   ///
   /// `return`: record id or error
-  pub async fn create_record(&mut self, req: AliyunCreateRecordReq<'_>) -> Result<String> {
-    self.exec_request("AddDomainRecord", req).await
+  pub async fn create_record(&self, req: AliyunCreateRecordReq<'_>) -> Result<String> {
+    let data: RecordIdData = self.exec_request("AddDomainRecord", req).await?;
+    Ok(data.record_id)
   }
 
   /// Delete DNS TXT record
   ///
   /// `return`: record id or error
-  pub async fn delete_record(&mut self, req: AliyunDeleteRecordReq<'_>) -> Result<String> {
-    self.exec_request("DeleteDomainRecord", req).await
+  pub async fn delete_record(&self, req: AliyunDeleteRecordReq<'_>) -> Result<String> {
+    let data: RecordIdData = self.exec_request("DeleteDomainRecord", req).await?;
+    Ok(data.record_id)
+  }
+
+  /// Update value (and other fields) of an existing DNS TXT record in place
+  ///
+  /// `return`: record id or error
+  pub async fn update_record(&self, req: AliyunUpdateRecordReq<'_>) -> Result<String> {
+    let data: RecordIdData = self.exec_request("UpdateDomainRecord", req).await?;
+    Ok(data.record_id)
+  }
+
+  /// List DNS TXT records of a sub domain
+  ///
+  /// `return`: records or error
+  pub async fn list_records(&self, req: AliyunListRecordsReq<'_>) -> Result<Vec<AliyunRecord>> {
+    let data: RecordListData = self.exec_request("DescribeSubDomainRecords", req).await?;
+    Ok(data.records.record)
+  }
+
+  /// Append value to the TXT record set of `req`'s name. If the same value already exists, the
+  /// existing record is reused instead of creating a duplicate one (which aliyun rejects).
+  ///
+  /// `return`: record id or error
+  pub async fn append_record(&self, req: AliyunCreateRecordReq<'_>) -> Result<String> {
+    let sub_domain = if req.rr == "@" {
+      req.domain.to_string()
+    } else {
+      format!("{}.{}", req.rr, req.domain)
+    };
+    let list_req = AliyunListRecordsReq::new(&sub_domain)
+      .domain(req.domain)
+      .page_size(500);
+    let records = self.list_records(list_req).await?;
+    if let Some(record) = records.into_iter().find(|r| r.value == req.value) {
+      return Ok(record.record_id);
+    }
+    self.create_record(req).await
+  }
+
+  /// List domains hosted in aliyun DNS
+  ///
+  /// `return`: domain names or error
+  pub async fn list_domains(&self, req: AliyunListDomainsReq<'_>) -> Result<Vec<String>> {
+    let data: DomainListData = self.exec_request("DescribeDomains", req).await?;
+    let domains = data.domains.domain.into_iter().map(|d| d.domain).collect();
+    Ok(domains)
+  }
+}
+
+impl DnsChallengeClient for AliyunClient {
+  fn has_zone<'a>(&'a self, zone: &'a str) -> BoxFuture<'a, Result<bool>> {
+    Box::pin(async move {
+      let req = AliyunListDomainsReq::new(zone);
+      let domains = self.list_domains(req).await?;
+      Ok(domains.iter().any(|d| d.eq_ignore_ascii_case(zone)))
+    })
+  }
+
+  fn create_txt<'a>(
+    &'a self,
+    zone: &'a str,
+    fqdn: &'a str,
+    value: &'a str,
+  ) -> BoxFuture<'a, Result<TxtRecord>> {
+    Box::pin(async move {
+      let req = AliyunCreateRecordReq::new(zone, relative_name(fqdn, zone), value);
+      let record_id = self.append_record(req).await?;
+      Ok(TxtRecord::new(zone, fqdn, value, record_id))
+    })
+  }
+
+  fn delete_txt<'a>(&'a self, record: &'a TxtRecord) -> BoxFuture<'a, Result<()>> {
+    Box::pin(async move {
+      self
+        .delete_record(AliyunDeleteRecordReq::new(&record.id))
+        .await?;
+      Ok(())
+    })
   }
 }
+
 impl AliyunClient {
-  async fn exec_request<T>(&mut self, action: &str, req: T) -> Result<String>
+  async fn exec_request<T, R>(&self, action: &str, req: T) -> Result<R>
   where
     T: Serialize,
+    R: DeserializeOwned,
   {
     let query = serde_urlencoded::to_string(&req).context(SerializeUrlSnafu)?;
     let headers = self.create_headers(action)?;
@@ -116,7 +212,7 @@ impl AliyunClient {
       "ACS3-HMAC-SHA256 Credential={},SignedHeaders={},Signature={}",
       self.access_key, NAME_TO_SIGN, signature
     );
-    let endpoint = format!("{}?{}", DNS_ENDPOINT, query);
+    let endpoint = format!("{}?{}", self.endpoint, query);
     let res = self
       .http_client
       .get(endpoint)
@@ -125,14 +221,14 @@ impl AliyunClient {
       .send()
       .await
       .context(ReqwestClientSnafu)?
-      .json::<AliyunRes>()
+      .json::<AliyunRes<R>>()
       .await
       .context(ReqwestClientSnafu)?
       .unwrap_data()?;
     Ok(res)
   }
 
-  fn create_headers(&mut self, action: &str) -> Result<HeaderMap> {
+  fn create_headers(&self, action: &str) -> Result<HeaderMap> {
     let action_value = str_to_header_value(action)?;
     let hash_value = str_to_header_value(EMPTY_SHA2)?;
     let datetime = Zoned::now()
@@ -140,11 +236,15 @@ impl AliyunClient {
       .strftime("%Y-%m-%dT%H:%M:%SZ")
       .to_string();
     let date_value = str_to_header_value(&datetime)?;
-    let nonce = self.random.next_u64();
+    let nonce = self
+      .random
+      .lock()
+      .unwrap_or_else(|err| err.into_inner())
+      .next_u64();
     let nonce_value = str_to_header_value(&nonce.to_string())?;
 
     let mut headers = HeaderMap::new();
-    headers.insert(HOST, HeaderValue::from_static("alidns.aliyuncs.com"));
+    headers.insert(HOST, str_to_header_value(url_host(&self.endpoint))?);
     headers.insert("x-acs-action", action_value);
     headers.insert("x-acs-content-sha256", hash_value);
     headers.insert("x-acs-date", date_value);
@@ -171,3 +271,93 @@ impl AliyunClient {
     hex::encode(hasher.finalize().as_slice())
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use std::sync::Arc;
+
+  use super::*;
+  use crate::test_util::{StubResponse, stub_server};
+
+  /// Stub aliyun DNS api, `_acme-challenge.example.com` has TXT record `r1` with value `old`,
+  /// actions and queries of requests are recorded
+  async fn stub_aliyun(requests: Arc<Mutex<Vec<String>>>) -> String {
+    stub_server(move |req| {
+      assert!(req.contains("authorization: ACS3-HMAC-SHA256 Credential=key,"));
+      let action = req
+        .lines()
+        .find_map(|l| l.strip_prefix("x-acs-action: "))
+        .unwrap();
+      let query = req.split(' ').nth(1).unwrap();
+      requests
+        .lock()
+        .unwrap()
+        .push(format!("{} {}", action, query));
+      let body = match action {
+        "DescribeSubDomainRecords" => {
+          r#"{"RequestId":"1","TotalCount":1,"DomainRecords":{"Record":[{"RecordId":"r1","DomainName":"example.com","RR":"_acme-challenge","Type":"TXT","Value":"old","TTL":600}]}}"#
+        }
+        "AddDomainRecord" => r#"{"RequestId":"2","RecordId":"r2"}"#,
+        "UpdateDomainRecord" if query.contains("RecordId=r1") => {
+          r#"{"RequestId":"3","RecordId":"r1"}"#
+        }
+        _ => {
+          r#"{"RequestId":"4","Code":"DomainRecordNotBelongToUser","Message":"The DNS record does not belong to you."}"#
+        }
+      };
+      StubResponse::ok(body)
+    })
+    .await
+  }
+
+  fn client(endpoint: &str) -> AliyunClient {
+    AliyunClient::new_with_option(AliyunClientOption::new("key", "secret").endpoint(endpoint))
+      .unwrap()
+  }
+
+  #[tokio::test]
+  async fn append_keeps_existing_values() {
+    let requests = Arc::new(Mutex::new(vec![]));
+    let client = client(&stub_aliyun(requests.clone()).await);
+
+    let req = AliyunCreateRecordReq::new("example.com", "_acme-challenge", "old");
+    assert_eq!(client.append_record(req).await.unwrap(), "r1");
+    let req = AliyunCreateRecordReq::new("example.com", "_acme-challenge", "new");
+    assert_eq!(client.append_record(req).await.unwrap(), "r2");
+
+    let requests = requests.lock().unwrap();
+    let actions: Vec<&str> = requests
+      .iter()
+      .map(|r| r.split(' ').next().unwrap())
+      .collect();
+    assert_eq!(
+      actions,
+      [
+        "DescribeSubDomainRecords",
+        "DescribeSubDomainRecords",
+        "AddDomainRecord"
+      ]
+    );
+    assert!(requests[0].contains("SubDomain=_acme-challenge.example.com"));
+    assert!(requests[0].contains("DomainName=example.com"));
+    assert!(requests[2].contains("RR=_acme-challenge"));
+    assert!(requests[2].contains("Value=new"));
+  }
+
+  #[tokio::test]
+  async fn update_record_in_place() {
+    let requests = Arc::new(Mutex::new(vec![]));
+    let client = client(&stub_aliyun(requests.clone()).await);
+
+    let req = AliyunUpdateRecordReq::new("r1", "_acme-challenge", "new").ttl(600);
+    assert_eq!(client.update_record(req).await.unwrap(), "r1");
+    assert_eq!(
+      requests.lock().unwrap()[0],
+      "UpdateDomainRecord /?RR=_acme-challenge&RecordId=r1&TTL=600&Type=TXT&Value=new"
+    );
+
+    let req = AliyunUpdateRecordReq::new("r9", "_acme-challenge", "new");
+    let err = client.update_record(req).await.unwrap_err().to_string();
+    assert!(err.contains("Aliyun Error: code: DomainRecordNotBelongToUser"));
+  }
+}
@@ -9,6 +9,10 @@ mod option;
 pub use option::AliyunClientOption;
 
 mod request;
-pub use request::{AliyunCreateRecordReq, AliyunDeleteRecordReq};
+pub use request::{
+  AliyunCreateRecordReq, AliyunDeleteRecordReq, AliyunListDomainsReq, AliyunListRecordsReq,
+  AliyunUpdateRecordReq,
+};
 
 mod response;
+pub use response::AliyunRecord;
@@ -12,6 +12,7 @@ pub struct AliyunClientOption {
   pub(crate) proxy: Option<String>,
   pub(crate) timeout: Option<Duration>,
   pub(crate) seed: Option<u64>,
+  pub(crate) endpoint: Option<String>,
 }
 
 impl AliyunClientOption {
@@ -23,6 +24,7 @@ impl AliyunClientOption {
       proxy: None,
       timeout: None,
       seed: None,
+      endpoint: None,
     }
   }
 
@@ -52,6 +54,12 @@ impl AliyunClientOption {
     self.seed = Some(seed);
     self
   }
+
+  /// Set api endpoint, default is `https://alidns.aliyuncs.com/`
+  pub fn endpoint(mut self, endpoint: impl Into<String>) -> Self {
+    self.endpoint = Some(endpoint.into());
+    self
+  }
 }
 
 impl AliyunClientOption {
@@ -6,7 +6,7 @@ use crate::challenge::dns::RecordType;
 #[derive(Debug, Serialize)]
 pub struct AliyunCreateRecordReq<'a> {
   #[serde(rename = "DomainName")]
-  domain: &'a str,
+  pub(crate) domain: &'a str,
 
   #[serde(rename = "Lang", skip_serializing_if = "Option::is_none")]
   lang: Option<&'a str>,
@@ -18,7 +18,7 @@ pub struct AliyunCreateRecordReq<'a> {
   priority: Option<i64>,
 
   #[serde(rename = "RR")]
-  rr: &'a str,
+  pub(crate) rr: &'a str,
 
   #[serde(rename = "TTL", skip_serializing_if = "Option::is_none")]
   ttl: Option<i64>,
@@ -27,7 +27,7 @@ pub struct AliyunCreateRecordReq<'a> {
   rtype: RecordType,
 
   #[serde(rename = "Value")]
-  value: &'a str,
+  pub(crate) value: &'a str,
 }
 
 impl<'a> AliyunCreateRecordReq<'a> {
@@ -88,3 +88,158 @@ impl<'a> AliyunDeleteRecordReq<'a> {
     self
   }
 }
+
+/// Request of [`crate::challenge::dns::AliyunClient::update_record`]
+#[derive(Debug, Serialize)]
+pub struct AliyunUpdateRecordReq<'a> {
+  #[serde(rename = "Lang", skip_serializing_if = "Option::is_none")]
+  lang: Option<&'a str>,
+
+  #[serde(rename = "Line", skip_serializing_if = "Option::is_none")]
+  line: Option<&'a str>,
+
+  #[serde(rename = "Priority", skip_serializing_if = "Option::is_none")]
+  priority: Option<i64>,
+
+  #[serde(rename = "RR")]
+  rr: &'a str,
+
+  #[serde(rename = "RecordId")]
+  record_id: &'a str,
+
+  #[serde(rename = "TTL", skip_serializing_if = "Option::is_none")]
+  ttl: Option<i64>,
+
+  #[serde(rename = "Type")]
+  rtype: RecordType,
+
+  #[serde(rename = "Value")]
+  value: &'a str,
+}
+
+impl<'a> AliyunUpdateRecordReq<'a> {
+  pub fn new(record_id: &'a str, rr: &'a str, value: &'a str) -> Self {
+    AliyunUpdateRecordReq {
+      record_id,
+      rr,
+      value,
+      rtype: RecordType::TXT,
+      lang: None,
+      ttl: None,
+      priority: None,
+      line: None,
+    }
+  }
+
+  pub fn lang(mut self, lang: &'a str) -> Self {
+    self.lang = Some(lang);
+    self
+  }
+
+  pub fn ttl(mut self, ttl: i64) -> Self {
+    self.ttl = Some(ttl);
+    self
+  }
+
+  pub fn priority(mut self, priority: i64) -> Self {
+    self.priority = Some(priority);
+    self
+  }
+
+  pub fn line(mut self, line: &'a str) -> Self {
+    self.line = Some(line);
+    self
+  }
+}
+
+/// Request of [`crate::challenge::dns::AliyunClient::list_records`]
+#[derive(Debug, Serialize)]
+pub struct AliyunListRecordsReq<'a> {
+  #[serde(rename = "DomainName", skip_serializing_if = "Option::is_none")]
+  domain: Option<&'a str>,
+
+  #[serde(rename = "Lang", skip_serializing_if = "Option::is_none")]
+  lang: Option<&'a str>,
+
+  #[serde(rename = "PageSize", skip_serializing_if = "Option::is_none")]
+  page_size: Option<i64>,
+
+  #[serde(rename = "SubDomain")]
+  sub_domain: &'a str,
+
+  #[serde(rename = "Type")]
+  rtype: RecordType,
+}
+
+impl<'a> AliyunListRecordsReq<'a> {
+  /// `sub_domain` is the full name of record, for example, `_acme-challenge.example.com`
+  pub fn new(sub_domain: &'a str) -> Self {
+    AliyunListRecordsReq {
+      sub_domain,
+      domain: None,
+      lang: None,
+      page_size: None,
+      rtype: RecordType::TXT,
+    }
+  }
+
+  pub fn domain(mut self, domain: &'a str) -> Self {
+    self.domain = Some(domain);
+    self
+  }
+
+  pub fn lang(mut self, lang: &'a str) -> Self {
+    self.lang = Some(lang);
+    self
+  }
+
+  /// Set page size, max is 500, default is 20
+  pub fn page_size(mut self, page_size: i64) -> Self {
+    self.page_size = Some(page_size);
+    self
+  }
+}
+
+/// Request of [`crate::challenge::dns::AliyunClient::list_domains`]
+#[derive(Debug, Serialize)]
+pub struct AliyunListDomainsReq<'a> {
+  #[serde(rename = "KeyWord")]
+  keyword: &'a str,
+
+  #[serde(rename = "Lang", skip_serializing_if = "Option::is_none")]
+  lang: Option<&'a str>,
+
+  #[serde(rename = "PageSize", skip_serializing_if = "Option::is_none")]
+  page_size: Option<i64>,
+
+  #[serde(rename = "SearchMode")]
+  search_mode: &'a str,
+}
+
+impl<'a> AliyunListDomainsReq<'a> {
+  /// Search domains equal to `keyword`, use [`Self::fuzzy`] to search domains containing it
+  pub fn new(keyword: &'a str) -> Self {
+    AliyunListDomainsReq {
+      keyword,
+      lang: None,
+      page_size: None,
+      search_mode: "EXACT",
+    }
+  }
+
+  pub fn fuzzy(mut self) -> Self {
+    self.search_mode = "LIKE";
+    self
+  }
+
+  pub fn lang(mut self, lang: &'a str) -> Self {
+    self.lang = Some(lang);
+    self
+  }
+
+  /// Set page size, max is 100, default is 20
+  pub fn page_size(mut self, page_size: i64) -> Self {
+    self.page_size = Some(page_size);
+    self
+  }
+}
@@ -3,26 +3,78 @@ use serde::Deserialize;
 use crate::errors::{PlainTextSnafu, Result};
 
 #[derive(Debug, Deserialize)]
-pub(crate) struct AliyunRes {
+pub(crate) struct AliyunRes<T> {
   #[allow(dead_code)]
   #[serde(rename = "RequestId")]
   request_id: String,
 
   #[serde(flatten)]
-  data: AliyunData,
+  data: AliyunData<T>,
 }
 
 #[derive(Debug, Deserialize)]
 #[serde(untagged)]
-enum AliyunData {
-  Success(SuccessData),
+enum AliyunData<T> {
+  Success(T),
   Failure(FailureData),
 }
 
 #[derive(Debug, Deserialize)]
-struct SuccessData {
+pub(crate) struct RecordIdData {
   #[serde(rename = "RecordId")]
-  record_id: String,
+  pub(crate) record_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct RecordListData {
+  #[serde(rename = "DomainRecords")]
+  pub(crate) records: RecordList,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct RecordList {
+  #[serde(rename = "Record", default)]
+  pub(crate) record: Vec<AliyunRecord>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct DomainListData {
+  #[serde(rename = "Domains")]
+  pub(crate) domains: DomainList,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct DomainList {
+  #[serde(rename = "Domain", default)]
+  pub(crate) domain: Vec<DomainData>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct DomainData {
+  #[serde(rename = "DomainName")]
+  pub(crate) domain: String,
+}
+
+/// DNS record returned by [`crate::challenge::dns::aliyun::AliyunClient::list_records`]
+#[derive(Debug, Clone, Deserialize)]
+pub struct AliyunRecord {
+  #[serde(rename = "RecordId")]
+  pub record_id: String,
+
+  #[serde(rename = "DomainName")]
+  pub domain: String,
+
+  #[serde(rename = "RR")]
+  pub rr: String,
+
+  #[serde(rename = "Type")]
+  pub rtype: String,
+
+  #[serde(rename = "Value")]
+  pub value: String,
+
+  #[serde(rename = "TTL")]
+  pub ttl: Option<i64>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -34,10 +86,10 @@ struct FailureData {
   message: String,
 }
 
-impl AliyunRes {
-  pub fn unwrap_data(self) -> Result<String> {
+impl<T> AliyunRes<T> {
+  pub fn unwrap_data(self) -> Result<T> {
     match self.data {
-      AliyunData::Success(success) => Ok(success.record_id),
+      AliyunData::Success(success) => Ok(success),
       AliyunData::Failure(failure) => PlainTextSnafu {
         message: format!(
           "Aliyun Error: code: {}, message: {}",
@@ -0,0 +1,434 @@
+use std::{
+  sync::Mutex,
+  time::{Duration, Instant},
+};
+
+use futures_util::future::BoxFuture;
+use http::{
+  Method, StatusCode,
+  header::{AUTHORIZATION, IF_MATCH, IF_NONE_MATCH},
+};
+use reqwest::{Client, ClientBuilder, Response};
+use snafu::ResultExt;
+
+use crate::{
+  challenge::dns::{
+    DnsChallengeClient, TxtRecord,
+    azure::{
+      AzureOption, AzureRecordSet,
+      request::RecordSetReq,
+      response::{FailureData, TokenRes},
+    },
+    relative_name,
+  },
+  errors::{PlainTextSnafu, ReqwestClientSnafu, Result},
+};
+
+const API_VERSION: &str = "2018-05-01";
+/// Token is refreshed when it expires within this time
+const REFRESH_MARGIN: Duration = Duration::from_secs(60);
+
+/// Client for Azure DNS api of Azure Resource Manager, authorized by client credential of service
+/// principal
+#[derive(Debug)]
+pub struct AzureClient {
+  tenant_id: String,
+  client_id: String,
+  client_secret: String,
+  subscription_id: String,
+  resource_group: String,
+  authority_host: String,
+  resource_manager: String,
+  ttl: u64,
+  token: Mutex<Option<(String, Instant)>>,
+  client: Client,
+}
+
+impl AzureClient {
+  /// Create client with option, see [`AzureOption`]
+  pub fn new(option: AzureOption) -> Result<Self> {
+    let AzureOption {
+      tenant_id,
+      client_id,
+      client_secret,
+      subscription_id,
+      resource_group,
+      authority_host,
+      resource_manager,
+      ttl,
+      proxy,
+      timeout,
+    } = option;
+    let mut client = ClientBuilder::new();
+    if let Some(timeout) = timeout {
+      client = client.timeout(timeout);
+    }
+    if let Some(proxy) = proxy {
+      let proxy = reqwest::Proxy::all(proxy).context(ReqwestClientSnafu)?;
+      client = client.proxy(proxy);
+    }
+    let client = client.build().context(ReqwestClientSnafu)?;
+    Ok(Self {
+      tenant_id,
+      client_id,
+      client_secret,
+      subscription_id,
+      resource_group,
+      authority_host: authority_host.trim_end_matches('/').to_string(),
+      resource_manager: resource_manager.trim_end_matches('/').to_string(),
+      ttl,
+      token: Mutex::new(None),
+      client,
+    })
+  }
+
+  /// Create client from environment variable, see [`AzureOption::new_from_env`]
+  pub fn new_from_env() -> Result<Self> {
+    Self::new(AzureOption::new_from_env()?)
+  }
+
+  /// Whether DNS zone `zone` exists in the resource group
+  pub async fn zone_exists(&self, zone: &str) -> Result<bool> {
+    let url = self.zone_url(zone);
+    let res = self.send(Method::GET, &url, None).await?;
+    match res.status() {
+      StatusCode::NOT_FOUND => Ok(false),
+      status if status.is_success() => Ok(true),
+      _ => Self::failure(res).await,
+    }
+  }
+
+  /// TXT record set `name` relative to zone, `None` if it does not exist
+  pub async fn record_set(&self, zone: &str, name: &str) -> Result<Option<AzureRecordSet>> {
+    let url = self.record_set_url(zone, name);
+    let res = self.send(Method::GET, &url, None).await?;
+    match res.status() {
+      StatusCode::NOT_FOUND => Ok(None),
+      status if status.is_success() => {
+        let set = res.json().await.context(ReqwestClientSnafu)?;
+        Ok(Some(set))
+      }
+      _ => Self::failure(res).await,
+    }
+  }
+
+  /// Add `value` to TXT record set `name`, other values are kept. The record set is updated with
+  /// `If-Match` of its etag, so concurrent changes are not overwritten, if the etag is stale the
+  /// record set is read again and the update is retried once
+  pub async fn add_txt(&self, zone: &str, name: &str, value: &str) -> Result<()> {
+    let mut retried = false;
+    loop {
+      let set = self.record_set(zone, name).await?;
+      let (mut values, ttl, etag) = match &set {
+        Some(set) => (
+          set.values(),
+          set.ttl().unwrap_or(self.ttl),
+          set.etag.clone(),
+        ),
+        None => (vec![], self.ttl, None),
+      };
+      if values.iter().any(|v| v == value) {
+        return Ok(());
+      }
+      values.push(value.to_string());
+      let res = self.put_record_set(zone, name, ttl, &values, etag).await?;
+      if res.status() == StatusCode::PRECONDITION_FAILED && !retried {
+        retried = true;
+        continue;
+      }
+      return Self::check(res).await;
+    }
+  }
+
+  /// Remove `value` from TXT record set `name`, the set is deleted if no value is left. Stale etag
+  /// is retried once like [`Self::add_txt`]
+  pub async fn remove_txt(&self, zone: &str, name: &str, value: &str) -> Result<()> {
+    let mut retried = false;
+    loop {
+      let Some(set) = self.record_set(zone, name).await? else {
+        return Ok(());
+      };
+      let values: Vec<String> = set.values().into_iter().filter(|v| v != value).collect();
+      if values.len() == set.values().len() {
+        return Ok(());
+      }
+      let res = if values.is_empty() {
+        let url = self.record_set_url(zone, name);
+        self.send(Method::DELETE, &url, set.etag.as_deref()).await?
+      } else {
+        let ttl = set.ttl().unwrap_or(self.ttl);
+        self
+          .put_record_set(zone, name, ttl, &values, set.etag)
+          .await?
+      };
+      if res.status() == StatusCode::PRECONDITION_FAILED && !retried {
+        retried = true;
+        continue;
+      }
+      return Self::check(res).await;
+    }
+  }
+
+  async fn put_record_set(
+    &self,
+    zone: &str,
+    name: &str,
+    ttl: u64,
+    values: &[String],
+    etag: Option<String>,
+  ) -> Result<Response> {
+    let url = self.record_set_url(zone, name);
+    let body = RecordSetReq::new(ttl, values);
+    let builder = self.authorized(Method::PUT, &url).await?.json(&body);
+    let builder = match etag {
+      Some(etag) => builder.header(IF_MATCH, etag),
+      None => builder.header(IF_NONE_MATCH, "*"),
+    };
+    builder.send().await.context(ReqwestClientSnafu)
+  }
+}
+
+impl DnsChallengeClient for AzureClient {
+  fn has_zone<'a>(&'a self, zone: &'a str) -> BoxFuture<'a, Result<bool>> {
+    Box::pin(async move { self.zone_exists(zone).await })
+  }
+
+  fn create_txt<'a>(
+    &'a self,
+    zone: &'a str,
+    fqdn: &'a str,
+    value: &'a str,
+  ) -> BoxFuture<'a, Result<TxtRecord>> {
+    Box::pin(async move {
+      let name = relative_name(fqdn, zone);
+      self.add_txt(zone, name, value).await?;
+      Ok(TxtRecord::new(zone, fqdn, value, name))
+    })
+  }
+
+  fn delete_txt<'a>(&'a self, record: &'a TxtRecord) -> BoxFuture<'a, Result<()>> {
+    Box::pin(async move {
+      self
+        .remove_txt(&record.zone, &record.id, &record.value)
+        .await
+    })
+  }
+}
+
+impl AzureClient {
+  fn zone_url(&self, zone: &str) -> String {
+    format!(
+      "{}/subscriptions/{}/resourceGroups/{}/providers/Microsoft.Network/dnsZones/{}?api-version={}",
+      self.resource_manager,
+      self.subscription_id,
+      self.resource_group,
+      zone.trim_end_matches('.'),
+      API_VERSION
+    )
+  }
+
+  fn record_set_url(&self, zone: &str, name: &str) -> String {
+    format!(
+      "{}/subscriptions/{}/resourceGroups/{}/providers/Microsoft.Network/dnsZones/{}/TXT/{}?api-version={}",
+      self.resource_manager,
+      self.subscription_id,
+      self.resource_group,
+      zone.trim_end_matches('.'),
+      name,
+      API_VERSION
+    )
+  }
+
+  async fn send(&self, method: Method, url: &str, etag: Option<&str>) -> Result<Response> {
+    let mut builder = self.authorized(method, url).await?;
+    if let Some(etag) = etag {
+      builder = builder.header(IF_MATCH, etag);
+    }
+    builder.send().await.context(ReqwestClientSnafu)
+  }
+
+  async fn authorized(&self, method: Method, url: &str) -> Result<reqwest::RequestBuilder> {
+    let token = self.access_token().await?;
+    let builder = self
+      .client
+      .request(method, url)
+      .header(AUTHORIZATION, format!("Bearer {}", token));
+    Ok(builder)
+  }
+
+  /// Cached access token, or a new one by client credential flow if it is about to expire
+  async fn access_token(&self) -> Result<String> {
+    if let Some((token, expires_at)) = &*self.lock_token()
+      && Instant::now() + REFRESH_MARGIN < *expires_at
+    {
+      return Ok(token.clone());
+    }
+    let url = format!(
+      "{}/{}/oauth2/v2.0/token",
+      self.authority_host, self.tenant_id
+    );
+    let scope = format!("{}/.default", self.resource_manager);
+    let form = [
+      ("grant_type", "client_credentials"),
+      ("client_id", &self.client_id),
+      ("client_secret", &self.client_secret),
+      ("scope", &scope),
+    ];
+    let res = self
+      .client
+      .post(url)
+      .form(&form)
+      .send()
+      .await
+      .context(ReqwestClientSnafu)?
+      .json::<TokenRes>()
+      .await
+      .context(ReqwestClientSnafu)?;
+    match res {
+      TokenRes::Success {
+        access_token,
+        expires_in,
+      } => {
+        let expires_at = Instant::now() + Duration::from_secs(expires_in);
+        *self.lock_token() = Some((access_token.clone(), expires_at));
+        Ok(access_token)
+      }
+      TokenRes::Failure {
+        error,
+        error_description,
+      } => PlainTextSnafu {
+        message: format!(
+          "Azure Error: token request failed, {}, {}",
+          error,
+          error_description.unwrap_or_default()
+        ),
+      }
+      .fail(),
+    }
+  }
+
+  fn lock_token(&self) -> std::sync::MutexGuard<'_, Option<(String, Instant)>> {
+    self.token.lock().unwrap_or_else(|err| err.into_inner())
+  }
+
+  async fn check(res: Response) -> Result<()> {
+    if res.status().is_success() {
+      return Ok(());
+    }
+    Self::failure(res).await
+  }
+
+  async fn failure<T>(res: Response) -> Result<T> {
+    let status = res.status();
+    let text = res.text().await.context(ReqwestClientSnafu)?;
+    let message = match serde_json::from_str::<FailureData>(&text) {
+      Ok(failure) => format!("{}, {}", failure.error.code, failure.error.message),
+      Err(_) => text,
+    };
+    PlainTextSnafu {
+      message: format!("Azure Error: {}, {}", status, message),
+    }
+    .fail()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::sync::Arc;
+
+  use super::*;
+  use crate::test_util::{StubResponse, request_body, stub_server};
+
+  /// Stub Azure AD and ARM, zone `example.com` has TXT record set `_acme-challenge` with value `old`
+  async fn stub_azure() -> String {
+    stub_server(|req| {
+      let zone = "GET /subscriptions/sub/resourceGroups/rg/providers/Microsoft.Network/dnsZones";
+      if req.starts_with("POST /tenant/oauth2/v2.0/token") {
+        assert!(req.contains("grant_type=client_credentials"));
+        StubResponse::ok(r#"{"access_token":"aad-token","expires_in":3600}"#)
+      } else if !req.contains("authorization: Bearer aad-token") {
+        let body = r#"{"error":{"code":"AuthenticationFailed","message":"no token"}}"#;
+        StubResponse::new("401 Unauthorized", body)
+      } else if req.starts_with(&format!("{}/example.com?", zone)) {
+        StubResponse::ok(r#"{"id":"zone","name":"example.com"}"#)
+      } else if req.starts_with(&format!("{}/example.com/TXT/_acme-challenge?", zone)) {
+        StubResponse::ok(
+          r#"{"id":"set","name":"_acme-challenge","etag":"e1","properties":{"TTL":60,"TXTRecords":[{"value":["old"]}]}}"#,
+        )
+      } else if req.starts_with("PUT ")
+        && req.contains("if-match: e1")
+        && req.contains(r#""TXTRecords":[{"value":["old"]},{"value":["token"]}]"#)
+      {
+        StubResponse::ok("{}")
+      } else {
+        let body = r#"{"error":{"code":"ResourceNotFound","message":"not found"}}"#;
+        StubResponse::new("404 Not Found", body)
+      }
+    })
+    .await
+  }
+
+  #[tokio::test]
+  async fn add_value_to_existing_set() {
+    let endpoint = stub_azure().await;
+    let option = AzureOption::new("tenant", "client", "secret", "sub", "rg")
+      .authority_host(&endpoint)
+      .resource_manager(&endpoint);
+    let client = AzureClient::new(option).unwrap();
+    assert!(client.has_zone("example.com").await.unwrap());
+    assert!(!client.has_zone("example.org").await.unwrap());
+    let record = client
+      .create_txt("example.com", "_acme-challenge.example.com", "token")
+      .await
+      .unwrap();
+    assert_eq!(record.id, "_acme-challenge");
+  }
+
+  #[tokio::test]
+  async fn retry_stale_etag_once() {
+    let puts = Arc::new(Mutex::new(vec![]));
+    let recorded = puts.clone();
+    let mut reads = 0;
+    let endpoint = stub_server(move |req| {
+      if req.starts_with("POST /tenant/oauth2/v2.0/token") {
+        return StubResponse::ok(r#"{"access_token":"aad-token","expires_in":3600}"#);
+      }
+      if req.starts_with("GET ") {
+        // another value is added concurrently after the first read
+        reads += 1;
+        let values = match reads {
+          1 => r#"[{"value":["old"]}]"#,
+          _ => r#"[{"value":["old"]},{"value":["other"]}]"#,
+        };
+        let body = format!(
+          r#"{{"id":"set","name":"_acme-challenge","etag":"e{}","properties":{{"TTL":60,"TXTRecords":{}}}}}"#,
+          reads, values
+        );
+        return StubResponse::ok(body);
+      }
+      recorded.lock().unwrap().push(request_body(req).to_string());
+      if req.contains(&format!("if-match: e{}", reads)) && reads > 1 {
+        StubResponse::ok("{}")
+      } else {
+        let body = r#"{"error":{"code":"PreconditionFailed","message":"etag mismatch"}}"#;
+        StubResponse::new("412 Precondition Failed", body)
+      }
+    })
+    .await;
+    let option = AzureOption::new("tenant", "client", "secret", "sub", "rg")
+      .authority_host(&endpoint)
+      .resource_manager(&endpoint);
+    let client = AzureClient::new(option).unwrap();
+    client
+      .add_txt("example.com", "_acme-challenge", "token")
+      .await
+      .unwrap();
+
+    let puts = puts.lock().unwrap();
+    assert_eq!(puts.len(), 2);
+    assert!(
+      puts[1]
+        .contains(r#""TXTRecords":[{"value":["old"]},{"value":["other"]},{"value":["token"]}]"#)
+    );
+  }
+}
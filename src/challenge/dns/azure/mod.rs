@@ -0,0 +1,14 @@
+//! Implement of [Azure DNS](https://azure.microsoft.com/products/dns) challenge
+//!
+//! Link: <https://learn.microsoft.com/rest/api/dns/record-sets>
+
+mod client;
+pub use client::AzureClient;
+
+mod option;
+pub use option::AzureOption;
+
+mod request;
+
+mod response;
+pub use response::AzureRecordSet;
@@ -0,0 +1,125 @@
+use std::time::Duration;
+
+use crate::{errors::Result, util::env_single_var};
+
+/// Options for create an [`crate::challenge::dns::azure::AzureClient`] instance
+#[derive(Debug)]
+pub struct AzureOption {
+  pub(crate) tenant_id: String,
+  pub(crate) client_id: String,
+  pub(crate) client_secret: String,
+  pub(crate) subscription_id: String,
+  pub(crate) resource_group: String,
+  pub(crate) authority_host: String,
+  pub(crate) resource_manager: String,
+  pub(crate) ttl: u64,
+  pub(crate) proxy: Option<String>,
+  pub(crate) timeout: Option<Duration>,
+}
+
+impl AzureOption {
+  /// Create option with client credential of service principal, the principal must have role
+  /// __DNS Zone Contributor__ on zones in `resource_group`
+  pub fn new(
+    tenant_id: impl Into<String>,
+    client_id: impl Into<String>,
+    client_secret: impl Into<String>,
+    subscription_id: impl Into<String>,
+    resource_group: impl Into<String>,
+  ) -> Self {
+    AzureOption {
+      tenant_id: tenant_id.into(),
+      client_id: client_id.into(),
+      client_secret: client_secret.into(),
+      subscription_id: subscription_id.into(),
+      resource_group: resource_group.into(),
+      authority_host: "https://login.microsoftonline.com".to_string(),
+      resource_manager: "https://management.azure.com".to_string(),
+      ttl: 60,
+      proxy: None,
+      timeout: None,
+    }
+  }
+
+  /// Create option from environment variable, variable names are __AZURE_TENANT_ID__,
+  /// __AZURE_CLIENT_ID__, __AZURE_CLIENT_SECRET__, __AZURE_SUBSCRIPTION_ID__,
+  /// __AZURE_RESOURCE_GROUP__ and optional __AZURE_AUTHORITY_HOST__, with __EASY_ACME_AZURE___
+  /// prefixed alternatives, for example, __EASY_ACME_AZURE_TENANT_ID__
+  pub fn new_from_env() -> Result<Self> {
+    let option = Self::new(
+      Self::env_tenant_id()?,
+      Self::env_client_id()?,
+      Self::env_client_secret()?,
+      Self::env_subscription_id()?,
+      Self::env_resource_group()?,
+    );
+    match Self::env_authority_host() {
+      Ok(host) => Ok(option.authority_host(host)),
+      Err(_) => Ok(option),
+    }
+  }
+
+  /// Set Azure AD authority host, default is `https://login.microsoftonline.com`, for example,
+  /// `https://login.chinacloudapi.cn` of Azure China
+  pub fn authority_host(mut self, host: impl Into<String>) -> Self {
+    self.authority_host = host.into();
+    self
+  }
+
+  /// Set Azure Resource Manager endpoint, default is `https://management.azure.com`, for example,
+  /// `https://management.chinacloudapi.cn` of Azure China
+  pub fn resource_manager(mut self, endpoint: impl Into<String>) -> Self {
+    self.resource_manager = endpoint.into();
+    self
+  }
+
+  /// Set TTL of TXT record set, default is 60 seconds
+  pub fn ttl(mut self, ttl: u64) -> Self {
+    self.ttl = ttl;
+    self
+  }
+
+  /// Set proxy, for example, `https://127.0.0.1:8080`, `socks5://127.0.0.1:9000`, default is `None`
+  pub fn proxy(mut self, proxy: impl Into<String>) -> Self {
+    self.proxy = Some(proxy.into());
+    self
+  }
+
+  /// Set timeout, for example, `Duration::from_secs(5)`, default is `None`
+  pub fn timeout(mut self, timeout: Duration) -> Self {
+    self.timeout = Some(timeout);
+    self
+  }
+}
+
+impl AzureOption {
+  #[inline]
+  pub(crate) fn env_tenant_id() -> Result<String> {
+    env_single_var(["AZURE_TENANT_ID", "EASY_ACME_AZURE_TENANT_ID"])
+  }
+
+  #[inline]
+  pub(crate) fn env_client_id() -> Result<String> {
+    env_single_var(["AZURE_CLIENT_ID", "EASY_ACME_AZURE_CLIENT_ID"])
+  }
+
+  #[inline]
+  pub(crate) fn env_client_secret() -> Result<String> {
+    env_single_var(["AZURE_CLIENT_SECRET", "EASY_ACME_AZURE_CLIENT_SECRET"])
+  }
+
+  #[inline]
+  pub(crate) fn env_subscription_id() -> Result<String> {
+    env_single_var(["AZURE_SUBSCRIPTION_ID", "EASY_ACME_AZURE_SUBSCRIPTION_ID"])
+  }
+
+  #[inline]
+  pub(crate) fn env_resource_group() -> Result<String> {
+    env_single_var(["AZURE_RESOURCE_GROUP", "EASY_ACME_AZURE_RESOURCE_GROUP"])
+  }
+
+  #[inline]
+  pub(crate) fn env_authority_host() -> Result<String> {
+    env_single_var(["AZURE_AUTHORITY_HOST", "EASY_ACME_AZURE_AUTHORITY_HOST"])
+  }
+}
@@ -0,0 +1,34 @@
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+pub(crate) struct RecordSetReq<'a> {
+  pub(crate) properties: RecordSetProperties<'a>,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct RecordSetProperties<'a> {
+  #[serde(rename = "TTL")]
+  pub(crate) ttl: u64,
+
+  #[serde(rename = "TXTRecords")]
+  pub(crate) txt_records: Vec<TxtValue<'a>>,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct TxtValue<'a> {
+  pub(crate) value: [&'a str; 1],
+}
+
+impl<'a> RecordSetReq<'a> {
+  pub(crate) fn new(ttl: u64, values: &'a [String]) -> Self {
+    let txt_records = values
+      .iter()
+      .map(|v| TxtValue {
+        value: [v.as_str()],
+      })
+      .collect();
+    RecordSetReq {
+      properties: RecordSetProperties { ttl, txt_records },
+    }
+  }
+}
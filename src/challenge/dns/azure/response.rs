@@ -0,0 +1,70 @@
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct FailureData {
+  pub(crate) error: ErrorData,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct ErrorData {
+  pub(crate) code: String,
+
+  pub(crate) message: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub(crate) enum TokenRes {
+  Success {
+    access_token: String,
+    expires_in: u64,
+  },
+  Failure {
+    error: String,
+    error_description: Option<String>,
+  },
+}
+
+/// TXT record set returned by [`crate::challenge::dns::azure::AzureClient::record_set`]
+#[derive(Debug, Clone, Deserialize)]
+pub struct AzureRecordSet {
+  pub id: String,
+
+  /// Name relative to zone, for example, `_acme-challenge`
+  pub name: String,
+
+  pub etag: Option<String>,
+
+  properties: RecordSetProperties,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct RecordSetProperties {
+  #[serde(rename = "TTL")]
+  ttl: Option<u64>,
+
+  #[serde(rename = "TXTRecords", default)]
+  txt_records: Vec<TxtValue>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct TxtValue {
+  #[serde(default)]
+  value: Vec<String>,
+}
+
+impl AzureRecordSet {
+  pub fn ttl(&self) -> Option<u64> {
+    self.properties.ttl
+  }
+
+  /// TXT values, strings of each value are concatenated
+  pub fn values(&self) -> Vec<String> {
+    self
+      .properties
+      .txt_records
+      .iter()
+      .map(|r| r.value.concat())
+      .collect()
+  }
+}
@@ -1,14 +1,19 @@
+use futures_util::future::BoxFuture;
 use http::{HeaderMap, HeaderName, Method, header::AUTHORIZATION};
 use reqwest::{Client, ClientBuilder};
-use serde::Serialize;
+use serde::{Serialize, de::DeserializeOwned};
 use snafu::ResultExt;
 
 use crate::{
-  challenge::dns::cloudflare::{
-    CloudflareCreateRecordReq, CloudflareDeleteRecordReq, CloudflareOption, CloudflareRes,
-    option::CloudflareAuth,
+  challenge::dns::{
+    DnsChallengeClient, TxtRecord,
+    cloudflare::{
+      CloudflareCreateRecordReq, CloudflareDeleteRecordReq, CloudflareListRecordsReq,
+      CloudflareListZonesReq, CloudflareOption, CloudflareRecord, CloudflareRecordId,
+      CloudflareRes, CloudflareUpdateRecordReq, CloudflareZone, option::CloudflareAuth,
+    },
   },
-  errors::{ReqwestClientSnafu, Result},
+  errors::{PlainTextSnafu, ReqwestClientSnafu, Result},
   util::str_to_header_value,
 };
 
@@ -16,15 +21,19 @@ use crate::{
 pub struct CloudflareClient {
   #[allow(dead_code)]
   auth: CloudflareAuth,
+  endpoint: String,
   client: Client,
 }
 
+const API_ENDPOINT: &str = "https://api.cloudflare.com/client/v4";
+
 impl CloudflareClient {
   pub fn new(option: CloudflareOption) -> Result<Self> {
     let CloudflareOption {
       auth,
       proxy,
       timeout,
+      endpoint,
     } = option;
     let mut client = ClientBuilder::new();
     if let Some(timeout) = timeout {
@@ -36,39 +45,144 @@ impl CloudflareClient {
     }
     client = client.default_headers(auth_headers(&auth)?);
     let client = client.build().context(ReqwestClientSnafu)?;
-    Ok(Self { auth, client })
+    let endpoint = endpoint.unwrap_or_else(|| API_ENDPOINT.to_string());
+    Ok(Self {
+      auth,
+      endpoint,
+      client,
+    })
   }
 
   pub async fn create_record(&self, req: CloudflareCreateRecordReq<'_>) -> Result<String> {
+    let url = format!("{}/zones/{}/dns_records", self.endpoint, req.zone_id);
+    let data: CloudflareRecordId = self.exec_request(Method::POST, &url, &req).await?;
+    Ok(data.id)
+  }
+
+  pub async fn delete_record(&self, req: CloudflareDeleteRecordReq<'_>) -> Result<String> {
     let url = format!(
-      "https://api.cloudflare.com/client/v4/zones/{}/dns_records",
-      req.zone_id
+      "{}/zones/{}/dns_records/{}",
+      self.endpoint, req.zone_id, req.record_id
     );
-    self.exec_request(Method::POST, &url, &req).await
+    let data: CloudflareRecordId = self.exec_request(Method::DELETE, &url, &req).await?;
+    Ok(data.id)
   }
 
-  pub async fn delete_record(&self, req: CloudflareDeleteRecordReq<'_>) -> Result<String> {
+  /// Update value (and other fields) of an existing DNS TXT record in place
+  pub async fn update_record(&self, req: CloudflareUpdateRecordReq<'_>) -> Result<String> {
     let url = format!(
-      "https://api.cloudflare.com/client/v4/zones/{}/dns_records/{}",
-      req.zone_id, req.record_id
+      "{}/zones/{}/dns_records/{}",
+      self.endpoint, req.zone_id, req.record_id
     );
-    self.exec_request(Method::DELETE, &url, &req).await
+    let data: CloudflareRecordId = self.exec_request(Method::PATCH, &url, &req).await?;
+    Ok(data.id)
+  }
+
+  /// List DNS TXT records of zone
+  pub async fn list_records(
+    &self,
+    req: CloudflareListRecordsReq<'_>,
+  ) -> Result<Vec<CloudflareRecord>> {
+    let url = format!("{}/zones/{}/dns_records", self.endpoint, req.zone_id);
+    self.exec_request(Method::GET, &url, &req).await
+  }
+
+  /// Append value to the TXT record set of `req`'s name. If the same value already exists, the
+  /// existing record is reused instead of creating a duplicate one (which cloudflare rejects).
+  ///
+  /// `name` of `req` must be the full name of record, for example, `_acme-challenge.example.com`
+  pub async fn append_record(&self, req: CloudflareCreateRecordReq<'_>) -> Result<String> {
+    let list_req = CloudflareListRecordsReq::new(req.zone_id)
+      .name(req.name)
+      .per_page(5000);
+    let records = self.list_records(list_req).await?;
+    let existed = records
+      .into_iter()
+      .find(|r| r.content.trim_matches('"') == req.content.trim_matches('"'));
+    if let Some(record) = existed {
+      return Ok(record.id);
+    }
+    self.create_record(req).await
+  }
+
+  /// List zones of account
+  pub async fn list_zones(&self, req: CloudflareListZonesReq<'_>) -> Result<Vec<CloudflareZone>> {
+    let url = format!("{}/zones", self.endpoint);
+    self.exec_request(Method::GET, &url, &req).await
+  }
+
+  /// Find id of zone by domain name, for example, `example.com`
+  pub async fn zone_id(&self, zone: &str) -> Result<Option<String>> {
+    let zones = self
+      .list_zones(CloudflareListZonesReq::new().name(zone))
+      .await?;
+    let zone_id = zones
+      .into_iter()
+      .find(|z| z.name.eq_ignore_ascii_case(zone))
+      .map(|z| z.id);
+    Ok(zone_id)
+  }
+
+  async fn require_zone_id(&self, zone: &str) -> Result<String> {
+    match self.zone_id(zone).await? {
+      Some(zone_id) => Ok(zone_id),
+      None => PlainTextSnafu {
+        message: format!("Cloudflare Error: zone {} not found", zone),
+      }
+      .fail(),
+    }
+  }
+}
+
+impl DnsChallengeClient for CloudflareClient {
+  fn has_zone<'a>(&'a self, zone: &'a str) -> BoxFuture<'a, Result<bool>> {
+    Box::pin(async move { Ok(self.zone_id(zone).await?.is_some()) })
+  }
+
+  fn create_txt<'a>(
+    &'a self,
+    zone: &'a str,
+    fqdn: &'a str,
+    value: &'a str,
+  ) -> BoxFuture<'a, Result<TxtRecord>> {
+    Box::pin(async move {
+      let zone_id = self.require_zone_id(zone).await?;
+      let req = CloudflareCreateRecordReq::new(&zone_id, value).name(fqdn);
+      let record_id = self.append_record(req).await?;
+      Ok(TxtRecord::new(zone, fqdn, value, record_id))
+    })
+  }
+
+  fn delete_txt<'a>(&'a self, record: &'a TxtRecord) -> BoxFuture<'a, Result<()>> {
+    Box::pin(async move {
+      let zone_id = self.require_zone_id(&record.zone).await?;
+      self
+        .delete_record(CloudflareDeleteRecordReq::new(&zone_id, &record.id))
+        .await?;
+      Ok(())
+    })
   }
 }
 
 impl CloudflareClient {
-  async fn exec_request(&self, method: Method, url: &str, req: &impl Serialize) -> Result<String> {
-    self
-      .client
-      .request(method, url)
-      .json(req)
+  async fn exec_request<R>(&self, method: Method, url: &str, req: &impl Serialize) -> Result<R>
+  where
+    R: DeserializeOwned,
+  {
+    let builder = self.client.request(method.clone(), url);
+    let builder = if method == Method::GET {
+      builder.query(req)
+    } else {
+      builder.json(req)
+    };
+    builder
       .send()
       .await
       .context(ReqwestClientSnafu)?
-      .json::<CloudflareRes>()
+      .json::<CloudflareRes<R>>()
       .await
       .context(ReqwestClientSnafu)?
-      .unwrap_data()
+      .unwrap_result()
   }
 }
 
@@ -97,3 +211,83 @@ fn auth_headers(auth: &CloudflareAuth) -> Result<HeaderMap> {
   }
   Ok(headers)
 }
+
+#[cfg(test)]
+mod tests {
+  use std::sync::{Arc, Mutex};
+
+  use super::*;
+  use crate::test_util::{StubResponse, request_body, stub_server};
+
+  /// Stub cloudflare api, zone `z1` of `example.com` has TXT record `r1` with value `"old"`,
+  /// request lines and bodies are recorded
+  async fn stub_cloudflare(requests: Arc<Mutex<Vec<String>>>) -> String {
+    stub_server(move |req| {
+      assert!(req.contains("authorization: Bearer token"));
+      let line = req.lines().next().unwrap().trim_end_matches(" HTTP/1.1");
+      requests
+        .lock()
+        .unwrap()
+        .push(format!("{} {}", line, request_body(req)));
+      let body = if line.starts_with("GET /zones?") {
+        r#"{"success":true,"errors":[],"result":[{"id":"z1","name":"example.com"}]}"#
+      } else if line.starts_with("GET /zones/z1/dns_records?") {
+        r#"{"success":true,"errors":[],"result":[{"id":"r1","name":"_acme-challenge.example.com","type":"TXT","content":"\"old\"","ttl":1,"comment":null}]}"#
+      } else if line == "POST /zones/z1/dns_records" {
+        r#"{"success":true,"errors":[],"result":{"id":"r2"}}"#
+      } else if line == "PATCH /zones/z1/dns_records/r1" {
+        r#"{"success":true,"errors":[],"result":{"id":"r1"}}"#
+      } else {
+        r#"{"success":false,"errors":[{"code":81044,"message":"Record does not exist."}],"result":null}"#
+      };
+      StubResponse::ok(body)
+    })
+    .await
+  }
+
+  fn client(endpoint: &str) -> CloudflareClient {
+    CloudflareClient::new(CloudflareOption::new_with_token("token").endpoint(endpoint)).unwrap()
+  }
+
+  #[tokio::test]
+  async fn append_keeps_existing_values() {
+    let requests = Arc::new(Mutex::new(vec![]));
+    let client = client(&stub_cloudflare(requests.clone()).await);
+    assert_eq!(client.zone_id("example.com").await.unwrap().unwrap(), "z1");
+
+    let name = "_acme-challenge.example.com";
+    let req = CloudflareCreateRecordReq::new("z1", "old").name(name);
+    assert_eq!(client.append_record(req).await.unwrap(), "r1");
+    let req = CloudflareCreateRecordReq::new("z1", "new").name(name);
+    assert_eq!(client.append_record(req).await.unwrap(), "r2");
+
+    let requests = requests.lock().unwrap();
+    assert_eq!(requests.len(), 4);
+    assert!(requests[1].contains("name=_acme-challenge.example.com"));
+    assert!(requests[1].contains("per_page=5000"));
+    assert!(requests[3].starts_with("POST /zones/z1/dns_records "));
+    assert!(requests[3].contains(r#""content":"new""#));
+    assert!(
+      requests
+        .iter()
+        .all(|r| !r.starts_with("PATCH") && !r.starts_with("DELETE"))
+    );
+  }
+
+  #[tokio::test]
+  async fn update_record_in_place() {
+    let requests = Arc::new(Mutex::new(vec![]));
+    let client = client(&stub_cloudflare(requests.clone()).await);
+
+    let req = CloudflareUpdateRecordReq::new("z1", "r1", "new").ttl(120);
+    assert_eq!(client.update_record(req).await.unwrap(), "r1");
+    assert_eq!(
+      requests.lock().unwrap()[0],
+      r#"PATCH /zones/z1/dns_records/r1 {"content":"new","ttl":120,"type":"TXT"}"#
+    );
+
+    let req = CloudflareUpdateRecordReq::new("z1", "r9", "new");
+    let err = client.update_record(req).await.unwrap_err().to_string();
+    assert!(err.contains("Cloudflare Error: code: 81044, message: Record does not exist."));
+  }
+}
@@ -6,10 +6,13 @@ mod client;
 pub use client::CloudflareClient;
 
 mod response;
-pub use response::CloudflareRes;
+pub use response::{CloudflareRecord, CloudflareRecordId, CloudflareRes, CloudflareZone};
 
 mod request;
-pub use request::{CloudflareCreateRecordReq, CloudflareDeleteRecordReq};
+pub use request::{
+  CloudflareCreateRecordReq, CloudflareDeleteRecordReq, CloudflareListRecordsReq,
+  CloudflareListZonesReq, CloudflareUpdateRecordReq,
+};
 
 mod option;
 pub use option::CloudflareOption;
@@ -8,6 +8,7 @@ pub struct CloudflareOption {
   pub(crate) auth: CloudflareAuth,
   pub(crate) proxy: Option<String>,
   pub(crate) timeout: Option<Duration>,
+  pub(crate) endpoint: Option<String>,
 }
 //
 impl CloudflareOption {
@@ -18,6 +19,7 @@ impl CloudflareOption {
       auth,
       proxy: None,
       timeout: None,
+      endpoint: None,
     }
   }
 
@@ -28,6 +30,7 @@ impl CloudflareOption {
       auth,
       proxy: None,
       timeout: None,
+      endpoint: None,
     }
   }
 
@@ -38,6 +41,7 @@ impl CloudflareOption {
         auth,
         proxy: None,
         timeout: None,
+        endpoint: None,
       };
       return Ok(option);
     }
@@ -46,6 +50,7 @@ impl CloudflareOption {
       auth,
       proxy: None,
       timeout: None,
+      endpoint: None,
     })
   }
 
@@ -60,6 +65,12 @@ impl CloudflareOption {
     self.timeout = Some(timeout);
     self
   }
+
+  /// Set api endpoint, default is `https://api.cloudflare.com/client/v4`
+  pub fn endpoint(mut self, endpoint: impl Into<String>) -> Self {
+    self.endpoint = Some(endpoint.into());
+    self
+  }
 }
 
 impl CloudflareOption {
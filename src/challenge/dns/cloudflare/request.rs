@@ -7,9 +7,9 @@ pub struct CloudflareCreateRecordReq<'a> {
   #[serde(skip_serializing)]
   pub(crate) zone_id: &'a str,
 
-  content: &'a str,
+  pub(crate) content: &'a str,
 
-  name: &'a str,
+  pub(crate) name: &'a str,
 
   #[serde(skip_serializing_if = "Option::is_none")]
   comment: Option<&'a str>,
@@ -70,3 +70,129 @@ impl<'a> CloudflareDeleteRecordReq<'a> {
     Self { zone_id, record_id }
   }
 }
+
+#[derive(Debug, Serialize)]
+pub struct CloudflareUpdateRecordReq<'a> {
+  #[serde(skip_serializing)]
+  pub(crate) zone_id: &'a str,
+
+  #[serde(skip_serializing)]
+  pub(crate) record_id: &'a str,
+
+  content: &'a str,
+
+  #[serde(skip_serializing_if = "Option::is_none")]
+  name: Option<&'a str>,
+
+  #[serde(skip_serializing_if = "Option::is_none")]
+  comment: Option<&'a str>,
+
+  #[serde(skip_serializing_if = "Option::is_none")]
+  ttl: Option<u64>,
+
+  #[serde(rename = "type")]
+  rtype: RecordType,
+}
+
+impl<'a> CloudflareUpdateRecordReq<'a> {
+  pub fn new(zone_id: &'a str, record_id: &'a str, value: &'a str) -> Self {
+    Self {
+      zone_id,
+      record_id,
+      content: value,
+      name: None,
+      comment: None,
+      ttl: None,
+      rtype: RecordType::TXT,
+    }
+  }
+
+  pub fn name(mut self, name: &'a str) -> Self {
+    self.name = Some(name);
+    self
+  }
+
+  pub fn comment(mut self, comment: &'a str) -> Self {
+    self.comment = Some(comment);
+    self
+  }
+
+  pub fn ttl(mut self, ttl: u64) -> Self {
+    self.ttl = Some(ttl);
+    self
+  }
+}
+
+#[derive(Debug, Serialize)]
+pub struct CloudflareListRecordsReq<'a> {
+  #[serde(skip_serializing)]
+  pub(crate) zone_id: &'a str,
+
+  #[serde(skip_serializing_if = "Option::is_none")]
+  name: Option<&'a str>,
+
+  #[serde(skip_serializing_if = "Option::is_none")]
+  per_page: Option<u64>,
+
+  #[serde(rename = "type")]
+  rtype: RecordType,
+}
+
+impl<'a> CloudflareListRecordsReq<'a> {
+  pub fn new(zone_id: &'a str) -> Self {
+    Self {
+      zone_id,
+      name: None,
+      per_page: None,
+      rtype: RecordType::TXT,
+    }
+  }
+
+  /// Filter by full name of record, for example, `_acme-challenge.example.com`
+  pub fn name(mut self, name: &'a str) -> Self {
+    self.name = Some(name);
+    self
+  }
+
+  /// Set page size, max is 5000, default is 100
+  pub fn per_page(mut self, per_page: u64) -> Self {
+    self.per_page = Some(per_page);
+    self
+  }
+}
+
+#[derive(Debug, Serialize)]
+pub struct CloudflareListZonesReq<'a> {
+  #[serde(skip_serializing_if = "Option::is_none")]
+  name: Option<&'a str>,
+
+  #[serde(skip_serializing_if = "Option::is_none")]
+  per_page: Option<u64>,
+}
+
+impl<'a> CloudflareListZonesReq<'a> {
+  pub fn new() -> Self {
+    Self {
+      name: None,
+      per_page: None,
+    }
+  }
+
+  /// Filter by domain name of zone, for example, `example.com`
+  pub fn name(mut self, name: &'a str) -> Self {
+    self.name = Some(name);
+    self
+  }
+
+  /// Set page size, max is 50, default is 20
+  pub fn per_page(mut self, per_page: u64) -> Self {
+    self.per_page = Some(per_page);
+    self
+  }
+}
+
+impl Default for CloudflareListZonesReq<'_> {
+  fn default() -> Self {
+    Self::new()
+  }
+}
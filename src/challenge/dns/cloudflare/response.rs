@@ -3,16 +3,42 @@ use serde::Deserialize;
 use crate::errors::{PlainTextSnafu, Result};
 
 #[derive(Debug, Deserialize)]
-pub struct CloudflareRes {
+pub struct CloudflareRes<T = CloudflareRecordId> {
   // success: bool,
   errors: Vec<FailureData>,
 
-  result: Option<SuccessData>,
+  result: Option<T>,
 }
 
+/// Id of record returned by create, update and delete api
 #[derive(Debug, Deserialize)]
-struct SuccessData {
-  id: String,
+pub struct CloudflareRecordId {
+  pub id: String,
+}
+
+/// DNS record returned by [`crate::challenge::dns::cloudflare::CloudflareClient::list_records`]
+#[derive(Debug, Clone, Deserialize)]
+pub struct CloudflareRecord {
+  pub id: String,
+
+  pub name: String,
+
+  pub content: String,
+
+  #[serde(rename = "type")]
+  pub rtype: String,
+
+  pub ttl: Option<u64>,
+
+  pub comment: Option<String>,
+}
+
+/// Zone returned by [`crate::challenge::dns::cloudflare::CloudflareClient::list_zones`]
+#[derive(Debug, Clone, Deserialize)]
+pub struct CloudflareZone {
+  pub id: String,
+
+  pub name: String,
 }
 
 #[derive(Debug, Deserialize)]
@@ -21,10 +47,10 @@ struct FailureData {
   message: String,
 }
 
-impl CloudflareRes {
-  pub fn unwrap_data(self) -> Result<String> {
+impl<T> CloudflareRes<T> {
+  pub fn unwrap_result(self) -> Result<T> {
     if let Some(data) = self.result {
-      return Ok(data.id);
+      return Ok(data);
     }
     let error = self
       .errors
@@ -34,3 +60,9 @@ impl CloudflareRes {
     PlainTextSnafu { message: error }.fail()
   }
 }
+
+impl CloudflareRes {
+  pub fn unwrap_data(self) -> Result<String> {
+    self.unwrap_result().map(|data| data.id)
+  }
+}
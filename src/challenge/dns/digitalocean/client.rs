@@ -0,0 +1,287 @@
+use futures_util::future::BoxFuture;
+use http::{HeaderMap, Method, header::AUTHORIZATION};
+use reqwest::{Client, ClientBuilder};
+use serde::{Serialize, de::DeserializeOwned};
+use snafu::ResultExt;
+
+use crate::{
+  challenge::dns::{
+    DnsChallengeClient, TxtRecord,
+    digitalocean::{
+      DigitalOceanCreateRecordReq, DigitalOceanDeleteRecordReq, DigitalOceanDomain,
+      DigitalOceanListRecordsReq, DigitalOceanOption, DigitalOceanRecord,
+      response::{DigitalOceanRes, DomainData, RecordData, RecordListData},
+    },
+    relative_name,
+  },
+  errors::{PlainTextSnafu, ReqwestClientSnafu, Result},
+  util::str_to_header_value,
+};
+
+const API_ENDPOINT: &str = "https://api.digitalocean.com/v2";
+
+/// Client for DigitalOcean DNS api
+#[derive(Debug)]
+pub struct DigitalOceanClient {
+  client: Client,
+  endpoint: String,
+}
+
+impl DigitalOceanClient {
+  /// Create client with option, see [`DigitalOceanOption`]
+  pub fn new(option: DigitalOceanOption) -> Result<Self> {
+    let DigitalOceanOption {
+      token,
+      proxy,
+      timeout,
+      endpoint,
+    } = option;
+    let mut client = ClientBuilder::new();
+    if let Some(timeout) = timeout {
+      client = client.timeout(timeout);
+    }
+    if let Some(proxy) = proxy {
+      let proxy = reqwest::Proxy::all(proxy).context(ReqwestClientSnafu)?;
+      client = client.proxy(proxy);
+    }
+    let mut headers = HeaderMap::new();
+    headers.insert(
+      AUTHORIZATION,
+      str_to_header_value(format!("Bearer {}", token))?,
+    );
+    client = client.default_headers(headers);
+    let client = client.build().context(ReqwestClientSnafu)?;
+    Ok(Self {
+      client,
+      endpoint: endpoint.unwrap_or_else(|| API_ENDPOINT.to_string()),
+    })
+  }
+
+  /// Create client from environment variable, see [`DigitalOceanOption::new_from_env`]
+  pub fn new_from_env() -> Result<Self> {
+    Self::new(DigitalOceanOption::new_from_env()?)
+  }
+
+  /// Create DNS TXT record
+  ///
+  /// `return`: record id or error
+  pub async fn create_record(&self, req: DigitalOceanCreateRecordReq<'_>) -> Result<u64> {
+    let url = format!("{}/domains/{}/records", self.endpoint, req.domain);
+    let data: RecordData = self.exec_request(Method::POST, &url, Some(&req)).await?;
+    Ok(data.domain_record.id)
+  }
+
+  /// Delete DNS TXT record
+  pub async fn delete_record(&self, req: DigitalOceanDeleteRecordReq<'_>) -> Result<()> {
+    let url = format!(
+      "{}/domains/{}/records/{}",
+      self.endpoint, req.domain, req.record_id
+    );
+    self.exec_request(Method::DELETE, &url, None::<&()>).await
+  }
+
+  /// List DNS TXT records of domain, pages are followed by `links.pages.next` of response
+  pub async fn list_records(
+    &self,
+    req: DigitalOceanListRecordsReq<'_>,
+  ) -> Result<Vec<DigitalOceanRecord>> {
+    let url = format!("{}/domains/{}/records", self.endpoint, req.domain);
+    let mut data: RecordListData = self.exec_request(Method::GET, &url, Some(&req)).await?;
+    let mut records = vec![];
+    loop {
+      records.append(&mut data.domain_records);
+      // url of next page keeps the query of the first one
+      match data.links.and_then(|l| l.pages).and_then(|p| p.next) {
+        Some(next) => data = self.exec_request(Method::GET, &next, None::<&()>).await?,
+        None => return Ok(records),
+      }
+    }
+  }
+
+  /// Append value to the TXT records of `req`'s name, the existing record is reused if it has the
+  /// same value
+  pub async fn append_record(&self, req: DigitalOceanCreateRecordReq<'_>) -> Result<u64> {
+    let fqdn = match req.name {
+      "@" => req.domain.to_string(),
+      name => format!("{}.{}", name, req.domain),
+    };
+    let list_req = DigitalOceanListRecordsReq::new(req.domain)
+      .name(&fqdn)
+      .per_page(200);
+    let records = self.list_records(list_req).await?;
+    if let Some(record) = records.into_iter().find(|r| r.data == req.data) {
+      return Ok(record.id);
+    }
+    self.create_record(req).await
+  }
+
+  /// Get domain, `None` if it is not in the account
+  pub async fn domain(&self, domain: &str) -> Result<Option<DigitalOceanDomain>> {
+    let url = format!("{}/domains/{}", self.endpoint, domain);
+    let res = self
+      .client
+      .get(url)
+      .send()
+      .await
+      .context(ReqwestClientSnafu)?
+      .json::<DigitalOceanRes<DomainData>>()
+      .await
+      .context(ReqwestClientSnafu)?;
+    match res {
+      DigitalOceanRes::Failure(failure) if failure.id == "not_found" => Ok(None),
+      res => Ok(Some(res.unwrap_data()?.domain)),
+    }
+  }
+}
+
+impl DnsChallengeClient for DigitalOceanClient {
+  fn has_zone<'a>(&'a self, zone: &'a str) -> BoxFuture<'a, Result<bool>> {
+    Box::pin(async move { Ok(self.domain(zone).await?.is_some()) })
+  }
+
+  fn create_txt<'a>(
+    &'a self,
+    zone: &'a str,
+    fqdn: &'a str,
+    value: &'a str,
+  ) -> BoxFuture<'a, Result<TxtRecord>> {
+    Box::pin(async move {
+      let req = DigitalOceanCreateRecordReq::new(zone, relative_name(fqdn, zone), value).ttl(30);
+      let record_id = self.append_record(req).await?;
+      Ok(TxtRecord::new(zone, fqdn, value, record_id.to_string()))
+    })
+  }
+
+  fn delete_txt<'a>(&'a self, record: &'a TxtRecord) -> BoxFuture<'a, Result<()>> {
+    Box::pin(async move {
+      let Ok(record_id) = record.id.parse() else {
+        return PlainTextSnafu {
+          message: format!("DigitalOcean Error: invalid record id {}", record.id),
+        }
+        .fail();
+      };
+      self
+        .delete_record(DigitalOceanDeleteRecordReq::new(&record.zone, record_id))
+        .await
+    })
+  }
+}
+
+impl DigitalOceanClient {
+  async fn exec_request<R>(
+    &self,
+    method: Method,
+    url: &str,
+    req: Option<&impl Serialize>,
+  ) -> Result<R>
+  where
+    R: DeserializeOwned,
+  {
+    let mut builder = self.client.request(method.clone(), url);
+    if let Some(req) = req {
+      builder = if method == Method::GET {
+        builder.query(req)
+      } else {
+        builder.json(req)
+      };
+    }
+    let text = builder
+      .send()
+      .await
+      .context(ReqwestClientSnafu)?
+      .text()
+      .await
+      .context(ReqwestClientSnafu)?;
+    // delete api responds with 204 and empty body
+    let text = if text.is_empty() { "null" } else { &text };
+    match serde_json::from_str::<DigitalOceanRes<R>>(text) {
+      Ok(res) => res.unwrap_data(),
+      Err(_) => PlainTextSnafu {
+        message: format!("DigitalOcean Error: unexpected response: {}", text),
+      }
+      .fail(),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::sync::{Arc, Mutex};
+
+  use super::*;
+  use crate::test_util::{StubResponse, request_body, stub_server};
+
+  /// Stub DigitalOcean api, `example.com` has TXT record `1` with value `old` at `_acme-challenge`
+  /// on the second page of records, request lines and bodies are recorded
+  async fn stub_digitalocean(requests: Arc<Mutex<Vec<String>>>) -> String {
+    stub_server(move |req| {
+      let host = req
+        .lines()
+        .find_map(|l| l.strip_prefix("host: "))
+        .unwrap();
+      assert!(req.contains("authorization: Bearer token"));
+      let line = req.lines().next().unwrap();
+      let line = line.strip_suffix(" HTTP/1.1").unwrap();
+      requests
+        .lock()
+        .unwrap()
+        .push(format!("{} {}", line, request_body(req)));
+      let (method, path) = line.split_once(' ').unwrap();
+      match (method, path.split('?').next().unwrap()) {
+        ("GET", "/domains/example.com") => {
+          StubResponse::ok(r#"{"domain":{"name":"example.com","ttl":1800}}"#)
+        }
+        ("GET", "/domains/example.com/records") if path.contains("?page=2") => StubResponse::ok(
+          r#"{"domain_records":[{"id":1,"name":"_acme-challenge","data":"old","type":"TXT","ttl":30}],"links":{"pages":{"prev":"prev"}}}"#,
+        ),
+        ("GET", "/domains/example.com/records") => StubResponse::ok(format!(
+          r#"{{"domain_records":[{{"id":3,"name":"_acme-challenge","data":"other","type":"TXT","ttl":30}}],"links":{{"pages":{{"next":"http://{}/domains/example.com/records?page=2&{}"}}}}}}"#,
+          host,
+          path.split_once('?').unwrap().1
+        )),
+        ("POST", "/domains/example.com/records") => StubResponse::new(
+          "201 Created",
+          r#"{"domain_record":{"id":2,"name":"_acme-challenge","data":"new","type":"TXT","ttl":30}}"#,
+        ),
+        ("DELETE", "/domains/example.com/records/2") => StubResponse::new("204 No Content", ""),
+        _ => StubResponse::new(
+          "404 Not Found",
+          r#"{"id":"not_found","message":"The resource you were accessing could not be found."}"#,
+        ),
+      }
+    })
+    .await
+  }
+
+  #[tokio::test]
+  async fn create_and_delete_txt() {
+    let requests = Arc::new(Mutex::new(vec![]));
+    let endpoint = stub_digitalocean(requests.clone()).await;
+    let client =
+      DigitalOceanClient::new(DigitalOceanOption::new("token").endpoint(endpoint)).unwrap();
+    assert!(client.has_zone("example.com").await.unwrap());
+    assert!(!client.has_zone("example.org").await.unwrap());
+
+    let existed = client
+      .create_txt("example.com", "_acme-challenge.example.com", "old")
+      .await
+      .unwrap();
+    assert_eq!(existed.id, "1");
+    let record = client
+      .create_txt("example.com", "_acme-challenge.example.com", "new")
+      .await
+      .unwrap();
+    assert_eq!(record.id, "2");
+    client.delete_txt(&record).await.unwrap();
+
+    let requests = requests.lock().unwrap();
+    assert_eq!(requests.len(), 8);
+    assert!(requests[2].contains("name=_acme-challenge.example.com"));
+    assert!(requests[3].contains("?page=2"));
+    assert!(requests[3].contains("name=_acme-challenge.example.com"));
+    assert!(requests[6].starts_with("POST /domains/example.com/records "));
+    assert!(requests[6].contains(r#""name":"_acme-challenge""#));
+    assert!(requests[6].contains(r#""data":"new""#));
+    assert!(requests[7].starts_with("DELETE /domains/example.com/records/2 "));
+  }
+}
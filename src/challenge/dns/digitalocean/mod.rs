@@ -0,0 +1,17 @@
+//! Implement of [DigitalOcean](https://www.digitalocean.com/) DNS challenge
+//!
+//! Link: <https://docs.digitalocean.com/reference/api/digitalocean/#tag/Domain-Records>
+
+mod client;
+pub use client::DigitalOceanClient;
+
+mod response;
+pub use response::{DigitalOceanDomain, DigitalOceanRecord};
+
+mod request;
+pub use request::{
+  DigitalOceanCreateRecordReq, DigitalOceanDeleteRecordReq, DigitalOceanListRecordsReq,
+};
+
+mod option;
+pub use option::DigitalOceanOption;
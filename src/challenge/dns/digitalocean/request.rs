@@ -0,0 +1,90 @@
+use serde::Serialize;
+
+use crate::challenge::dns::RecordType;
+
+#[derive(Debug, Serialize)]
+pub struct DigitalOceanCreateRecordReq<'a> {
+  #[serde(skip_serializing)]
+  pub(crate) domain: &'a str,
+
+  pub(crate) name: &'a str,
+
+  pub(crate) data: &'a str,
+
+  #[serde(skip_serializing_if = "Option::is_none")]
+  ttl: Option<u64>,
+
+  #[serde(rename = "type")]
+  rtype: RecordType,
+}
+
+impl<'a> DigitalOceanCreateRecordReq<'a> {
+  /// `name` is relative to `domain`, for example, `_acme-challenge` or `@`
+  pub fn new(domain: &'a str, name: &'a str, value: &'a str) -> Self {
+    Self {
+      domain,
+      name,
+      data: value,
+      ttl: None,
+      rtype: RecordType::TXT,
+    }
+  }
+
+  /// Set TTL, min is 30, default is 1800
+  pub fn ttl(mut self, ttl: u64) -> Self {
+    self.ttl = Some(ttl);
+    self
+  }
+}
+
+#[derive(Debug)]
+pub struct DigitalOceanDeleteRecordReq<'a> {
+  pub(crate) domain: &'a str,
+
+  pub(crate) record_id: u64,
+}
+
+impl<'a> DigitalOceanDeleteRecordReq<'a> {
+  pub fn new(domain: &'a str, record_id: u64) -> Self {
+    Self { domain, record_id }
+  }
+}
+
+#[derive(Debug, Serialize)]
+pub struct DigitalOceanListRecordsReq<'a> {
+  #[serde(skip_serializing)]
+  pub(crate) domain: &'a str,
+
+  #[serde(skip_serializing_if = "Option::is_none")]
+  name: Option<&'a str>,
+
+  #[serde(skip_serializing_if = "Option::is_none")]
+  per_page: Option<u64>,
+
+  #[serde(rename = "type")]
+  rtype: RecordType,
+}
+
+impl<'a> DigitalOceanListRecordsReq<'a> {
+  pub fn new(domain: &'a str) -> Self {
+    Self {
+      domain,
+      name: None,
+      per_page: None,
+      rtype: RecordType::TXT,
+    }
+  }
+
+  /// Only list records of `name`, which must be the full name, for example,
+  /// `_acme-challenge.example.com`
+  pub fn name(mut self, name: &'a str) -> Self {
+    self.name = Some(name);
+    self
+  }
+
+  /// Set page size, max is 200, default is 20
+  pub fn per_page(mut self, per_page: u64) -> Self {
+    self.per_page = Some(per_page);
+    self
+  }
+}
@@ -0,0 +1,84 @@
+use serde::Deserialize;
+
+use crate::errors::{PlainTextSnafu, Result};
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub(crate) enum DigitalOceanRes<T> {
+  Failure(FailureData),
+  Success(T),
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct RecordData {
+  pub(crate) domain_record: DigitalOceanRecord,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct RecordListData {
+  #[serde(default)]
+  pub(crate) domain_records: Vec<DigitalOceanRecord>,
+
+  pub(crate) links: Option<LinksData>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct LinksData {
+  pub(crate) pages: Option<PagesData>,
+}
+
+/// Urls of other pages, absent on the last page
+#[derive(Debug, Deserialize)]
+pub(crate) struct PagesData {
+  pub(crate) next: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct DomainData {
+  pub(crate) domain: DigitalOceanDomain,
+}
+
+/// DNS record returned by [`crate::challenge::dns::digitalocean::DigitalOceanClient::list_records`]
+#[derive(Debug, Clone, Deserialize)]
+pub struct DigitalOceanRecord {
+  pub id: u64,
+
+  /// Name relative to domain, for example, `_acme-challenge`
+  pub name: String,
+
+  pub data: String,
+
+  #[serde(rename = "type")]
+  pub rtype: String,
+
+  pub ttl: Option<u64>,
+}
+
+/// Domain returned by [`crate::challenge::dns::digitalocean::DigitalOceanClient::domain`]
+#[derive(Debug, Clone, Deserialize)]
+pub struct DigitalOceanDomain {
+  pub name: String,
+
+  pub ttl: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct FailureData {
+  pub(crate) id: String,
+  message: String,
+}
+
+impl<T> DigitalOceanRes<T> {
+  pub fn unwrap_data(self) -> Result<T> {
+    match self {
+      DigitalOceanRes::Success(data) => Ok(data),
+      DigitalOceanRes::Failure(failure) => PlainTextSnafu {
+        message: format!(
+          "DigitalOcean Error: id: {}, message: {}",
+          failure.id, failure.message
+        ),
+      }
+      .fail(),
+    }
+  }
+}
@@ -0,0 +1,372 @@
+use futures_util::future::BoxFuture;
+use http::{
+  HeaderMap, HeaderValue,
+  header::{AUTHORIZATION, CONTENT_TYPE, HOST},
+};
+use jiff::{Timestamp, tz::TimeZone};
+use serde::{Serialize, de::DeserializeOwned};
+use snafu::ResultExt;
+
+use crate::{
+  challenge::dns::{
+    DnsChallengeClient, TxtRecord,
+    dnspod::{
+      option::DnspodClientOption,
+      request::{
+        DnspodCreateRecordReq, DnspodDeleteRecordReq, DnspodListDomainsReq, DnspodListRecordsReq,
+      },
+      response::{
+        DnspodRecord, DnspodRes, DomainListData, EmptyData, RecordIdData, RecordListData,
+      },
+    },
+    relative_name,
+  },
+  errors::{PlainTextSnafu, ReqwestClientSnafu, Result},
+  util::{json_serialize, sha2_hex, sha2_hmac, str_to_header_value, url_host},
+};
+
+/// Client for DNSPod api of Tencent Cloud
+#[derive(Debug)]
+pub struct DnspodClient {
+  secret_id: String,
+  secret_key: String,
+  endpoint: String,
+  http_client: reqwest::Client,
+}
+
+const DNS_ENDPOINT: &str = "https://dnspod.tencentcloudapi.com/";
+const DNS_SERVICE: &str = "dnspod";
+const DNS_VERSION: &str = "2021-03-23";
+const CONTENT_TYPE_JSON: &str = "application/json; charset=utf-8";
+const NO_DATA_OF_RECORD: &str = "ResourceNotFound.NoDataOfRecord";
+const NO_DATA_OF_DOMAIN: &str = "ResourceNotFound.NoDataOfDomain";
+
+impl DnspodClient {
+  /// Create client with Tencent Cloud api key, this key must have privilege to access
+  /// __QcloudDNSPodFullAccess__. If you want to set proxy or timeout, please use
+  /// [`Self::new_with_option`]
+  pub fn new(secret_id: impl Into<String>, secret_key: impl Into<String>) -> Self {
+    DnspodClient {
+      secret_id: secret_id.into(),
+      secret_key: secret_key.into(),
+      endpoint: DNS_ENDPOINT.to_string(),
+      http_client: reqwest::Client::new(),
+    }
+  }
+
+  /// Create client from environment variable, variable name for secret id is __DP_Id__ or
+  /// __EASY_ACME_DNSPOD_ID__, and for secret key is __DP_Key__ or __EASY_ACME_DNSPOD_KEY__.
+  /// If you want to set proxy or timeout, please use [`Self::new_with_option`]
+  pub fn new_from_env() -> Result<Self> {
+    Ok(Self::new(
+      DnspodClientOption::env_secret_id()?,
+      DnspodClientOption::env_secret_key()?,
+    ))
+  }
+
+  /// Create client with option, see [`DnspodClientOption`]
+  pub fn new_with_option(option: DnspodClientOption) -> Result<Self> {
+    let DnspodClientOption {
+      secret_id,
+      secret_key,
+      proxy,
+      timeout,
+      endpoint,
+    } = option;
+    let mut client = reqwest::ClientBuilder::new();
+    if let Some(timeout) = timeout {
+      client = client.timeout(timeout);
+    }
+    if let Some(proxy) = proxy {
+      let proxy = reqwest::Proxy::all(proxy).context(ReqwestClientSnafu)?;
+      client = client.proxy(proxy);
+    }
+    let client = client.build().context(ReqwestClientSnafu)?;
+    Ok(DnspodClient {
+      secret_id,
+      secret_key,
+      endpoint: endpoint.unwrap_or_else(|| DNS_ENDPOINT.to_string()),
+      http_client: client,
+    })
+  }
+
+  /// Create DNS TXT record
+  ///
+  /// `return`: record id or error
+  pub async fn create_record(&self, req: DnspodCreateRecordReq<'_>) -> Result<u64> {
+    let res: DnspodRes<RecordIdData> = self.exec_request("CreateRecord", &req).await?;
+    Ok(res.unwrap_data()?.record_id)
+  }
+
+  /// Delete DNS TXT record
+  pub async fn delete_record(&self, req: DnspodDeleteRecordReq<'_>) -> Result<()> {
+    let res: DnspodRes<EmptyData> = self.exec_request("DeleteRecord", &req).await?;
+    res.unwrap_data()?;
+    Ok(())
+  }
+
+  /// List DNS TXT records of a domain
+  ///
+  /// `return`: records, empty if there is no record, or error
+  pub async fn list_records(&self, req: DnspodListRecordsReq<'_>) -> Result<Vec<DnspodRecord>> {
+    let res: DnspodRes<RecordListData> = self.exec_request("DescribeRecordList", &req).await?;
+    if res.error_code() == Some(NO_DATA_OF_RECORD) {
+      return Ok(vec![]);
+    }
+    Ok(res.unwrap_data()?.records)
+  }
+
+  /// List domains hosted in DNSPod
+  ///
+  /// `return`: domain names or error
+  pub async fn list_domains(&self, req: DnspodListDomainsReq<'_>) -> Result<Vec<String>> {
+    let res: DnspodRes<DomainListData> = self.exec_request("DescribeDomainList", &req).await?;
+    if res.error_code() == Some(NO_DATA_OF_DOMAIN) {
+      return Ok(vec![]);
+    }
+    let domains = res.unwrap_data()?.domains;
+    Ok(domains.into_iter().map(|d| d.name).collect())
+  }
+
+  /// Append value to the TXT records of `req`'s name, the existing record is reused if it has the
+  /// same value
+  ///
+  /// `return`: record id or error
+  pub async fn append_record(&self, req: DnspodCreateRecordReq<'_>) -> Result<u64> {
+    let list_req = DnspodListRecordsReq::new(req.domain)
+      .sub_domain(req.sub_domain)
+      .limit(3000);
+    let records = self.list_records(list_req).await?;
+    if let Some(record) = records.into_iter().find(|r| r.value == req.value) {
+      return Ok(record.record_id);
+    }
+    self.create_record(req).await
+  }
+}
+
+impl DnsChallengeClient for DnspodClient {
+  fn has_zone<'a>(&'a self, zone: &'a str) -> BoxFuture<'a, Result<bool>> {
+    Box::pin(async move {
+      let domains = self.list_domains(DnspodListDomainsReq::new(zone)).await?;
+      Ok(domains.iter().any(|d| d.eq_ignore_ascii_case(zone)))
+    })
+  }
+
+  fn create_txt<'a>(
+    &'a self,
+    zone: &'a str,
+    fqdn: &'a str,
+    value: &'a str,
+  ) -> BoxFuture<'a, Result<TxtRecord>> {
+    Box::pin(async move {
+      let req = DnspodCreateRecordReq::new(zone, relative_name(fqdn, zone), value);
+      let record_id = self.append_record(req).await?;
+      Ok(TxtRecord::new(zone, fqdn, value, record_id.to_string()))
+    })
+  }
+
+  fn delete_txt<'a>(&'a self, record: &'a TxtRecord) -> BoxFuture<'a, Result<()>> {
+    Box::pin(async move {
+      let Ok(record_id) = record.id.parse() else {
+        return PlainTextSnafu {
+          message: format!("DNSPod Error: invalid record id {}", record.id),
+        }
+        .fail();
+      };
+      self
+        .delete_record(DnspodDeleteRecordReq::new(&record.zone, record_id))
+        .await
+    })
+  }
+}
+
+impl DnspodClient {
+  async fn exec_request<T, R>(&self, action: &str, req: &T) -> Result<DnspodRes<R>>
+  where
+    T: Serialize,
+    R: DeserializeOwned,
+  {
+    let payload = json_serialize(req)?;
+    let timestamp = Timestamp::now();
+    let headers = self.create_headers(action, &payload, timestamp)?;
+    let res = self
+      .http_client
+      .post(&self.endpoint)
+      .headers(headers)
+      .body(payload)
+      .send()
+      .await
+      .context(ReqwestClientSnafu)?
+      .json::<DnspodRes<R>>()
+      .await
+      .context(ReqwestClientSnafu)?;
+    Ok(res)
+  }
+
+  fn create_headers(&self, action: &str, payload: &str, timestamp: Timestamp) -> Result<HeaderMap> {
+    let auth = self.signature(action, payload, timestamp)?;
+    let mut headers = HeaderMap::new();
+    headers.insert(AUTHORIZATION, str_to_header_value(&auth)?);
+    headers.insert(CONTENT_TYPE, HeaderValue::from_static(CONTENT_TYPE_JSON));
+    headers.insert(HOST, str_to_header_value(url_host(&self.endpoint))?);
+    headers.insert("x-tc-action", str_to_header_value(action)?);
+    headers.insert(
+      "x-tc-timestamp",
+      str_to_header_value(timestamp.as_second().to_string())?,
+    );
+    headers.insert("x-tc-version", HeaderValue::from_static(DNS_VERSION));
+    Ok(headers)
+  }
+
+  fn signature(&self, action: &str, payload: &str, timestamp: Timestamp) -> Result<String> {
+    let headers = [
+      ("content-type", CONTENT_TYPE_JSON),
+      ("host", url_host(&self.endpoint)),
+      ("x-tc-action", &action.to_lowercase()),
+    ];
+    tc3_signature(
+      &self.secret_id,
+      &self.secret_key,
+      DNS_SERVICE,
+      &headers,
+      payload,
+      timestamp,
+    )
+  }
+}
+
+/// [TC3-HMAC-SHA256](https://cloud.tencent.com/document/api/1427/56189) signature of POST
+/// request, `headers` to sign are lowercase and sorted by name
+///
+/// `return`: value of `Authorization` header
+fn tc3_signature(
+  secret_id: &str,
+  secret_key: &str,
+  service: &str,
+  headers: &[(&str, &str)],
+  payload: &str,
+  timestamp: Timestamp,
+) -> Result<String> {
+  let date = timestamp
+    .to_zoned(TimeZone::UTC)
+    .strftime("%Y-%m-%d")
+    .to_string();
+  let canonical_headers: String = headers
+    .iter()
+    .map(|(name, value)| format!("{}:{}\n", name, value))
+    .collect();
+  let signed_headers = headers
+    .iter()
+    .map(|(name, _)| *name)
+    .collect::<Vec<_>>()
+    .join(";");
+  let canonical_request = format!(
+    "POST\n/\n\n{}\n{}\n{}",
+    canonical_headers,
+    signed_headers,
+    sha2_hex(payload)
+  );
+  let scope = format!("{}/{}/tc3_request", date, service);
+  let str_to_sign = format!(
+    "TC3-HMAC-SHA256\n{}\n{}\n{}",
+    timestamp.as_second(),
+    scope,
+    sha2_hex(canonical_request)
+  );
+  let key = sha2_hmac(format!("TC3{}", secret_key), date.as_bytes())?;
+  let key = sha2_hmac(key, service.as_bytes())?;
+  let key = sha2_hmac(key, b"tc3_request")?;
+  let signature = hex::encode(sha2_hmac(key, str_to_sign.as_bytes())?);
+  Ok(format!(
+    "TC3-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+    secret_id, scope, signed_headers, signature
+  ))
+}
+
+#[cfg(test)]
+mod tests {
+  use std::sync::{Arc, Mutex};
+
+  use super::*;
+  use crate::test_util::{StubResponse, request_body, stub_server};
+
+  #[test]
+  fn tc3_signature_of_example() {
+    // example of the TC3-HMAC-SHA256 document, signed with the masked key as published
+    let payload =
+      r#"{"Limit": 1, "Filters": [{"Values": ["\u672a\u547d\u540d"], "Name": "instance-name"}]}"#;
+    let headers = [
+      ("content-type", "application/json; charset=utf-8"),
+      ("host", "cvm.tencentcloudapi.com"),
+    ];
+    let timestamp = Timestamp::from_second(1551113065).unwrap();
+    let auth = tc3_signature(
+      "AKIDz8krbsJ5yKBZQpn74WFkmLPx3*******",
+      "Gu5t9xGARNpq86cd98joQYCN3*******",
+      "cvm",
+      &headers,
+      payload,
+      timestamp,
+    )
+    .unwrap();
+    assert_eq!(
+      auth,
+      "TC3-HMAC-SHA256 Credential=AKIDz8krbsJ5yKBZQpn74WFkmLPx3*******/2019-02-25/cvm/tc3_request, \
+       SignedHeaders=content-type;host, \
+       Signature=2230eefd229f582d8b1b891af7107b91597240707d778ab3738f756258d7652c"
+    );
+  }
+
+  /// Stub DNSPod api, `example.com` has TXT record `1` with value `old` at `_acme-challenge`,
+  /// actions and bodies of requests are recorded
+  async fn stub_dnspod(requests: Arc<Mutex<Vec<String>>>) -> String {
+    stub_server(move |req| {
+      assert!(req.contains("authorization: TC3-HMAC-SHA256 Credential=id/"));
+      assert!(req.contains("SignedHeaders=content-type;host;x-tc-action, Signature="));
+      let action = req
+        .lines()
+        .find_map(|l| l.strip_prefix("x-tc-action: "))
+        .unwrap();
+      requests
+        .lock()
+        .unwrap()
+        .push(format!("{} {}", action, request_body(req)));
+      let body = match action {
+        "DescribeDomainList" => r#"{"Response":{"DomainList":[{"Name":"example.com"}],"RequestId":"1"}}"#,
+        "DescribeRecordList" => {
+          r#"{"Response":{"RecordList":[{"RecordId":1,"Name":"_acme-challenge","Type":"TXT","Value":"old","TTL":600}],"RequestId":"2"}}"#
+        }
+        "CreateRecord" => r#"{"Response":{"RecordId":2,"RequestId":"3"}}"#,
+        "DeleteRecord" => r#"{"Response":{"RequestId":"4"}}"#,
+        _ => r#"{"Response":{"Error":{"Code":"InvalidAction","Message":"unknown action"},"RequestId":"5"}}"#,
+      };
+      StubResponse::ok(body)
+    })
+    .await
+  }
+
+  #[tokio::test]
+  async fn create_and_delete_txt() {
+    let requests = Arc::new(Mutex::new(vec![]));
+    let endpoint = stub_dnspod(requests.clone()).await;
+    let option = DnspodClientOption::new("id", "key").endpoint(format!("{}/", endpoint));
+    let client = DnspodClient::new_with_option(option).unwrap();
+    assert!(client.has_zone("example.com").await.unwrap());
+
+    let record = client
+      .create_txt("example.com", "_acme-challenge.example.com", "new")
+      .await
+      .unwrap();
+    assert_eq!(record.id, "2");
+    client.delete_txt(&record).await.unwrap();
+
+    let requests = requests.lock().unwrap();
+    assert_eq!(requests.len(), 4);
+    assert!(requests[1].contains(r#""Subdomain":"_acme-challenge""#));
+    assert!(requests[2].starts_with("CreateRecord "));
+    assert!(requests[2].contains(r#""SubDomain":"_acme-challenge""#));
+    assert!(requests[2].contains(r#""Value":"new""#));
+    assert!(requests[3].starts_with("DeleteRecord "));
+    assert!(requests[3].contains(r#""RecordId":2"#));
+  }
+}
@@ -0,0 +1,17 @@
+//! Implement of [DNSPod](https://www.dnspod.cn/) (Tencent Cloud) DNS challenge
+//!
+//! Link: <https://cloud.tencent.com/document/api/1427/56180>
+
+mod client;
+pub use client::DnspodClient;
+
+mod option;
+pub use option::DnspodClientOption;
+
+mod request;
+pub use request::{
+  DnspodCreateRecordReq, DnspodDeleteRecordReq, DnspodListDomainsReq, DnspodListRecordsReq,
+};
+
+mod response;
+pub use response::DnspodRecord;
@@ -0,0 +1,63 @@
+use std::time::Duration;
+
+use crate::{errors::Result, util::env_single_var};
+
+/// Options for create a [`crate::challenge::dns::dnspod::DnspodClient`] instance
+#[derive(Debug)]
+pub struct DnspodClientOption {
+  pub(crate) secret_id: String,
+  pub(crate) secret_key: String,
+  pub(crate) proxy: Option<String>,
+  pub(crate) timeout: Option<Duration>,
+  pub(crate) endpoint: Option<String>,
+}
+
+impl DnspodClientOption {
+  /// Create option with Tencent Cloud api key, this key must have privilege to access
+  /// __QcloudDNSPodFullAccess__
+  pub fn new(secret_id: impl Into<String>, secret_key: impl Into<String>) -> Self {
+    DnspodClientOption {
+      secret_id: secret_id.into(),
+      secret_key: secret_key.into(),
+      proxy: None,
+      timeout: None,
+      endpoint: None,
+    }
+  }
+
+  /// Create option from environment variable, variable name for secret id is __DP_Id__ or
+  /// __EASY_ACME_DNSPOD_ID__, and for secret key is __DP_Key__ or __EASY_ACME_DNSPOD_KEY__
+  pub fn new_from_env() -> Result<Self> {
+    Ok(Self::new(Self::env_secret_id()?, Self::env_secret_key()?))
+  }
+
+  /// Set proxy, for example, `https://127.0.0.1:8080`, `socks5://127.0.0.1:9000`, default is `None`
+  pub fn proxy(mut self, proxy: impl Into<String>) -> Self {
+    self.proxy = Some(proxy.into());
+    self
+  }
+
+  /// Set timeout, for example, `Duration::from_secs(5)`, default is `None`
+  pub fn timeout(mut self, timeout: Duration) -> Self {
+    self.timeout = Some(timeout);
+    self
+  }
+
+  /// Set api endpoint, default is `https://dnspod.tencentcloudapi.com/`
+  pub fn endpoint(mut self, endpoint: impl Into<String>) -> Self {
+    self.endpoint = Some(endpoint.into());
+    self
+  }
+}
+
+impl DnspodClientOption {
+  #[inline]
+  pub(crate) fn env_secret_id() -> Result<String> {
+    env_single_var(["DP_Id", "EASY_ACME_DNSPOD_ID"])
+  }
+
+  #[inline]
+  pub(crate) fn env_secret_key() -> Result<String> {
+    env_single_var(["DP_Key", "EASY_ACME_DNSPOD_KEY"])
+  }
+}
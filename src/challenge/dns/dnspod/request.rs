@@ -0,0 +1,131 @@
+use serde::Serialize;
+
+use crate::challenge::dns::RecordType;
+
+/// Request of [`crate::challenge::dns::dnspod::DnspodClient::create_record`]
+#[derive(Debug, Serialize)]
+pub struct DnspodCreateRecordReq<'a> {
+  #[serde(rename = "Domain")]
+  pub(crate) domain: &'a str,
+
+  #[serde(rename = "SubDomain")]
+  pub(crate) sub_domain: &'a str,
+
+  #[serde(rename = "RecordType")]
+  rtype: RecordType,
+
+  #[serde(rename = "RecordLine")]
+  line: &'a str,
+
+  #[serde(rename = "Value")]
+  pub(crate) value: &'a str,
+
+  #[serde(rename = "TTL", skip_serializing_if = "Option::is_none")]
+  ttl: Option<u64>,
+}
+
+impl<'a> DnspodCreateRecordReq<'a> {
+  /// `sub_domain` is relative to `domain`, for example, `_acme-challenge` or `@`
+  pub fn new(domain: &'a str, sub_domain: &'a str, value: &'a str) -> Self {
+    DnspodCreateRecordReq {
+      domain,
+      sub_domain,
+      value,
+      rtype: RecordType::TXT,
+      line: "默认",
+      ttl: None,
+    }
+  }
+
+  /// Set record line, default is `默认`
+  pub fn line(mut self, line: &'a str) -> Self {
+    self.line = line;
+    self
+  }
+
+  pub fn ttl(mut self, ttl: u64) -> Self {
+    self.ttl = Some(ttl);
+    self
+  }
+}
+
+/// Request of [`crate::challenge::dns::dnspod::DnspodClient::delete_record`]
+#[derive(Debug, Serialize)]
+pub struct DnspodDeleteRecordReq<'a> {
+  #[serde(rename = "Domain")]
+  domain: &'a str,
+
+  #[serde(rename = "RecordId")]
+  record_id: u64,
+}
+
+impl<'a> DnspodDeleteRecordReq<'a> {
+  pub fn new(domain: &'a str, record_id: u64) -> Self {
+    DnspodDeleteRecordReq { domain, record_id }
+  }
+}
+
+/// Request of [`crate::challenge::dns::dnspod::DnspodClient::list_records`]
+#[derive(Debug, Serialize)]
+pub struct DnspodListRecordsReq<'a> {
+  #[serde(rename = "Domain")]
+  domain: &'a str,
+
+  #[serde(rename = "Subdomain", skip_serializing_if = "Option::is_none")]
+  sub_domain: Option<&'a str>,
+
+  #[serde(rename = "RecordType")]
+  rtype: RecordType,
+
+  #[serde(rename = "Limit", skip_serializing_if = "Option::is_none")]
+  limit: Option<u64>,
+}
+
+impl<'a> DnspodListRecordsReq<'a> {
+  pub fn new(domain: &'a str) -> Self {
+    DnspodListRecordsReq {
+      domain,
+      sub_domain: None,
+      rtype: RecordType::TXT,
+      limit: None,
+    }
+  }
+
+  /// Only list records of `sub_domain`, relative to domain, for example, `_acme-challenge`
+  pub fn sub_domain(mut self, sub_domain: &'a str) -> Self {
+    self.sub_domain = Some(sub_domain);
+    self
+  }
+
+  /// Set page size, max is 3000, default is 100
+  pub fn limit(mut self, limit: u64) -> Self {
+    self.limit = Some(limit);
+    self
+  }
+}
+
+/// Request of [`crate::challenge::dns::dnspod::DnspodClient::list_domains`]
+#[derive(Debug, Serialize)]
+pub struct DnspodListDomainsReq<'a> {
+  #[serde(rename = "Keyword")]
+  keyword: &'a str,
+
+  #[serde(rename = "Limit", skip_serializing_if = "Option::is_none")]
+  limit: Option<u64>,
+}
+
+impl<'a> DnspodListDomainsReq<'a> {
+  /// Search domains containing `keyword`
+  pub fn new(keyword: &'a str) -> Self {
+    DnspodListDomainsReq {
+      keyword,
+      limit: None,
+    }
+  }
+
+  /// Set page size, max is 3000, default is 20
+  pub fn limit(mut self, limit: u64) -> Self {
+    self.limit = Some(limit);
+    self
+  }
+}
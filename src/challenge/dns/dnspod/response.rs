@@ -0,0 +1,101 @@
+use serde::Deserialize;
+
+use crate::errors::{PlainTextSnafu, Result};
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct DnspodRes<T> {
+  #[serde(rename = "Response")]
+  response: DnspodData<T>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum DnspodData<T> {
+  Failure(FailureData),
+  Success(T),
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct RecordIdData {
+  #[serde(rename = "RecordId")]
+  pub(crate) record_id: u64,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct EmptyData {}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct RecordListData {
+  #[serde(rename = "RecordList", default)]
+  pub(crate) records: Vec<DnspodRecord>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct DomainListData {
+  #[serde(rename = "DomainList", default)]
+  pub(crate) domains: Vec<DomainData>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct DomainData {
+  #[serde(rename = "Name")]
+  pub(crate) name: String,
+}
+
+/// DNS record returned by [`crate::challenge::dns::dnspod::DnspodClient::list_records`]
+#[derive(Debug, Clone, Deserialize)]
+pub struct DnspodRecord {
+  #[serde(rename = "RecordId")]
+  pub record_id: u64,
+
+  /// Name relative to domain, for example, `_acme-challenge`
+  #[serde(rename = "Name")]
+  pub name: String,
+
+  #[serde(rename = "Type")]
+  pub rtype: String,
+
+  #[serde(rename = "Value")]
+  pub value: String,
+
+  #[serde(rename = "TTL")]
+  pub ttl: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct FailureData {
+  #[serde(rename = "Error")]
+  error: ErrorData,
+}
+
+#[derive(Debug, Deserialize)]
+struct ErrorData {
+  #[serde(rename = "Code")]
+  code: String,
+
+  #[serde(rename = "Message")]
+  message: String,
+}
+
+impl<T> DnspodRes<T> {
+  pub fn unwrap_data(self) -> Result<T> {
+    match self.response {
+      DnspodData::Success(success) => Ok(success),
+      DnspodData::Failure(failure) => PlainTextSnafu {
+        message: format!(
+          "DNSPod Error: code: {}, message: {}",
+          failure.error.code, failure.error.message,
+        ),
+      }
+      .fail(),
+    }
+  }
+
+  /// Error code, if the request failed
+  pub fn error_code(&self) -> Option<&str> {
+    match &self.response {
+      DnspodData::Failure(failure) => Some(&failure.error.code),
+      DnspodData::Success(_) => None,
+    }
+  }
+}
@@ -0,0 +1,209 @@
+use std::{path::PathBuf, process::Stdio, time::Duration};
+
+use futures_util::future::BoxFuture;
+use serde::Serialize;
+use snafu::ResultExt;
+use tokio::{io::AsyncWriteExt, process::Command};
+
+use crate::{
+  challenge::dns::{
+    DnsChallengeClient, TxtRecord,
+    exec::{ExecMode, ExecOption},
+  },
+  errors::{IoOperationSnafu, PlainTextSnafu, Result},
+  util::json_serialize,
+};
+
+/// JSON object written to stdin in [`ExecMode::Json`]
+#[derive(Debug, Serialize)]
+struct ExecReq<'a> {
+  action: &'a str,
+  zone: &'a str,
+  fqdn: &'a str,
+  value: &'a str,
+}
+
+/// Client runs external program to present and cleanup TXT records
+#[derive(Debug)]
+pub struct ExecClient {
+  program: PathBuf,
+  args: Vec<String>,
+  mode: ExecMode,
+  timeout: Duration,
+}
+
+impl ExecClient {
+  /// Create client with option, see [`ExecOption`]
+  pub fn new(option: ExecOption) -> Self {
+    let ExecOption {
+      program,
+      args,
+      mode,
+      timeout,
+    } = option;
+    ExecClient {
+      program,
+      args,
+      mode,
+      timeout,
+    }
+  }
+
+  /// Create client from environment variable, see [`ExecOption::new_from_env`]
+  pub fn new_from_env() -> Result<Self> {
+    Ok(Self::new(ExecOption::new_from_env()?))
+  }
+
+  /// Run `present` action, the program should create TXT record with `value` at `fqdn`
+  pub async fn present(&self, zone: &str, fqdn: &str, value: &str) -> Result<()> {
+    self.run("present", zone, fqdn, value).await
+  }
+
+  /// Run `cleanup` action, the program should delete TXT record with `value` at `fqdn`
+  pub async fn cleanup(&self, zone: &str, fqdn: &str, value: &str) -> Result<()> {
+    self.run("cleanup", zone, fqdn, value).await
+  }
+
+  async fn run(&self, action: &str, zone: &str, fqdn: &str, value: &str) -> Result<()> {
+    let mut command = Command::new(&self.program);
+    command
+      .args(&self.args)
+      .arg(action)
+      .stdout(Stdio::null())
+      .stderr(Stdio::piped())
+      .kill_on_drop(true);
+    let stdin = match self.mode {
+      ExecMode::Args => {
+        command.arg(fqdn).arg(value).stdin(Stdio::null());
+        None
+      }
+      ExecMode::Json => {
+        command.stdin(Stdio::piped());
+        let req = ExecReq {
+          action,
+          zone,
+          fqdn,
+          value,
+        };
+        Some(json_serialize(&req)?)
+      }
+    };
+    let mut child = command.spawn().context(IoOperationSnafu)?;
+    let run = async {
+      if let (Some(input), Some(mut writer)) = (stdin, child.stdin.take()) {
+        writer
+          .write_all(input.as_bytes())
+          .await
+          .context(IoOperationSnafu)?;
+        // close stdin, so the program sees EOF
+        drop(writer);
+      }
+      child.wait_with_output().await.context(IoOperationSnafu)
+    };
+    // child is killed on drop when timeout
+    let output = match tokio::time::timeout(self.timeout, run).await {
+      Ok(output) => output?,
+      Err(_) => {
+        return PlainTextSnafu {
+          message: format!(
+            "Exec Error: {} {} timeout after {:?}",
+            self.program.display(),
+            action,
+            self.timeout
+          ),
+        }
+        .fail();
+      }
+    };
+    if output.status.success() {
+      return Ok(());
+    }
+    PlainTextSnafu {
+      message: format!(
+        "Exec Error: {} {} exited with {}, stderr: {}",
+        self.program.display(),
+        action,
+        output.status,
+        String::from_utf8_lossy(&output.stderr).trim()
+      ),
+    }
+    .fail()
+  }
+}
+
+impl DnsChallengeClient for ExecClient {
+  /// The program is assumed to manage every zone, so it should be the last client of solver
+  fn has_zone<'a>(&'a self, _zone: &'a str) -> BoxFuture<'a, Result<bool>> {
+    Box::pin(async move { Ok(true) })
+  }
+
+  fn create_txt<'a>(
+    &'a self,
+    zone: &'a str,
+    fqdn: &'a str,
+    value: &'a str,
+  ) -> BoxFuture<'a, Result<TxtRecord>> {
+    Box::pin(async move {
+      self.present(zone, fqdn, value).await?;
+      Ok(TxtRecord::new(zone, fqdn, value, ""))
+    })
+  }
+
+  fn delete_txt<'a>(&'a self, record: &'a TxtRecord) -> BoxFuture<'a, Result<()>> {
+    Box::pin(async move {
+      self
+        .cleanup(&record.zone, &record.fqdn, &record.value)
+        .await
+    })
+  }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+  use super::*;
+
+  fn sh_client(script: &str) -> ExecClient {
+    ExecClient::new(ExecOption::new("sh").arg("-c").arg(script).arg("sh"))
+  }
+
+  #[tokio::test]
+  async fn run_program() {
+    let client = sh_client(r#"[ "$1 $2 $3" = "present _acme-challenge.example.com token" ]"#);
+    client
+      .present("example.com", "_acme-challenge.example.com", "token")
+      .await
+      .unwrap();
+
+    let client = sh_client("echo \"no such zone $2\" >&2; exit 3");
+    let err = client
+      .cleanup("example.com", "_acme-challenge.example.com", "token")
+      .await
+      .unwrap_err();
+    assert!(err.to_string().contains("no such zone _acme-challenge"));
+
+    let client = ExecClient::new(
+      ExecOption::new("sh")
+        .arg("-c")
+        .arg("sleep 5")
+        .timeout(Duration::from_millis(100)),
+    );
+    let err = client.present("a", "b", "c").await.unwrap_err();
+    assert!(err.to_string().contains("timeout"));
+  }
+
+  #[tokio::test]
+  async fn json_stdin() {
+    let script = r#"[ "$1" = "cleanup" ] && grep -q '"fqdn":"_acme-challenge.example.com"'"#;
+    let client = ExecClient::new(
+      ExecOption::new("sh")
+        .arg("-c")
+        .arg(script)
+        .arg("sh")
+        .mode(ExecMode::Json),
+    );
+    client
+      .cleanup("example.com", "_acme-challenge.example.com", "token")
+      .await
+      .unwrap();
+  }
+}
@@ -0,0 +1,13 @@
+//! Generic DNS challenge which runs an external program to manage TXT records, similar to the
+//! `exec` provider of [lego](https://go-acme.github.io/lego/dns/exec/)
+//!
+//! The program is called as `program [args..] present <fqdn> <value>` and
+//! `program [args..] cleanup <fqdn> <value>`, or `program [args..] present` with a JSON object of
+//! `action`, `zone`, `fqdn` and `value` written to stdin in [`ExecMode::Json`]. Exit code `0`
+//! means success, stderr is reported in error otherwise.
+
+mod client;
+pub use client::ExecClient;
+
+mod option;
+pub use option::{ExecMode, ExecOption};
@@ -0,0 +1,101 @@
+use std::{path::PathBuf, time::Duration};
+
+use crate::{
+  errors::{PlainTextSnafu, Result},
+  util::env_single_var,
+};
+
+/// How to pass arguments of challenge to the program
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExecMode {
+  /// `program present <fqdn> <value>`
+  Args,
+  /// `program present`, with JSON object of `action`, `zone`, `fqdn` and `value` in stdin
+  Json,
+}
+
+/// Options for create an [`crate::challenge::dns::exec::ExecClient`] instance
+#[derive(Debug)]
+pub struct ExecOption {
+  pub(crate) program: PathBuf,
+  pub(crate) args: Vec<String>,
+  pub(crate) mode: ExecMode,
+  pub(crate) timeout: Duration,
+}
+
+impl ExecOption {
+  /// Create option with path of program, for example, `/usr/local/bin/update-dns.sh`
+  pub fn new(program: impl Into<PathBuf>) -> Self {
+    ExecOption {
+      program: program.into(),
+      args: vec![],
+      mode: ExecMode::Args,
+      timeout: Duration::from_secs(60),
+    }
+  }
+
+  /// Create option from environment variable, variable name for program is __EXEC_PATH__ or
+  /// __EASY_ACME_EXEC_PATH__, for mode (optional, `args` or `json`) is __EXEC_MODE__ or
+  /// __EASY_ACME_EXEC_MODE__, and for timeout in seconds (optional) is __EXEC_TIMEOUT__ or
+  /// __EASY_ACME_EXEC_TIMEOUT__
+  pub fn new_from_env() -> Result<Self> {
+    let mut option = Self::new(Self::env_path()?);
+    if let Ok(mode) = Self::env_mode() {
+      option = match mode.to_lowercase().as_str() {
+        "args" | "" => option.mode(ExecMode::Args),
+        "json" => option.mode(ExecMode::Json),
+        _ => {
+          return PlainTextSnafu {
+            message: format!("Exec Error: unsupported mode {}", mode),
+          }
+          .fail();
+        }
+      };
+    }
+    if let Ok(timeout) = Self::env_timeout() {
+      let Ok(secs) = timeout.parse() else {
+        return PlainTextSnafu {
+          message: format!("Exec Error: invalid timeout {}", timeout),
+        }
+        .fail();
+      };
+      option = option.timeout(Duration::from_secs(secs));
+    }
+    Ok(option)
+  }
+
+  /// Append argument passed before the action, for example, `-c` and script of `sh`
+  pub fn arg(mut self, arg: impl Into<String>) -> Self {
+    self.args.push(arg.into());
+    self
+  }
+
+  /// Set mode of passing arguments, default is [`ExecMode::Args`]
+  pub fn mode(mut self, mode: ExecMode) -> Self {
+    self.mode = mode;
+    self
+  }
+
+  /// Set timeout of each run, the program is killed when timeout, default is 60 seconds
+  pub fn timeout(mut self, timeout: Duration) -> Self {
+    self.timeout = timeout;
+    self
+  }
+}
+
+impl ExecOption {
+  #[inline]
+  pub(crate) fn env_path() -> Result<String> {
+    env_single_var(["EXEC_PATH", "EASY_ACME_EXEC_PATH"])
+  }
+
+  #[inline]
+  pub(crate) fn env_mode() -> Result<String> {
+    env_single_var(["EXEC_MODE", "EASY_ACME_EXEC_MODE"])
+  }
+
+  #[inline]
+  pub(crate) fn env_timeout() -> Result<String> {
+    env_single_var(["EXEC_TIMEOUT", "EASY_ACME_EXEC_TIMEOUT"])
+  }
+}
@@ -0,0 +1,261 @@
+use futures_util::future::BoxFuture;
+use http::{HeaderMap, StatusCode, header::AUTHORIZATION};
+use reqwest::{Client, ClientBuilder, Response};
+use snafu::ResultExt;
+
+use crate::{
+  challenge::dns::{
+    DnsChallengeClient, TxtRecord,
+    gandi::{GandiOption, GandiRecordSet, response::FailureData},
+    relative_name,
+  },
+  errors::{PlainTextSnafu, ReqwestClientSnafu, Result},
+  util::str_to_header_value,
+};
+
+const API_ENDPOINT: &str = "https://api.gandi.net/v5/livedns";
+
+#[derive(Debug)]
+pub struct GandiClient {
+  ttl: u64,
+  endpoint: String,
+  client: Client,
+}
+
+impl GandiClient {
+  pub fn new(option: GandiOption) -> Result<Self> {
+    let GandiOption {
+      token,
+      ttl,
+      proxy,
+      timeout,
+      endpoint,
+    } = option;
+    let mut client = ClientBuilder::new();
+    if let Some(timeout) = timeout {
+      client = client.timeout(timeout);
+    }
+    if let Some(proxy) = proxy {
+      let proxy = reqwest::Proxy::all(proxy).context(ReqwestClientSnafu)?;
+      client = client.proxy(proxy);
+    }
+    let mut headers = HeaderMap::new();
+    headers.insert(
+      AUTHORIZATION,
+      str_to_header_value(format!("Bearer {}", token))?,
+    );
+    client = client.default_headers(headers);
+    let client = client.build().context(ReqwestClientSnafu)?;
+    Ok(Self {
+      ttl,
+      endpoint: endpoint.unwrap_or_else(|| API_ENDPOINT.to_string()),
+      client,
+    })
+  }
+
+  pub fn new_from_env() -> Result<Self> {
+    Self::new(GandiOption::new_from_env()?)
+  }
+
+  /// Whether `domain` is managed by LiveDNS of the account
+  pub async fn has_domain(&self, domain: &str) -> Result<bool> {
+    let url = format!("{}/domains/{}", self.endpoint, domain);
+    let res = self
+      .client
+      .get(url)
+      .send()
+      .await
+      .context(ReqwestClientSnafu)?;
+    match res.status() {
+      StatusCode::NOT_FOUND | StatusCode::FORBIDDEN => Ok(false),
+      status if status.is_success() => Ok(true),
+      _ => failure(res).await,
+    }
+  }
+
+  /// TXT record set `name` relative to `domain`, `None` if it does not exist
+  pub async fn record_set(&self, domain: &str, name: &str) -> Result<Option<GandiRecordSet>> {
+    let url = format!("{}/domains/{}/records/{}/TXT", self.endpoint, domain, name);
+    let res = self
+      .client
+      .get(url)
+      .send()
+      .await
+      .context(ReqwestClientSnafu)?;
+    match res.status() {
+      StatusCode::NOT_FOUND => Ok(None),
+      status if status.is_success() => Ok(Some(res.json().await.context(ReqwestClientSnafu)?)),
+      _ => failure(res).await,
+    }
+  }
+
+  /// Replace values of TXT record set `name`, the set is created if it does not exist
+  pub async fn put_record_set(&self, domain: &str, name: &str, values: &[String]) -> Result<()> {
+    let url = format!("{}/domains/{}/records/{}/TXT", self.endpoint, domain, name);
+    let set = GandiRecordSet {
+      rrset_name: name.to_string(),
+      rrset_ttl: Some(self.ttl),
+      rrset_values: values.iter().map(|v| format!("\"{}\"", v)).collect(),
+    };
+    let res = self
+      .client
+      .put(url)
+      .json(&set)
+      .send()
+      .await
+      .context(ReqwestClientSnafu)?;
+    if res.status().is_success() {
+      return Ok(());
+    }
+    failure(res).await
+  }
+
+  /// Delete TXT record set `name`
+  pub async fn delete_record_set(&self, domain: &str, name: &str) -> Result<()> {
+    let url = format!("{}/domains/{}/records/{}/TXT", self.endpoint, domain, name);
+    let res = self
+      .client
+      .delete(url)
+      .send()
+      .await
+      .context(ReqwestClientSnafu)?;
+    if res.status().is_success() || res.status() == StatusCode::NOT_FOUND {
+      return Ok(());
+    }
+    failure(res).await
+  }
+}
+
+impl DnsChallengeClient for GandiClient {
+  fn has_zone<'a>(&'a self, zone: &'a str) -> BoxFuture<'a, Result<bool>> {
+    Box::pin(async move { self.has_domain(zone).await })
+  }
+
+  fn create_txt<'a>(
+    &'a self,
+    zone: &'a str,
+    fqdn: &'a str,
+    value: &'a str,
+  ) -> BoxFuture<'a, Result<TxtRecord>> {
+    Box::pin(async move {
+      let name = relative_name(fqdn, zone);
+      let mut values = match self.record_set(zone, name).await? {
+        Some(set) => set.values(),
+        None => vec![],
+      };
+      if !values.iter().any(|v| v == value) {
+        values.push(value.to_string());
+        self.put_record_set(zone, name, &values).await?;
+      }
+      Ok(TxtRecord::new(zone, fqdn, value, name))
+    })
+  }
+
+  fn delete_txt<'a>(&'a self, record: &'a TxtRecord) -> BoxFuture<'a, Result<()>> {
+    Box::pin(async move {
+      let Some(set) = self.record_set(&record.zone, &record.id).await? else {
+        return Ok(());
+      };
+      let values: Vec<String> = set
+        .values()
+        .into_iter()
+        .filter(|v| *v != record.value)
+        .collect();
+      if values.is_empty() {
+        self.delete_record_set(&record.zone, &record.id).await
+      } else if values.len() < set.rrset_values.len() {
+        self.put_record_set(&record.zone, &record.id, &values).await
+      } else {
+        Ok(())
+      }
+    })
+  }
+}
+
+async fn failure<T>(res: Response) -> Result<T> {
+  let status = res.status();
+  let text = res.text().await.context(ReqwestClientSnafu)?;
+  let message = match serde_json::from_str::<FailureData>(&text) {
+    Ok(failure) => match failure.cause {
+      Some(cause) => format!("{}, {}", cause, failure.message),
+      None => failure.message,
+    },
+    Err(_) => text,
+  };
+  PlainTextSnafu {
+    message: format!("Gandi Error: {}, {}", status, message),
+  }
+  .fail()
+}
+
+#[cfg(test)]
+mod tests {
+  use std::sync::{Arc, Mutex};
+
+  use super::*;
+  use crate::test_util::{StubResponse, request_body, stub_server};
+
+  /// Stub Gandi LiveDNS api, `example.com` has TXT record set `_acme-challenge`, which is replaced
+  /// by PUT and removed by DELETE, changes are recorded
+  async fn stub_gandi(changes: Arc<Mutex<Vec<String>>>) -> String {
+    let mut values = Some(r#"["\"old\""]"#.to_string());
+    stub_server(move |req| {
+      assert!(req.contains("authorization: Bearer token"));
+      let line = req.lines().next().unwrap();
+      let line = line.strip_suffix(" HTTP/1.1").unwrap();
+      let not_found = || {
+        let body = r#"{"code":404,"message":"Unknown domain","object":"dns-domain","cause":"Not Found"}"#;
+        StubResponse::new("404 Not Found", body)
+      };
+      match line {
+        "GET /domains/example.com" => StubResponse::ok(r#"{"fqdn":"example.com"}"#),
+        "GET /domains/example.com/records/_acme-challenge/TXT" => match &values {
+          Some(values) => StubResponse::ok(format!(
+            r#"{{"rrset_name":"_acme-challenge","rrset_type":"TXT","rrset_ttl":300,"rrset_values":{}}}"#,
+            values
+          )),
+          None => not_found(),
+        },
+        "PUT /domains/example.com/records/_acme-challenge/TXT" => {
+          let body: serde_json::Value = serde_json::from_str(request_body(req)).unwrap();
+          values = Some(body["rrset_values"].to_string());
+          changes.lock().unwrap().push(format!("PUT {}", body["rrset_values"]));
+          StubResponse::new("201 Created", r#"{"message":"DNS Record Created"}"#)
+        }
+        "DELETE /domains/example.com/records/_acme-challenge/TXT" => {
+          values = None;
+          changes.lock().unwrap().push("DELETE".to_string());
+          StubResponse::new("204 No Content", "")
+        }
+        _ => not_found(),
+      }
+    })
+    .await
+  }
+
+  #[tokio::test]
+  async fn add_and_remove_value() {
+    let changes = Arc::new(Mutex::new(vec![]));
+    let endpoint = stub_gandi(changes.clone()).await;
+    let client = GandiClient::new(GandiOption::new("token").endpoint(endpoint)).unwrap();
+    assert!(client.has_zone("example.com").await.unwrap());
+    assert!(!client.has_zone("example.org").await.unwrap());
+
+    let fqdn = "_acme-challenge.example.com";
+    let record = client.create_txt("example.com", fqdn, "new").await.unwrap();
+    assert_eq!(record.id, "_acme-challenge");
+    client.delete_txt(&record).await.unwrap();
+    let old = TxtRecord::new("example.com", fqdn, "old", "_acme-challenge");
+    client.delete_txt(&old).await.unwrap();
+
+    let changes = changes.lock().unwrap();
+    assert_eq!(
+      *changes,
+      [
+        r#"PUT ["\"old\"","\"new\""]"#,
+        r#"PUT ["\"old\""]"#,
+        "DELETE"
+      ]
+    );
+  }
+}
@@ -0,0 +1,12 @@
+//! Implement of [Gandi LiveDNS](https://www.gandi.net/) challenge
+//!
+//! Link: <https://api.gandi.net/docs/livedns/>
+
+mod client;
+pub use client::GandiClient;
+
+mod response;
+pub use response::GandiRecordSet;
+
+mod option;
+pub use option::GandiOption;
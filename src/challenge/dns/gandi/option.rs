@@ -0,0 +1,68 @@
+use std::time::Duration;
+
+use crate::{errors::Result, util::env_single_var};
+
+/// Options for create a [`crate::challenge::dns::gandi::GandiClient`] instance
+#[derive(Debug)]
+pub struct GandiOption {
+  pub(crate) token: String,
+  pub(crate) ttl: u64,
+  pub(crate) proxy: Option<String>,
+  pub(crate) timeout: Option<Duration>,
+  pub(crate) endpoint: Option<String>,
+}
+
+impl GandiOption {
+  /// Create option with personal access token, this token must have permission to
+  /// __Manage domain name technical configurations__
+  pub fn new(token: impl Into<String>) -> Self {
+    GandiOption {
+      token: token.into(),
+      ttl: 300,
+      proxy: None,
+      timeout: None,
+      endpoint: None,
+    }
+  }
+
+  /// Create option from environment variable, variable name for token is
+  /// __GANDIV5_PERSONAL_ACCESS_TOKEN__, __GANDI_LIVEDNS_TOKEN__ or __EASY_ACME_GANDI_TOKEN__
+  pub fn new_from_env() -> Result<Self> {
+    Ok(Self::new(Self::env_auth_token()?))
+  }
+
+  /// Set TTL of TXT record set, default is 300 seconds, which is the minimum of Gandi
+  pub fn ttl(mut self, ttl: u64) -> Self {
+    self.ttl = ttl;
+    self
+  }
+
+  /// Set proxy, for example, `https://127.0.0.1:8080`, `socks5://127.0.0.1:9000`, default is `None`
+  pub fn proxy(mut self, proxy: impl Into<String>) -> Self {
+    self.proxy = Some(proxy.into());
+    self
+  }
+
+  /// Set timeout, for example, `Duration::from_secs(5)`, default is `None`
+  pub fn timeout(mut self, timeout: Duration) -> Self {
+    self.timeout = Some(timeout);
+    self
+  }
+
+  /// Set api endpoint, default is `https://api.gandi.net/v5/livedns`
+  pub fn endpoint(mut self, endpoint: impl Into<String>) -> Self {
+    self.endpoint = Some(endpoint.into());
+    self
+  }
+}
+
+impl GandiOption {
+  #[inline]
+  pub(crate) fn env_auth_token() -> Result<String> {
+    env_single_var([
+      "GANDIV5_PERSONAL_ACCESS_TOKEN",
+      "GANDI_LIVEDNS_TOKEN",
+      "EASY_ACME_GANDI_TOKEN",
+    ])
+  }
+}
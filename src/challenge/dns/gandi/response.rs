@@ -0,0 +1,30 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct FailureData {
+  pub(crate) message: String,
+
+  pub(crate) cause: Option<String>,
+}
+
+/// TXT record set of [`crate::challenge::dns::gandi::GandiClient::record_set`], values are quoted
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GandiRecordSet {
+  #[serde(skip_serializing)]
+  pub rrset_name: String,
+
+  pub rrset_ttl: Option<u64>,
+
+  pub rrset_values: Vec<String>,
+}
+
+impl GandiRecordSet {
+  /// TXT values without quotes
+  pub fn values(&self) -> Vec<String> {
+    self
+      .rrset_values
+      .iter()
+      .map(|v| v.trim_matches('"').to_string())
+      .collect()
+  }
+}
@@ -0,0 +1,196 @@
+use std::{
+  path::Path,
+  sync::Mutex,
+  time::{Duration, Instant},
+};
+
+use jiff::Timestamp;
+use reqwest::Client;
+use rsa::{
+  RsaPrivateKey,
+  pkcs1v15::SigningKey,
+  pkcs8::DecodePrivateKey,
+  signature::{SignatureEncoding, Signer},
+};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use snafu::ResultExt;
+
+use crate::{
+  errors::{
+    IoOperationSnafu, PlainTextSnafu, ReqwestClientSnafu, Result, RsaPrivateKeySnafu,
+    RsaSignatureSnafu, SerializeJsonSnafu,
+  },
+  util::{base64_url_unpadded_str, json_serialize},
+};
+
+const DNS_SCOPE: &str = "https://www.googleapis.com/auth/ndev.clouddns.readwrite";
+const GRANT_TYPE: &str = "urn:ietf:params:oauth:grant-type:jwt-bearer";
+const TOKEN_LIFETIME: u64 = 3600;
+/// Token is refreshed when it expires within this time
+const REFRESH_MARGIN: Duration = Duration::from_secs(60);
+
+/// Service account key in JSON format, created in __IAM & Admin > Service Accounts__
+#[derive(Debug, Clone, Deserialize)]
+pub struct GoogleServiceAccount {
+  pub project_id: Option<String>,
+
+  pub private_key_id: Option<String>,
+
+  pub private_key: String,
+
+  pub client_email: String,
+
+  pub token_uri: Option<String>,
+}
+
+impl GoogleServiceAccount {
+  /// Parse service account key from JSON string
+  pub fn from_json(json: &str) -> Result<Self> {
+    serde_json::from_str(json).context(SerializeJsonSnafu)
+  }
+
+  /// Load service account key from JSON file
+  pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+    let content = std::fs::read_to_string(path).context(IoOperationSnafu)?;
+    Self::from_json(&content)
+  }
+}
+
+#[derive(Debug, Serialize)]
+struct JwtHeader<'a> {
+  alg: &'a str,
+
+  typ: &'a str,
+
+  #[serde(skip_serializing_if = "Option::is_none")]
+  kid: Option<&'a str>,
+}
+
+#[derive(Debug, Serialize)]
+struct JwtClaims<'a> {
+  iss: &'a str,
+  scope: &'a str,
+  aud: &'a str,
+  iat: u64,
+  exp: u64,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum TokenRes {
+  Success {
+    access_token: String,
+    expires_in: u64,
+  },
+  Failure {
+    error: String,
+    error_description: Option<String>,
+  },
+}
+
+/// Exchanges signed JWT for OAuth access token, and caches the token until it is about to expire
+pub(crate) struct TokenProvider {
+  account: GoogleServiceAccount,
+  signing_key: SigningKey<Sha256>,
+  token_endpoint: String,
+  cached: Mutex<Option<(String, Instant)>>,
+}
+
+impl std::fmt::Debug for TokenProvider {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("TokenProvider")
+      .field("client_email", &self.account.client_email)
+      .field("token_endpoint", &self.token_endpoint)
+      .finish()
+  }
+}
+
+impl TokenProvider {
+  pub(crate) fn new(account: GoogleServiceAccount, token_endpoint: String) -> Result<Self> {
+    let private_key =
+      RsaPrivateKey::from_pkcs8_pem(&account.private_key).context(RsaPrivateKeySnafu)?;
+    Ok(TokenProvider {
+      account,
+      signing_key: SigningKey::new(private_key),
+      token_endpoint,
+      cached: Mutex::new(None),
+    })
+  }
+
+  /// Cached access token, or a new one if it is absent or about to expire
+  pub(crate) async fn token(&self, client: &Client) -> Result<String> {
+    if let Some((token, expires_at)) = &*self.lock()
+      && Instant::now() + REFRESH_MARGIN < *expires_at
+    {
+      return Ok(token.clone());
+    }
+    let assertion = self.assertion()?;
+    let form = [("grant_type", GRANT_TYPE), ("assertion", &assertion)];
+    let res = client
+      .post(&self.token_endpoint)
+      .form(&form)
+      .send()
+      .await
+      .context(ReqwestClientSnafu)?
+      .json::<TokenRes>()
+      .await
+      .context(ReqwestClientSnafu)?;
+    match res {
+      TokenRes::Success {
+        access_token,
+        expires_in,
+      } => {
+        let expires_at = Instant::now() + Duration::from_secs(expires_in);
+        *self.lock() = Some((access_token.clone(), expires_at));
+        Ok(access_token)
+      }
+      TokenRes::Failure {
+        error,
+        error_description,
+      } => PlainTextSnafu {
+        message: format!(
+          "GoogleCloud Error: token exchange failed, {}, {}",
+          error,
+          error_description.unwrap_or_default()
+        ),
+      }
+      .fail(),
+    }
+  }
+
+  /// JWT signed with RS256, see <https://developers.google.com/identity/protocols/oauth2/service-account#authorizingrequests>
+  pub(crate) fn assertion(&self) -> Result<String> {
+    let iat = Timestamp::now().as_second().max(0) as u64;
+    let header = JwtHeader {
+      alg: "RS256",
+      typ: "JWT",
+      kid: self.account.private_key_id.as_deref(),
+    };
+    let claims = JwtClaims {
+      iss: &self.account.client_email,
+      scope: DNS_SCOPE,
+      aud: &self.token_endpoint,
+      iat,
+      exp: iat + TOKEN_LIFETIME,
+    };
+    let input = format!(
+      "{}.{}",
+      base64_url_unpadded_str(json_serialize(&header)?),
+      base64_url_unpadded_str(json_serialize(&claims)?)
+    );
+    let signature = self
+      .signing_key
+      .try_sign(input.as_bytes())
+      .context(RsaSignatureSnafu)?;
+    Ok(format!(
+      "{}.{}",
+      input,
+      base64_url_unpadded_str(signature.to_bytes())
+    ))
+  }
+
+  fn lock(&self) -> std::sync::MutexGuard<'_, Option<(String, Instant)>> {
+    self.cached.lock().unwrap_or_else(|err| err.into_inner())
+  }
+}
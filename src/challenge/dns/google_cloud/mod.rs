@@ -0,0 +1,18 @@
+//! Implement of [Google Cloud DNS](https://cloud.google.com/dns) challenge
+//!
+//! Link: <https://cloud.google.com/dns/docs/reference/rest/v1/changes/create>
+
+mod auth;
+pub use auth::GoogleServiceAccount;
+
+mod client;
+pub use client::GoogleCloudClient;
+
+mod option;
+pub use option::GoogleCloudOption;
+
+mod request;
+pub use request::GoogleCloudChangeReq;
+
+mod response;
+pub use response::{GoogleCloudChange, GoogleCloudManagedZone, GoogleCloudRecordSet};
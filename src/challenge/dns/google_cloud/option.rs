@@ -0,0 +1,102 @@
+use std::{path::Path, time::Duration};
+
+use crate::{
+  challenge::dns::google_cloud::GoogleServiceAccount, errors::Result, util::env_single_var,
+};
+
+/// Options for create a [`crate::challenge::dns::google_cloud::GoogleCloudClient`] instance
+#[derive(Debug)]
+pub struct GoogleCloudOption {
+  pub(crate) account: GoogleServiceAccount,
+  pub(crate) project_id: Option<String>,
+  pub(crate) token_endpoint: Option<String>,
+  pub(crate) api_endpoint: String,
+  pub(crate) ttl: u64,
+  pub(crate) proxy: Option<String>,
+  pub(crate) timeout: Option<Duration>,
+}
+
+impl GoogleCloudOption {
+  /// Create option with service account, the account must have role __DNS Administrator__
+  pub fn new(account: GoogleServiceAccount) -> Self {
+    GoogleCloudOption {
+      account,
+      project_id: None,
+      token_endpoint: None,
+      api_endpoint: "https://dns.googleapis.com/dns/v1".to_string(),
+      ttl: 60,
+      proxy: None,
+      timeout: None,
+    }
+  }
+
+  /// Create option with service account key file
+  pub fn new_from_file(path: impl AsRef<Path>) -> Result<Self> {
+    Ok(Self::new(GoogleServiceAccount::from_file(path)?))
+  }
+
+  /// Create option from environment variable, variable name for path of service account key file
+  /// is __GCE_SERVICE_ACCOUNT_FILE__, __GOOGLE_APPLICATION_CREDENTIALS__ or
+  /// __EASY_ACME_GCLOUD_SERVICE_ACCOUNT_FILE__, and for optional project is __GCE_PROJECT__ or
+  /// __EASY_ACME_GCLOUD_PROJECT__
+  pub fn new_from_env() -> Result<Self> {
+    let option = Self::new_from_file(Self::env_account_file()?)?;
+    match Self::env_project_id() {
+      Ok(project_id) => Ok(option.project_id(project_id)),
+      Err(_) => Ok(option),
+    }
+  }
+
+  /// Set project of managed zones, default is `project_id` of service account
+  pub fn project_id(mut self, project_id: impl Into<String>) -> Self {
+    self.project_id = Some(project_id.into());
+    self
+  }
+
+  /// Set OAuth token endpoint, default is `token_uri` of service account, or
+  /// `https://oauth2.googleapis.com/token`
+  pub fn token_endpoint(mut self, endpoint: impl Into<String>) -> Self {
+    self.token_endpoint = Some(endpoint.into());
+    self
+  }
+
+  /// Set api endpoint, default is `https://dns.googleapis.com/dns/v1`
+  pub fn api_endpoint(mut self, endpoint: impl Into<String>) -> Self {
+    self.api_endpoint = endpoint.into();
+    self
+  }
+
+  /// Set TTL of TXT record, default is 60 seconds
+  pub fn ttl(mut self, ttl: u64) -> Self {
+    self.ttl = ttl;
+    self
+  }
+
+  /// Set proxy, for example, `https://127.0.0.1:8080`, `socks5://127.0.0.1:9000`, default is `None`
+  pub fn proxy(mut self, proxy: impl Into<String>) -> Self {
+    self.proxy = Some(proxy.into());
+    self
+  }
+
+  /// Set timeout, for example, `Duration::from_secs(5)`, default is `None`
+  pub fn timeout(mut self, timeout: Duration) -> Self {
+    self.timeout = Some(timeout);
+    self
+  }
+}
+
+impl GoogleCloudOption {
+  #[inline]
+  pub(crate) fn env_account_file() -> Result<String> {
+    env_single_var([
+      "GCE_SERVICE_ACCOUNT_FILE",
+      "GOOGLE_APPLICATION_CREDENTIALS",
+      "EASY_ACME_GCLOUD_SERVICE_ACCOUNT_FILE",
+    ])
+  }
+
+  #[inline]
+  pub(crate) fn env_project_id() -> Result<String> {
+    env_single_var(["GCE_PROJECT", "EASY_ACME_GCLOUD_PROJECT"])
+  }
+}
@@ -0,0 +1,38 @@
+use serde::Serialize;
+
+use crate::challenge::dns::google_cloud::GoogleCloudRecordSet;
+
+/// Request of [`crate::challenge::dns::google_cloud::GoogleCloudClient::create_change`], record
+/// sets in `deletions` must match the current ones exactly
+#[derive(Debug, Serialize)]
+pub struct GoogleCloudChangeReq<'a> {
+  #[serde(skip_serializing)]
+  pub(crate) managed_zone: &'a str,
+
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  additions: Vec<GoogleCloudRecordSet>,
+
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  deletions: Vec<GoogleCloudRecordSet>,
+}
+
+impl<'a> GoogleCloudChangeReq<'a> {
+  /// `managed_zone` is the name of zone, for example, `example-com`
+  pub fn new(managed_zone: &'a str) -> Self {
+    GoogleCloudChangeReq {
+      managed_zone,
+      additions: vec![],
+      deletions: vec![],
+    }
+  }
+
+  pub fn addition(mut self, record_set: GoogleCloudRecordSet) -> Self {
+    self.additions.push(record_set);
+    self
+  }
+
+  pub fn deletion(mut self, record_set: GoogleCloudRecordSet) -> Self {
+    self.deletions.push(record_set);
+    self
+  }
+}
@@ -0,0 +1,101 @@
+use serde::{Deserialize, Serialize};
+
+use crate::errors::{PlainTextSnafu, Result};
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub(crate) enum GoogleCloudRes<T> {
+  Failure { error: ErrorData },
+  Success(T),
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct ErrorData {
+  code: u16,
+  message: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ManagedZoneListData {
+  #[serde(default)]
+  pub(crate) managed_zones: Vec<GoogleCloudManagedZone>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct RecordSetListData {
+  #[serde(default)]
+  pub(crate) rrsets: Vec<GoogleCloudRecordSet>,
+}
+
+/// Managed zone returned by [`crate::challenge::dns::google_cloud::GoogleCloudClient::list_managed_zones`]
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GoogleCloudManagedZone {
+  /// Name of zone, for example, `example-com`
+  pub name: String,
+
+  /// Domain name with trailing dot, for example, `example.com.`
+  pub dns_name: String,
+
+  /// `public` or `private`
+  pub visibility: Option<String>,
+}
+
+/// Resource record set, TXT values in `rrdatas` are quoted
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GoogleCloudRecordSet {
+  pub name: String,
+
+  #[serde(rename = "type")]
+  pub rtype: String,
+
+  pub ttl: u64,
+
+  pub rrdatas: Vec<String>,
+}
+
+impl GoogleCloudRecordSet {
+  /// TXT record set of `fqdn` with unquoted `values`
+  pub fn txt(fqdn: &str, ttl: u64, values: &[String]) -> Self {
+    GoogleCloudRecordSet {
+      name: format!("{}.", fqdn.trim_end_matches('.')),
+      rtype: "TXT".to_string(),
+      ttl,
+      rrdatas: values.iter().map(|v| format!("\"{}\"", v)).collect(),
+    }
+  }
+
+  /// TXT values without quotes
+  pub fn values(&self) -> Vec<String> {
+    self
+      .rrdatas
+      .iter()
+      .map(|v| v.trim_matches('"').to_string())
+      .collect()
+  }
+}
+
+/// Change returned by [`crate::challenge::dns::google_cloud::GoogleCloudClient::create_change`]
+#[derive(Debug, Clone, Deserialize)]
+pub struct GoogleCloudChange {
+  pub id: String,
+
+  /// `pending` or `done`
+  pub status: String,
+}
+
+impl<T> GoogleCloudRes<T> {
+  pub fn unwrap_data(self) -> Result<T> {
+    match self {
+      GoogleCloudRes::Success(data) => Ok(data),
+      GoogleCloudRes::Failure { error } => PlainTextSnafu {
+        message: format!(
+          "GoogleCloud Error: code: {}, message: {}",
+          error.code, error.message
+        ),
+      }
+      .fail(),
+    }
+  }
+}
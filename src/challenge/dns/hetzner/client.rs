@@ -0,0 +1,266 @@
+use futures_util::future::BoxFuture;
+use http::{HeaderMap, HeaderName, Method};
+use reqwest::{Client, ClientBuilder};
+use serde::{Serialize, de::DeserializeOwned};
+use snafu::ResultExt;
+
+use crate::{
+  challenge::dns::{
+    DnsChallengeClient, TxtRecord,
+    hetzner::{
+      HetznerCreateRecordReq, HetznerOption, HetznerRecord, HetznerZone,
+      response::{HetznerRes, RecordData, RecordListData, ZoneListData},
+    },
+    relative_name,
+  },
+  errors::{PlainTextSnafu, ReqwestClientSnafu, Result},
+  util::str_to_header_value,
+};
+
+const API_ENDPOINT: &str = "https://dns.hetzner.com/api/v1";
+
+#[derive(Debug)]
+pub struct HetznerClient {
+  client: Client,
+  endpoint: String,
+}
+
+impl HetznerClient {
+  pub fn new(option: HetznerOption) -> Result<Self> {
+    let HetznerOption {
+      token,
+      proxy,
+      timeout,
+      endpoint,
+    } = option;
+    let mut client = ClientBuilder::new();
+    if let Some(timeout) = timeout {
+      client = client.timeout(timeout);
+    }
+    if let Some(proxy) = proxy {
+      let proxy = reqwest::Proxy::all(proxy).context(ReqwestClientSnafu)?;
+      client = client.proxy(proxy);
+    }
+    let mut headers = HeaderMap::new();
+    headers.insert(
+      HeaderName::from_static("auth-api-token"),
+      str_to_header_value(token)?,
+    );
+    client = client.default_headers(headers);
+    let client = client.build().context(ReqwestClientSnafu)?;
+    Ok(Self {
+      client,
+      endpoint: endpoint.unwrap_or_else(|| API_ENDPOINT.to_string()),
+    })
+  }
+
+  pub fn new_from_env() -> Result<Self> {
+    Self::new(HetznerOption::new_from_env()?)
+  }
+
+  pub async fn create_record(&self, req: HetznerCreateRecordReq<'_>) -> Result<String> {
+    let url = format!("{}/records", self.endpoint);
+    let data: RecordData = self.exec_request(Method::POST, &url, Some(&req)).await?;
+    Ok(data.record.id)
+  }
+
+  pub async fn delete_record(&self, record_id: &str) -> Result<()> {
+    let url = format!("{}/records/{}", self.endpoint, record_id);
+    let _: serde_json::Value = self.exec_request(Method::DELETE, &url, None::<&()>).await?;
+    Ok(())
+  }
+
+  /// List all DNS records of zone
+  pub async fn list_records(&self, zone_id: &str) -> Result<Vec<HetznerRecord>> {
+    let url = format!("{}/records", self.endpoint);
+    let query = [("zone_id", zone_id)];
+    let data: RecordListData = self.exec_request(Method::GET, &url, Some(&query)).await?;
+    Ok(data.records)
+  }
+
+  /// Append value to the TXT records of `req`'s name, the existing record is reused if it has the
+  /// same value
+  pub async fn append_record(&self, req: HetznerCreateRecordReq<'_>) -> Result<String> {
+    let records = self.list_records(req.zone_id).await?;
+    let existed = records
+      .into_iter()
+      .find(|r| r.rtype == "TXT" && r.name.eq_ignore_ascii_case(req.name) && r.value == req.value);
+    if let Some(record) = existed {
+      return Ok(record.id);
+    }
+    self.create_record(req).await
+  }
+
+  /// List zones with `name`, for example, `example.com`
+  pub async fn list_zones(&self, name: &str) -> Result<Vec<HetznerZone>> {
+    let url = format!("{}/zones", self.endpoint);
+    let query = [("name", name)];
+    let res: HetznerRes<ZoneListData> = self.send_request(Method::GET, &url, Some(&query)).await?;
+    match res {
+      // searching a zone not in account responds with 404
+      HetznerRes::Failure { error } if error.code == 404 => Ok(vec![]),
+      res => Ok(res.unwrap_data()?.zones),
+    }
+  }
+
+  /// Find id of zone by name, for example, `example.com`
+  pub async fn zone_id(&self, zone: &str) -> Result<Option<String>> {
+    let zones = self.list_zones(zone).await?;
+    let zone_id = zones
+      .into_iter()
+      .find(|z| z.name.eq_ignore_ascii_case(zone))
+      .map(|z| z.id);
+    Ok(zone_id)
+  }
+
+  async fn require_zone_id(&self, zone: &str) -> Result<String> {
+    match self.zone_id(zone).await? {
+      Some(zone_id) => Ok(zone_id),
+      None => PlainTextSnafu {
+        message: format!("Hetzner Error: zone {} not found", zone),
+      }
+      .fail(),
+    }
+  }
+}
+
+impl DnsChallengeClient for HetznerClient {
+  fn has_zone<'a>(&'a self, zone: &'a str) -> BoxFuture<'a, Result<bool>> {
+    Box::pin(async move { Ok(self.zone_id(zone).await?.is_some()) })
+  }
+
+  fn create_txt<'a>(
+    &'a self,
+    zone: &'a str,
+    fqdn: &'a str,
+    value: &'a str,
+  ) -> BoxFuture<'a, Result<TxtRecord>> {
+    Box::pin(async move {
+      let zone_id = self.require_zone_id(zone).await?;
+      let req = HetznerCreateRecordReq::new(&zone_id, relative_name(fqdn, zone), value).ttl(60);
+      let record_id = self.append_record(req).await?;
+      Ok(TxtRecord::new(zone, fqdn, value, record_id))
+    })
+  }
+
+  fn delete_txt<'a>(&'a self, record: &'a TxtRecord) -> BoxFuture<'a, Result<()>> {
+    Box::pin(async move { self.delete_record(&record.id).await })
+  }
+}
+
+impl HetznerClient {
+  async fn exec_request<R>(
+    &self,
+    method: Method,
+    url: &str,
+    req: Option<&impl Serialize>,
+  ) -> Result<R>
+  where
+    R: DeserializeOwned,
+  {
+    self.send_request(method, url, req).await?.unwrap_data()
+  }
+
+  async fn send_request<R>(
+    &self,
+    method: Method,
+    url: &str,
+    req: Option<&impl Serialize>,
+  ) -> Result<HetznerRes<R>>
+  where
+    R: DeserializeOwned,
+  {
+    let mut builder = self.client.request(method.clone(), url);
+    if let Some(req) = req {
+      builder = if method == Method::GET {
+        builder.query(req)
+      } else {
+        builder.json(req)
+      };
+    }
+    let text = builder
+      .send()
+      .await
+      .context(ReqwestClientSnafu)?
+      .text()
+      .await
+      .context(ReqwestClientSnafu)?;
+    // delete api responds with empty body
+    let text = if text.is_empty() { "null" } else { &text };
+    match serde_json::from_str(text) {
+      Ok(res) => Ok(res),
+      Err(_) => PlainTextSnafu {
+        message: format!("Hetzner Error: unexpected response: {}", text),
+      }
+      .fail(),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::sync::{Arc, Mutex};
+
+  use super::*;
+  use crate::test_util::{StubResponse, request_body, stub_server};
+
+  /// Stub Hetzner DNS api, zone `example.com` with id `z1` has TXT record `1` with value `old` at
+  /// `_acme-challenge`, request lines and bodies are recorded
+  async fn stub_hetzner(requests: Arc<Mutex<Vec<String>>>) -> String {
+    stub_server(move |req| {
+      assert!(req.contains("auth-api-token: token"));
+      let line = req.lines().next().unwrap();
+      let line = line.strip_suffix(" HTTP/1.1").unwrap();
+      requests
+        .lock()
+        .unwrap()
+        .push(format!("{} {}", line, request_body(req)));
+      match line {
+        "GET /zones?name=example.com" => {
+          StubResponse::ok(r#"{"zones":[{"id":"z1","name":"example.com","ttl":86400}]}"#)
+        }
+        "GET /records?zone_id=z1" => StubResponse::ok(
+          r#"{"records":[{"id":"1","zone_id":"z1","name":"_acme-challenge","value":"old","type":"TXT","ttl":60}]}"#,
+        ),
+        "POST /records" => StubResponse::ok(
+          r#"{"record":{"id":"2","zone_id":"z1","name":"_acme-challenge","value":"new","type":"TXT","ttl":60}}"#,
+        ),
+        "DELETE /records/2" => StubResponse::ok(""),
+        _ => StubResponse::new(
+          "404 Not Found",
+          r#"{"error":{"message":"zone not found","code":404}}"#,
+        ),
+      }
+    })
+    .await
+  }
+
+  #[tokio::test]
+  async fn create_and_delete_txt() {
+    let requests = Arc::new(Mutex::new(vec![]));
+    let endpoint = stub_hetzner(requests.clone()).await;
+    let client = HetznerClient::new(HetznerOption::new("token").endpoint(endpoint)).unwrap();
+    assert!(client.has_zone("example.com").await.unwrap());
+    assert!(!client.has_zone("example.org").await.unwrap());
+
+    let existed = client
+      .create_txt("example.com", "_acme-challenge.example.com", "old")
+      .await
+      .unwrap();
+    assert_eq!(existed.id, "1");
+    let record = client
+      .create_txt("example.com", "_acme-challenge.example.com", "new")
+      .await
+      .unwrap();
+    assert_eq!(record.id, "2");
+    client.delete_txt(&record).await.unwrap();
+
+    let requests = requests.lock().unwrap();
+    assert_eq!(requests.len(), 8);
+    assert!(requests[6].starts_with("POST /records "));
+    assert!(requests[6].contains(r#""zone_id":"z1""#));
+    assert!(requests[6].contains(r#""name":"_acme-challenge""#));
+    assert!(requests[6].contains(r#""value":"new""#));
+    assert!(requests[7].starts_with("DELETE /records/2 "));
+  }
+}
@@ -0,0 +1,15 @@
+//! Implement of [Hetzner DNS](https://www.hetzner.com/dns-console) challenge
+//!
+//! Link: <https://dns.hetzner.com/api-docs>
+
+mod client;
+pub use client::HetznerClient;
+
+mod response;
+pub use response::{HetznerRecord, HetznerZone};
+
+mod request;
+pub use request::HetznerCreateRecordReq;
+
+mod option;
+pub use option::HetznerOption;
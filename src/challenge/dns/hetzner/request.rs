@@ -0,0 +1,36 @@
+use serde::Serialize;
+
+use crate::challenge::dns::RecordType;
+
+#[derive(Debug, Serialize)]
+pub struct HetznerCreateRecordReq<'a> {
+  pub(crate) zone_id: &'a str,
+
+  pub(crate) name: &'a str,
+
+  pub(crate) value: &'a str,
+
+  #[serde(skip_serializing_if = "Option::is_none")]
+  ttl: Option<u64>,
+
+  #[serde(rename = "type")]
+  rtype: RecordType,
+}
+
+impl<'a> HetznerCreateRecordReq<'a> {
+  /// `name` is relative to zone, for example, `_acme-challenge` or `@`
+  pub fn new(zone_id: &'a str, name: &'a str, value: &'a str) -> Self {
+    Self {
+      zone_id,
+      name,
+      value,
+      ttl: None,
+      rtype: RecordType::TXT,
+    }
+  }
+
+  pub fn ttl(mut self, ttl: u64) -> Self {
+    self.ttl = Some(ttl);
+    self
+  }
+}
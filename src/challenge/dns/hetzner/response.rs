@@ -0,0 +1,73 @@
+use serde::Deserialize;
+
+use crate::errors::{PlainTextSnafu, Result};
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub(crate) enum HetznerRes<T> {
+  Failure { error: ErrorData },
+  Success(T),
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct ErrorData {
+  pub(crate) code: u16,
+
+  message: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct RecordData {
+  pub(crate) record: HetznerRecord,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct RecordListData {
+  #[serde(default)]
+  pub(crate) records: Vec<HetznerRecord>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct ZoneListData {
+  #[serde(default)]
+  pub(crate) zones: Vec<HetznerZone>,
+}
+
+/// DNS record returned by [`crate::challenge::dns::hetzner::HetznerClient::list_records`]
+#[derive(Debug, Clone, Deserialize)]
+pub struct HetznerRecord {
+  pub id: String,
+
+  /// Name relative to zone, for example, `_acme-challenge`
+  pub name: String,
+
+  pub value: String,
+
+  #[serde(rename = "type")]
+  pub rtype: String,
+
+  pub ttl: Option<u64>,
+}
+
+/// Zone returned by [`crate::challenge::dns::hetzner::HetznerClient::list_zones`]
+#[derive(Debug, Clone, Deserialize)]
+pub struct HetznerZone {
+  pub id: String,
+
+  pub name: String,
+}
+
+impl<T> HetznerRes<T> {
+  pub fn unwrap_data(self) -> Result<T> {
+    match self {
+      HetznerRes::Success(data) => Ok(data),
+      HetznerRes::Failure { error } => PlainTextSnafu {
+        message: format!(
+          "Hetzner Error: code: {}, message: {}",
+          error.code, error.message
+        ),
+      }
+      .fail(),
+    }
+  }
+}
@@ -0,0 +1,410 @@
+use futures_util::future::BoxFuture;
+use http::{
+  HeaderMap, HeaderName, HeaderValue, Method,
+  header::{AUTHORIZATION, CONTENT_TYPE, HOST},
+};
+use jiff::{Timestamp, tz::TimeZone};
+use reqwest::{Client, ClientBuilder};
+use serde::{Serialize, de::DeserializeOwned};
+use snafu::ResultExt;
+
+use crate::{
+  challenge::dns::{
+    DnsChallengeClient, TxtRecord,
+    huaweicloud::{
+      HuaweiCloudCreateRecordSetReq, HuaweiCloudListRecordSetsReq, HuaweiCloudListZonesReq,
+      HuaweiCloudOption, HuaweiCloudRecordSet, HuaweiCloudUpdateRecordSetReq, HuaweiCloudZone,
+      response::{HuaweiCloudRes, RecordSetListData, ZoneListData},
+    },
+    resolver::name_eq,
+  },
+  errors::{PlainTextSnafu, ReqwestClientSnafu, Result},
+  util::{
+    canonical_request, json_serialize, sha2_hex, sha2_hmac, str_to_header_value, uri_encode,
+    url_host,
+  },
+};
+
+/// Client for Huawei Cloud DNS api, requests are signed with AK/SK (SDK-HMAC-SHA256)
+#[derive(Debug)]
+pub struct HuaweiCloudClient {
+  access_key: String,
+  secret_key: String,
+  project_id: Option<String>,
+  endpoint: String,
+  host: String,
+  ttl: u64,
+  client: Client,
+}
+
+impl HuaweiCloudClient {
+  /// Create client with option, see [`HuaweiCloudOption`]
+  pub fn new(option: HuaweiCloudOption) -> Result<Self> {
+    let HuaweiCloudOption {
+      access_key,
+      secret_key,
+      region,
+      project_id,
+      endpoint,
+      ttl,
+      proxy,
+      timeout,
+    } = option;
+    let mut client = ClientBuilder::new();
+    if let Some(timeout) = timeout {
+      client = client.timeout(timeout);
+    }
+    if let Some(proxy) = proxy {
+      let proxy = reqwest::Proxy::all(proxy).context(ReqwestClientSnafu)?;
+      client = client.proxy(proxy);
+    }
+    let client = client.build().context(ReqwestClientSnafu)?;
+    let endpoint = endpoint.unwrap_or_else(|| format!("https://dns.{}.myhuaweicloud.com", region));
+    let endpoint = endpoint.trim_end_matches('/').to_string();
+    let host = url_host(&endpoint).to_string();
+    Ok(Self {
+      access_key,
+      secret_key,
+      project_id,
+      endpoint,
+      host,
+      ttl,
+      client,
+    })
+  }
+
+  /// Create client from environment variable, see [`HuaweiCloudOption::new_from_env`]
+  pub fn new_from_env() -> Result<Self> {
+    Self::new(HuaweiCloudOption::new_from_env()?)
+  }
+
+  /// List public zones
+  pub async fn list_zones(&self, req: HuaweiCloudListZonesReq<'_>) -> Result<Vec<HuaweiCloudZone>> {
+    let data: ZoneListData = self
+      .exec_request(Method::GET, "/v2/zones", &req.query(), None::<&()>)
+      .await?;
+    Ok(data.zones)
+  }
+
+  /// Id of public zone `zone`, for example, `example.com`
+  pub async fn zone_id(&self, zone: &str) -> Result<String> {
+    let zones = self.list_zones(HuaweiCloudListZonesReq::new(zone)).await?;
+    match zones.into_iter().find(|z| name_eq(&z.name, zone)) {
+      Some(zone) => Ok(zone.id),
+      None => PlainTextSnafu {
+        message: format!("HuaweiCloud Error: public zone {} not found", zone),
+      }
+      .fail(),
+    }
+  }
+
+  /// List TXT record sets of zone
+  pub async fn list_record_sets(
+    &self,
+    req: HuaweiCloudListRecordSetsReq<'_>,
+  ) -> Result<Vec<HuaweiCloudRecordSet>> {
+    let path = format!("/v2/zones/{}/recordsets", req.zone_id);
+    let data: RecordSetListData = self
+      .exec_request(Method::GET, &path, &req.query(), None::<&()>)
+      .await?;
+    Ok(data.recordsets)
+  }
+
+  /// Create TXT record set
+  pub async fn create_record_set(
+    &self,
+    req: HuaweiCloudCreateRecordSetReq<'_>,
+  ) -> Result<HuaweiCloudRecordSet> {
+    let path = format!("/v2/zones/{}/recordsets", req.zone_id);
+    self
+      .exec_request(Method::POST, &path, &[], Some(&req))
+      .await
+  }
+
+  /// Replace values of TXT record set
+  pub async fn update_record_set(
+    &self,
+    req: HuaweiCloudUpdateRecordSetReq<'_>,
+  ) -> Result<HuaweiCloudRecordSet> {
+    let path = format!("/v2/zones/{}/recordsets/{}", req.zone_id, req.record_set_id);
+    self.exec_request(Method::PUT, &path, &[], Some(&req)).await
+  }
+
+  /// Delete record set
+  pub async fn delete_record_set(
+    &self,
+    zone_id: &str,
+    record_set_id: &str,
+  ) -> Result<HuaweiCloudRecordSet> {
+    let path = format!("/v2/zones/{}/recordsets/{}", zone_id, record_set_id);
+    self
+      .exec_request(Method::DELETE, &path, &[], None::<&()>)
+      .await
+  }
+
+  /// TXT record set named `fqdn` exactly, `None` if it does not exist
+  pub async fn find_record_set(
+    &self,
+    zone_id: &str,
+    fqdn: &str,
+  ) -> Result<Option<HuaweiCloudRecordSet>> {
+    let name = format!("{}.", fqdn.trim_end_matches('.'));
+    let req = HuaweiCloudListRecordSetsReq::new(zone_id).name(&name);
+    let sets = self.list_record_sets(req).await?;
+    Ok(sets.into_iter().find(|s| name_eq(&s.name, fqdn)))
+  }
+}
+
+impl DnsChallengeClient for HuaweiCloudClient {
+  fn has_zone<'a>(&'a self, zone: &'a str) -> BoxFuture<'a, Result<bool>> {
+    Box::pin(async move {
+      let zones = self.list_zones(HuaweiCloudListZonesReq::new(zone)).await?;
+      Ok(zones.iter().any(|z| name_eq(&z.name, zone)))
+    })
+  }
+
+  /// Huawei Cloud keeps one record set per name and type, so the value is added to the existing
+  /// record set if there is one
+  fn create_txt<'a>(
+    &'a self,
+    zone: &'a str,
+    fqdn: &'a str,
+    value: &'a str,
+  ) -> BoxFuture<'a, Result<TxtRecord>> {
+    Box::pin(async move {
+      let zone_id = self.zone_id(zone).await?;
+      let set = match self.find_record_set(&zone_id, fqdn).await? {
+        Some(set) if set.values().iter().any(|v| v == value) => set,
+        Some(set) => {
+          let mut values = set.values();
+          values.push(value.to_string());
+          let req = HuaweiCloudUpdateRecordSetReq::new(&zone_id, &set.id, &values);
+          self.update_record_set(req).await?
+        }
+        None => {
+          let values = [value.to_string()];
+          let req = HuaweiCloudCreateRecordSetReq::new(&zone_id, fqdn, &values).ttl(self.ttl);
+          self.create_record_set(req).await?
+        }
+      };
+      Ok(TxtRecord::new(zone, fqdn, value, set.id))
+    })
+  }
+
+  fn delete_txt<'a>(&'a self, record: &'a TxtRecord) -> BoxFuture<'a, Result<()>> {
+    Box::pin(async move {
+      let zone_id = self.zone_id(&record.zone).await?;
+      let Some(set) = self.find_record_set(&zone_id, &record.fqdn).await? else {
+        return Ok(());
+      };
+      let values: Vec<String> = set
+        .values()
+        .into_iter()
+        .filter(|v| *v != record.value)
+        .collect();
+      if values.is_empty() {
+        self.delete_record_set(&zone_id, &set.id).await?;
+      } else if values.len() < set.records.len() {
+        let req = HuaweiCloudUpdateRecordSetReq::new(&zone_id, &set.id, &values);
+        self.update_record_set(req).await?;
+      }
+      Ok(())
+    })
+  }
+}
+
+impl HuaweiCloudClient {
+  async fn exec_request<B, R>(
+    &self,
+    method: Method,
+    path: &str,
+    query: &[(&str, String)],
+    body: Option<&B>,
+  ) -> Result<R>
+  where
+    B: Serialize,
+    R: DeserializeOwned,
+  {
+    let payload = match body {
+      Some(body) => json_serialize(body)?,
+      None => String::new(),
+    };
+    let sdk_date = Timestamp::now()
+      .to_zoned(TimeZone::UTC)
+      .strftime("%Y%m%dT%H%M%SZ")
+      .to_string();
+    let mut headers = HeaderMap::new();
+    headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+    headers.insert(HOST, str_to_header_value(&self.host)?);
+    headers.insert(
+      HeaderName::from_static("x-sdk-date"),
+      str_to_header_value(&sdk_date)?,
+    );
+    if let Some(project_id) = &self.project_id {
+      headers.insert(
+        HeaderName::from_static("x-project-id"),
+        str_to_header_value(project_id)?,
+      );
+    }
+    let query: Vec<(&str, &str)> = query.iter().map(|(k, v)| (*k, v.as_str())).collect();
+    let auth = self.sign(method.as_str(), path, &query, &headers, &payload, &sdk_date)?;
+    headers.insert(AUTHORIZATION, str_to_header_value(&auth)?);
+    let url = format!("{}{}", self.endpoint, path);
+    self
+      .client
+      .request(method, url)
+      .query(&query)
+      .headers(headers)
+      .body(payload)
+      .send()
+      .await
+      .context(ReqwestClientSnafu)?
+      .json::<HuaweiCloudRes<R>>()
+      .await
+      .context(ReqwestClientSnafu)?
+      .unwrap_data()
+  }
+
+  /// [SDK-HMAC-SHA256](https://support.huaweicloud.com/devg-apisign/api-sign-algorithm.html)
+  /// signature, the canonical uri always ends with `/`
+  fn sign(
+    &self,
+    method: &str,
+    path: &str,
+    query: &[(&str, &str)],
+    headers: &HeaderMap,
+    payload: &str,
+    sdk_date: &str,
+  ) -> Result<String> {
+    let mut canonical_uri = path
+      .split('/')
+      .map(uri_encode)
+      .collect::<Vec<_>>()
+      .join("/");
+    if !canonical_uri.ends_with('/') {
+      canonical_uri.push('/');
+    }
+    let (canonical_request, signed_headers) =
+      canonical_request(method, &canonical_uri, query, headers, payload.as_bytes());
+    let str_to_sign = format!(
+      "SDK-HMAC-SHA256\n{}\n{}",
+      sdk_date,
+      sha2_hex(canonical_request)
+    );
+    let signature = hex::encode(sha2_hmac(&self.secret_key, str_to_sign.as_bytes())?);
+    Ok(format!(
+      "SDK-HMAC-SHA256 Access={}, SignedHeaders={}, Signature={}",
+      self.access_key, signed_headers, signature
+    ))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::sync::{Arc, Mutex};
+
+  use super::*;
+  use crate::test_util::{StubResponse, request_body, stub_server};
+
+  #[test]
+  fn sign_example_request() {
+    // example of the SDK-HMAC-SHA256 document
+    let option = HuaweiCloudOption::new(
+      "QTWAOYTTINDUT2QVKYUC",
+      "MFyfvK41ba2giqM7Uio6PznpdUKGpownRZlmVmHc",
+    );
+    let client = HuaweiCloudClient::new(option).unwrap();
+    let mut headers = HeaderMap::new();
+    headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+    headers.insert(HOST, HeaderValue::from_static("service.region.example.com"));
+    headers.insert("x-sdk-date", HeaderValue::from_static("20191115T033655Z"));
+    let query = [
+      ("limit", "2"),
+      ("marker", "13551d6b-755d-4757-b956-536f674975c0"),
+    ];
+    let auth = client
+      .sign(
+        "GET",
+        "/v1/77b6a44cba5143ab91d13ab9a8ff44fd/vpcs",
+        &query,
+        &headers,
+        "",
+        "20191115T033655Z",
+      )
+      .unwrap();
+    assert_eq!(
+      auth,
+      "SDK-HMAC-SHA256 Access=QTWAOYTTINDUT2QVKYUC, SignedHeaders=content-type;host;x-sdk-date, \
+       Signature=7be6668032f70418fcc22abc52071e57aff61b84a1d2381bb430d6870f4f6ebe"
+    );
+  }
+
+  /// Stub Huawei Cloud DNS api, zone `z1` of `example.com` has record set `s1` with value `old`
+  /// at `_acme-challenge`, record set is updated by requests, whose lines and bodies are recorded
+  async fn stub_huaweicloud(requests: Arc<Mutex<Vec<String>>>) -> String {
+    let mut records = Some(vec!["\"old\"".to_string()]);
+    stub_server(move |req| {
+      assert!(req.contains("authorization: SDK-HMAC-SHA256 Access=ak, "));
+      let line = req.lines().next().unwrap().trim_end_matches(" HTTP/1.1");
+      let body = request_body(req);
+      requests.lock().unwrap().push(format!("{} {}", line, body));
+      let set = |records: &Vec<String>| {
+        serde_json::json!({
+          "id": "s1",
+          "name": "_acme-challenge.example.com.",
+          "type": "TXT",
+          "ttl": 300,
+          "records": records,
+        })
+      };
+      if line.starts_with("GET /v2/zones?") {
+        StubResponse::ok(r#"{"zones":[{"id":"z1","name":"example.com."}]}"#)
+      } else if line.starts_with("GET /v2/zones/z1/recordsets?") {
+        let sets: Vec<_> = records.iter().map(set).collect();
+        StubResponse::ok(serde_json::json!({ "recordsets": sets }).to_string())
+      } else if line == "PUT /v2/zones/z1/recordsets/s1" && records.is_some() {
+        let body: serde_json::Value = serde_json::from_str(body).unwrap();
+        records = serde_json::from_value(body["records"].clone()).unwrap();
+        StubResponse::ok(set(records.as_ref().unwrap()).to_string())
+      } else if line == "DELETE /v2/zones/z1/recordsets/s1" && records.is_some() {
+        StubResponse::new("202 Accepted", set(&records.take().unwrap()).to_string())
+      } else {
+        let body = r#"{"code":"DNS.0101","message":"The resource does not exist."}"#;
+        StubResponse::new("404 Not Found", body)
+      }
+    })
+    .await
+  }
+
+  #[tokio::test]
+  async fn add_and_remove_value() {
+    let requests = Arc::new(Mutex::new(vec![]));
+    let endpoint = stub_huaweicloud(requests.clone()).await;
+    let client =
+      HuaweiCloudClient::new(HuaweiCloudOption::new("ak", "sk").endpoint(endpoint)).unwrap();
+    assert!(client.has_zone("example.com").await.unwrap());
+
+    let fqdn = "_acme-challenge.example.com";
+    let record = client.create_txt("example.com", fqdn, "new").await.unwrap();
+    assert_eq!(record.id, "s1");
+    let old = TxtRecord::new("example.com", fqdn, "old", "s1");
+    client.delete_txt(&old).await.unwrap();
+    client.delete_txt(&record).await.unwrap();
+
+    let requests = requests.lock().unwrap();
+    let changes: Vec<&str> = requests
+      .iter()
+      .filter(|r| !r.starts_with("GET "))
+      .map(|r| r.as_str())
+      .collect();
+    assert_eq!(
+      changes,
+      [
+        r#"PUT /v2/zones/z1/recordsets/s1 {"records":["\"old\"","\"new\""]}"#,
+        r#"PUT /v2/zones/z1/recordsets/s1 {"records":["\"new\""]}"#,
+        "DELETE /v2/zones/z1/recordsets/s1 ",
+      ]
+    );
+    assert!(requests[2].contains("name=_acme-challenge.example.com."));
+  }
+}
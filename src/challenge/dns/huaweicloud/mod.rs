@@ -0,0 +1,18 @@
+//! Implement of [Huawei Cloud](https://www.huaweicloud.com/) DNS challenge
+//!
+//! Link: <https://support.huaweicloud.com/api-dns/dns_api_64001.html>
+
+mod client;
+pub use client::HuaweiCloudClient;
+
+mod option;
+pub use option::HuaweiCloudOption;
+
+mod request;
+pub use request::{
+  HuaweiCloudCreateRecordSetReq, HuaweiCloudListRecordSetsReq, HuaweiCloudListZonesReq,
+  HuaweiCloudUpdateRecordSetReq,
+};
+
+mod response;
+pub use response::{HuaweiCloudRecordSet, HuaweiCloudZone};
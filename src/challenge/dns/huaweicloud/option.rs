@@ -0,0 +1,104 @@
+use std::time::Duration;
+
+use crate::{errors::Result, util::env_single_var};
+
+/// Options for create a [`crate::challenge::dns::huaweicloud::HuaweiCloudClient`] instance
+#[derive(Debug)]
+pub struct HuaweiCloudOption {
+  pub(crate) access_key: String,
+  pub(crate) secret_key: String,
+  pub(crate) region: String,
+  pub(crate) project_id: Option<String>,
+  pub(crate) endpoint: Option<String>,
+  pub(crate) ttl: u64,
+  pub(crate) proxy: Option<String>,
+  pub(crate) timeout: Option<Duration>,
+}
+
+impl HuaweiCloudOption {
+  /// Create option with access key (AK) and secret key (SK), the user must have privilege of
+  /// __DNS FullAccess__
+  pub fn new(access_key: impl Into<String>, secret_key: impl Into<String>) -> Self {
+    HuaweiCloudOption {
+      access_key: access_key.into(),
+      secret_key: secret_key.into(),
+      region: "cn-north-4".to_string(),
+      project_id: None,
+      endpoint: None,
+      ttl: 300,
+      proxy: None,
+      timeout: None,
+    }
+  }
+
+  /// Create option from environment variable, variable name for access key is
+  /// __HUAWEICLOUD_ACCESS_KEY_ID__ or __EASY_ACME_HUAWEICLOUD_ACCESS_KEY__, for secret key is
+  /// __HUAWEICLOUD_SECRET_ACCESS_KEY__ or __EASY_ACME_HUAWEICLOUD_SECRET_KEY__, and for optional
+  /// region is __HUAWEICLOUD_REGION__ or __EASY_ACME_HUAWEICLOUD_REGION__
+  pub fn new_from_env() -> Result<Self> {
+    let option = Self::new(Self::env_access_key()?, Self::env_secret_key()?);
+    match Self::env_region() {
+      Ok(region) => Ok(option.region(region)),
+      Err(_) => Ok(option),
+    }
+  }
+
+  /// Set region, endpoint is `https://dns.{region}.myhuaweicloud.com`, default is `cn-north-4`
+  pub fn region(mut self, region: impl Into<String>) -> Self {
+    self.region = region.into();
+    self
+  }
+
+  /// Set project id sent in `X-Project-Id` header, default is `None`
+  pub fn project_id(mut self, project_id: impl Into<String>) -> Self {
+    self.project_id = Some(project_id.into());
+    self
+  }
+
+  /// Set endpoint instead of the one of region, for example, `https://dns.myhuaweicloud.com`
+  pub fn endpoint(mut self, endpoint: impl Into<String>) -> Self {
+    self.endpoint = Some(endpoint.into());
+    self
+  }
+
+  /// Set TTL of TXT record, default is 300 seconds
+  pub fn ttl(mut self, ttl: u64) -> Self {
+    self.ttl = ttl;
+    self
+  }
+
+  /// Set proxy, for example, `https://127.0.0.1:8080`, `socks5://127.0.0.1:9000`, default is `None`
+  pub fn proxy(mut self, proxy: impl Into<String>) -> Self {
+    self.proxy = Some(proxy.into());
+    self
+  }
+
+  /// Set timeout, for example, `Duration::from_secs(5)`, default is `None`
+  pub fn timeout(mut self, timeout: Duration) -> Self {
+    self.timeout = Some(timeout);
+    self
+  }
+}
+
+impl HuaweiCloudOption {
+  #[inline]
+  pub(crate) fn env_access_key() -> Result<String> {
+    env_single_var([
+      "HUAWEICLOUD_ACCESS_KEY_ID",
+      "EASY_ACME_HUAWEICLOUD_ACCESS_KEY",
+    ])
+  }
+
+  #[inline]
+  pub(crate) fn env_secret_key() -> Result<String> {
+    env_single_var([
+      "HUAWEICLOUD_SECRET_ACCESS_KEY",
+      "EASY_ACME_HUAWEICLOUD_SECRET_KEY",
+    ])
+  }
+
+  #[inline]
+  pub(crate) fn env_region() -> Result<String> {
+    env_single_var(["HUAWEICLOUD_REGION", "EASY_ACME_HUAWEICLOUD_REGION"])
+  }
+}
@@ -0,0 +1,150 @@
+use serde::Serialize;
+
+use crate::challenge::dns::RecordType;
+
+/// Request of [`crate::challenge::dns::huaweicloud::HuaweiCloudClient::list_zones`]
+#[derive(Debug)]
+pub struct HuaweiCloudListZonesReq<'a> {
+  name: &'a str,
+  zone_type: &'a str,
+  limit: Option<u64>,
+}
+
+impl<'a> HuaweiCloudListZonesReq<'a> {
+  /// Search public zones with `name`, fuzzy matched by Huawei Cloud
+  pub fn new(name: &'a str) -> Self {
+    HuaweiCloudListZonesReq {
+      name,
+      zone_type: "public",
+      limit: None,
+    }
+  }
+
+  /// Set page size, max is 500, default is 500
+  pub fn limit(mut self, limit: u64) -> Self {
+    self.limit = Some(limit);
+    self
+  }
+
+  pub(crate) fn query(&self) -> Vec<(&'static str, String)> {
+    let mut query = vec![
+      ("name", self.name.to_string()),
+      ("type", self.zone_type.to_string()),
+    ];
+    if let Some(limit) = self.limit {
+      query.push(("limit", limit.to_string()));
+    }
+    query
+  }
+}
+
+/// Request of [`crate::challenge::dns::huaweicloud::HuaweiCloudClient::list_record_sets`]
+#[derive(Debug)]
+pub struct HuaweiCloudListRecordSetsReq<'a> {
+  pub(crate) zone_id: &'a str,
+  name: Option<&'a str>,
+  rtype: RecordType,
+  limit: Option<u64>,
+}
+
+impl<'a> HuaweiCloudListRecordSetsReq<'a> {
+  pub fn new(zone_id: &'a str) -> Self {
+    HuaweiCloudListRecordSetsReq {
+      zone_id,
+      name: None,
+      rtype: RecordType::TXT,
+      limit: None,
+    }
+  }
+
+  /// Search record sets with `name`, fuzzy matched by Huawei Cloud
+  pub fn name(mut self, name: &'a str) -> Self {
+    self.name = Some(name);
+    self
+  }
+
+  /// Set page size, max is 500, default is 500
+  pub fn limit(mut self, limit: u64) -> Self {
+    self.limit = Some(limit);
+    self
+  }
+
+  pub(crate) fn query(&self) -> Vec<(&'static str, String)> {
+    let mut query = vec![("type", format!("{:?}", self.rtype))];
+    if let Some(name) = self.name {
+      query.push(("name", name.to_string()));
+    }
+    if let Some(limit) = self.limit {
+      query.push(("limit", limit.to_string()));
+    }
+    query
+  }
+}
+
+/// Request of [`crate::challenge::dns::huaweicloud::HuaweiCloudClient::create_record_set`]
+#[derive(Debug, Serialize)]
+pub struct HuaweiCloudCreateRecordSetReq<'a> {
+  #[serde(skip)]
+  pub(crate) zone_id: &'a str,
+
+  name: String,
+
+  #[serde(rename = "type")]
+  rtype: RecordType,
+
+  #[serde(skip_serializing_if = "Option::is_none")]
+  ttl: Option<u64>,
+
+  records: Vec<String>,
+}
+
+impl<'a> HuaweiCloudCreateRecordSetReq<'a> {
+  /// `name` is the full name, for example, `_acme-challenge.example.com`, and `values` are
+  /// quoted when sending
+  pub fn new(zone_id: &'a str, name: &str, values: &[String]) -> Self {
+    HuaweiCloudCreateRecordSetReq {
+      zone_id,
+      name: format!("{}.", name.trim_end_matches('.')),
+      rtype: RecordType::TXT,
+      ttl: None,
+      records: values.iter().map(|v| format!("\"{}\"", v)).collect(),
+    }
+  }
+
+  pub fn ttl(mut self, ttl: u64) -> Self {
+    self.ttl = Some(ttl);
+    self
+  }
+}
+
+/// Request of [`crate::challenge::dns::huaweicloud::HuaweiCloudClient::update_record_set`]
+#[derive(Debug, Serialize)]
+pub struct HuaweiCloudUpdateRecordSetReq<'a> {
+  #[serde(skip)]
+  pub(crate) zone_id: &'a str,
+
+  #[serde(skip)]
+  pub(crate) record_set_id: &'a str,
+
+  #[serde(skip_serializing_if = "Option::is_none")]
+  ttl: Option<u64>,
+
+  records: Vec<String>,
+}
+
+impl<'a> HuaweiCloudUpdateRecordSetReq<'a> {
+  /// Replace values of record set, `values` are quoted when sending
+  pub fn new(zone_id: &'a str, record_set_id: &'a str, values: &[String]) -> Self {
+    HuaweiCloudUpdateRecordSetReq {
+      zone_id,
+      record_set_id,
+      ttl: None,
+      records: values.iter().map(|v| format!("\"{}\"", v)).collect(),
+    }
+  }
+
+  pub fn ttl(mut self, ttl: u64) -> Self {
+    self.ttl = Some(ttl);
+    self
+  }
+}
@@ -0,0 +1,85 @@
+use serde::Deserialize;
+
+use crate::errors::{PlainTextSnafu, Result};
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub(crate) enum HuaweiCloudRes<T> {
+  Failure(FailureData),
+  Success(T),
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct ZoneListData {
+  #[serde(default)]
+  pub(crate) zones: Vec<HuaweiCloudZone>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct RecordSetListData {
+  #[serde(default)]
+  pub(crate) recordsets: Vec<HuaweiCloudRecordSet>,
+}
+
+/// Zone returned by [`crate::challenge::dns::huaweicloud::HuaweiCloudClient::list_zones`]
+#[derive(Debug, Clone, Deserialize)]
+pub struct HuaweiCloudZone {
+  pub id: String,
+
+  /// Name with trailing dot, for example, `example.com.`
+  pub name: String,
+}
+
+/// Record set returned by [`crate::challenge::dns::huaweicloud::HuaweiCloudClient`]
+#[derive(Debug, Clone, Deserialize)]
+pub struct HuaweiCloudRecordSet {
+  pub id: String,
+
+  /// Name with trailing dot, for example, `_acme-challenge.example.com.`
+  pub name: String,
+
+  #[serde(rename = "type")]
+  pub rtype: String,
+
+  pub ttl: Option<u64>,
+
+  #[serde(default)]
+  pub records: Vec<String>,
+}
+
+impl HuaweiCloudRecordSet {
+  /// TXT values without quotes
+  pub fn values(&self) -> Vec<String> {
+    self
+      .records
+      .iter()
+      .map(|r| r.trim_matches('"').to_string())
+      .collect()
+  }
+}
+
+/// Error of api gateway is `error_code` and `error_msg`, and error of DNS service is `code` and
+/// `message`
+#[derive(Debug, Deserialize)]
+pub(crate) struct FailureData {
+  #[serde(alias = "error_code")]
+  code: String,
+
+  #[serde(alias = "error_msg")]
+  message: String,
+}
+
+impl<T> HuaweiCloudRes<T> {
+  pub fn unwrap_data(self) -> Result<T> {
+    match self {
+      HuaweiCloudRes::Success(success) => Ok(success),
+      HuaweiCloudRes::Failure(failure) => PlainTextSnafu {
+        message: format!(
+          "HuaweiCloud Error: code: {}, message: {}",
+          failure.code, failure.message,
+        ),
+      }
+      .fail(),
+    }
+  }
+}
@@ -0,0 +1,296 @@
+use futures_util::future::BoxFuture;
+use http::{HeaderMap, HeaderName, Method, header::AUTHORIZATION};
+use reqwest::{Client, ClientBuilder};
+use serde::{Serialize, de::DeserializeOwned};
+use snafu::ResultExt;
+
+use crate::{
+  challenge::dns::{
+    DnsChallengeClient, TxtRecord,
+    linode::{
+      LinodeCreateRecordReq, LinodeDeleteRecordReq, LinodeDomain, LinodeOption, LinodeRecord,
+      response::{LinodeRes, PageData},
+    },
+    relative_name,
+  },
+  errors::{PlainTextSnafu, ReqwestClientSnafu, Result},
+  util::str_to_header_value,
+};
+
+const API_ENDPOINT: &str = "https://api.linode.com/v4";
+
+/// Client for Linode DNS api
+#[derive(Debug)]
+pub struct LinodeClient {
+  client: Client,
+  endpoint: String,
+}
+
+impl LinodeClient {
+  /// Create client with option, see [`LinodeOption`]
+  pub fn new(option: LinodeOption) -> Result<Self> {
+    let LinodeOption {
+      token,
+      proxy,
+      timeout,
+      endpoint,
+    } = option;
+    let mut client = ClientBuilder::new();
+    if let Some(timeout) = timeout {
+      client = client.timeout(timeout);
+    }
+    if let Some(proxy) = proxy {
+      let proxy = reqwest::Proxy::all(proxy).context(ReqwestClientSnafu)?;
+      client = client.proxy(proxy);
+    }
+    let mut headers = HeaderMap::new();
+    headers.insert(
+      AUTHORIZATION,
+      str_to_header_value(format!("Bearer {}", token))?,
+    );
+    client = client.default_headers(headers);
+    let client = client.build().context(ReqwestClientSnafu)?;
+    Ok(Self {
+      client,
+      endpoint: endpoint.unwrap_or_else(|| API_ENDPOINT.to_string()),
+    })
+  }
+
+  /// Create client from environment variable, see [`LinodeOption::new_from_env`]
+  pub fn new_from_env() -> Result<Self> {
+    Self::new(LinodeOption::new_from_env()?)
+  }
+
+  /// Create DNS TXT record
+  ///
+  /// `return`: record id or error
+  pub async fn create_record(&self, req: LinodeCreateRecordReq<'_>) -> Result<u64> {
+    let url = format!("{}/domains/{}/records", self.endpoint, req.domain_id);
+    let record: LinodeRecord = self.exec_request(Method::POST, &url, Some(&req)).await?;
+    Ok(record.id)
+  }
+
+  /// Delete DNS TXT record
+  pub async fn delete_record(&self, req: LinodeDeleteRecordReq) -> Result<()> {
+    let url = format!(
+      "{}/domains/{}/records/{}",
+      self.endpoint, req.domain_id, req.record_id
+    );
+    let _: serde_json::Value = self.exec_request(Method::DELETE, &url, None::<&()>).await?;
+    Ok(())
+  }
+
+  /// List all DNS records of domain
+  pub async fn list_records(&self, domain_id: u64) -> Result<Vec<LinodeRecord>> {
+    let url = format!("{}/domains/{}/records", self.endpoint, domain_id);
+    self.list_pages(&url, None).await
+  }
+
+  /// Append value to the TXT records of `req`'s name, the existing record is reused if it has the
+  /// same value
+  pub async fn append_record(&self, req: LinodeCreateRecordReq<'_>) -> Result<u64> {
+    let records = self.list_records(req.domain_id).await?;
+    let existed = records.into_iter().find(|r| {
+      r.rtype == "TXT" && r.name.eq_ignore_ascii_case(req.name) && r.target == req.target
+    });
+    if let Some(record) = existed {
+      return Ok(record.id);
+    }
+    self.create_record(req).await
+  }
+
+  /// List domains of account, only domains equal to `domain` if it is set
+  pub async fn list_domains(&self, domain: Option<&str>) -> Result<Vec<LinodeDomain>> {
+    let url = format!("{}/domains", self.endpoint);
+    let filter = domain.map(|d| serde_json::json!({ "domain": d }).to_string());
+    self.list_pages(&url, filter.as_deref()).await
+  }
+
+  /// Id of domain, for example, `example.com`
+  pub async fn domain_id(&self, domain: &str) -> Result<Option<u64>> {
+    let domains = self.list_domains(Some(domain)).await?;
+    let domain_id = domains
+      .into_iter()
+      .find(|d| d.domain.eq_ignore_ascii_case(domain))
+      .map(|d| d.id);
+    Ok(domain_id)
+  }
+
+  async fn require_domain_id(&self, domain: &str) -> Result<u64> {
+    match self.domain_id(domain).await? {
+      Some(domain_id) => Ok(domain_id),
+      None => PlainTextSnafu {
+        message: format!("Linode Error: domain {} not found", domain),
+      }
+      .fail(),
+    }
+  }
+}
+
+impl DnsChallengeClient for LinodeClient {
+  fn has_zone<'a>(&'a self, zone: &'a str) -> BoxFuture<'a, Result<bool>> {
+    Box::pin(async move { Ok(self.domain_id(zone).await?.is_some()) })
+  }
+
+  fn create_txt<'a>(
+    &'a self,
+    zone: &'a str,
+    fqdn: &'a str,
+    value: &'a str,
+  ) -> BoxFuture<'a, Result<TxtRecord>> {
+    Box::pin(async move {
+      let domain_id = self.require_domain_id(zone).await?;
+      let name = match relative_name(fqdn, zone) {
+        "@" => "",
+        name => name,
+      };
+      let req = LinodeCreateRecordReq::new(domain_id, name, value);
+      let record_id = self.append_record(req).await?;
+      Ok(TxtRecord::new(zone, fqdn, value, record_id.to_string()))
+    })
+  }
+
+  fn delete_txt<'a>(&'a self, record: &'a TxtRecord) -> BoxFuture<'a, Result<()>> {
+    Box::pin(async move {
+      let Ok(record_id) = record.id.parse() else {
+        return PlainTextSnafu {
+          message: format!("Linode Error: invalid record id {}", record.id),
+        }
+        .fail();
+      };
+      let domain_id = self.require_domain_id(&record.zone).await?;
+      self
+        .delete_record(LinodeDeleteRecordReq::new(domain_id, record_id))
+        .await
+    })
+  }
+}
+
+impl LinodeClient {
+  /// Collect items of all pages, `filter` is sent in `X-Filter` header
+  async fn list_pages<T>(&self, url: &str, filter: Option<&str>) -> Result<Vec<T>>
+  where
+    T: DeserializeOwned,
+  {
+    let mut items = vec![];
+    let mut page = 1;
+    loop {
+      let mut builder = self
+        .client
+        .get(url)
+        .query(&[("page", page), ("page_size", 500)]);
+      if let Some(filter) = filter {
+        builder = builder.header(
+          HeaderName::from_static("x-filter"),
+          str_to_header_value(filter)?,
+        );
+      }
+      let data: PageData<T> = Self::send_request(builder).await?;
+      items.extend(data.data);
+      if data.page >= data.pages {
+        return Ok(items);
+      }
+      page = data.page + 1;
+    }
+  }
+
+  async fn exec_request<R>(
+    &self,
+    method: Method,
+    url: &str,
+    req: Option<&impl Serialize>,
+  ) -> Result<R>
+  where
+    R: DeserializeOwned,
+  {
+    let mut builder = self.client.request(method, url);
+    if let Some(req) = req {
+      builder = builder.json(req);
+    }
+    Self::send_request(builder).await
+  }
+
+  async fn send_request<R>(builder: reqwest::RequestBuilder) -> Result<R>
+  where
+    R: DeserializeOwned,
+  {
+    builder
+      .send()
+      .await
+      .context(ReqwestClientSnafu)?
+      .json::<LinodeRes<R>>()
+      .await
+      .context(ReqwestClientSnafu)?
+      .unwrap_data()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::sync::{Arc, Mutex};
+
+  use super::*;
+  use crate::test_util::{StubResponse, request_body, stub_server};
+
+  /// Stub Linode api, `example.com` with id `10` has TXT record `1` with value `old` at
+  /// `_acme-challenge`, request lines and bodies are recorded
+  async fn stub_linode(requests: Arc<Mutex<Vec<String>>>) -> String {
+    stub_server(move |req| {
+      assert!(req.contains("authorization: Bearer token"));
+      let line = req.lines().next().unwrap();
+      let line = line.strip_suffix(" HTTP/1.1").unwrap();
+      requests
+        .lock()
+        .unwrap()
+        .push(format!("{} {}", line, request_body(req)));
+      let (method, path) = line.split_once(' ').unwrap();
+      match (method, path.split('?').next().unwrap()) {
+        ("GET", "/domains") => {
+          let body = if req.contains(r#"x-filter: {"domain":"example.com"}"#) {
+            r#"{"data":[{"id":10,"domain":"example.com"}],"page":1,"pages":1,"results":1}"#
+          } else {
+            r#"{"data":[],"page":1,"pages":1,"results":0}"#
+          };
+          StubResponse::ok(body)
+        }
+        ("GET", "/domains/10/records") => StubResponse::ok(
+          r#"{"data":[{"id":1,"name":"_acme-challenge","target":"old","type":"TXT","ttl_sec":300}],"page":1,"pages":1,"results":1}"#,
+        ),
+        ("POST", "/domains/10/records") => StubResponse::ok(
+          r#"{"id":2,"name":"_acme-challenge","target":"new","type":"TXT","ttl_sec":300}"#,
+        ),
+        ("DELETE", "/domains/10/records/2") => StubResponse::ok("{}"),
+        _ => StubResponse::new("404 Not Found", r#"{"errors":[{"reason":"Not found"}]}"#),
+      }
+    })
+    .await
+  }
+
+  #[tokio::test]
+  async fn create_and_delete_txt() {
+    let requests = Arc::new(Mutex::new(vec![]));
+    let endpoint = stub_linode(requests.clone()).await;
+    let client = LinodeClient::new(LinodeOption::new("token").endpoint(endpoint)).unwrap();
+    assert!(client.has_zone("example.com").await.unwrap());
+    assert!(!client.has_zone("example.org").await.unwrap());
+
+    let existed = client
+      .create_txt("example.com", "_acme-challenge.example.com", "old")
+      .await
+      .unwrap();
+    assert_eq!(existed.id, "1");
+    let record = client
+      .create_txt("example.com", "_acme-challenge.example.com", "new")
+      .await
+      .unwrap();
+    assert_eq!(record.id, "2");
+    client.delete_txt(&record).await.unwrap();
+
+    let requests = requests.lock().unwrap();
+    assert_eq!(requests.len(), 9);
+    assert!(requests[6].starts_with("POST /domains/10/records "));
+    assert!(requests[6].contains(r#""name":"_acme-challenge""#));
+    assert!(requests[6].contains(r#""target":"new""#));
+    assert!(requests[8].starts_with("DELETE /domains/10/records/2 "));
+  }
+}
@@ -0,0 +1,15 @@
+//! Implement of [Linode](https://www.linode.com/) (Akamai) DNS challenge
+//!
+//! Link: <https://techdocs.akamai.com/linode-api/reference/post-domain-record>
+
+mod client;
+pub use client::LinodeClient;
+
+mod response;
+pub use response::{LinodeDomain, LinodeRecord};
+
+mod request;
+pub use request::{LinodeCreateRecordReq, LinodeDeleteRecordReq};
+
+mod option;
+pub use option::LinodeOption;
@@ -0,0 +1,56 @@
+use std::time::Duration;
+
+use crate::{errors::Result, util::env_single_var};
+
+/// Options for create a [`crate::challenge::dns::linode::LinodeClient`] instance
+#[derive(Debug)]
+pub struct LinodeOption {
+  pub(crate) token: String,
+  pub(crate) proxy: Option<String>,
+  pub(crate) timeout: Option<Duration>,
+  pub(crate) endpoint: Option<String>,
+}
+
+impl LinodeOption {
+  /// Create option with Linode personal access token, this token must have __Domains__ read/write
+  /// scope
+  pub fn new(token: impl Into<String>) -> Self {
+    LinodeOption {
+      token: token.into(),
+      proxy: None,
+      timeout: None,
+      endpoint: None,
+    }
+  }
+
+  /// Create option from environment variable, variable name for token is __LINODE_TOKEN__ or
+  /// __EASY_ACME_LINODE_TOKEN__
+  pub fn new_from_env() -> Result<Self> {
+    Ok(Self::new(Self::env_auth_token()?))
+  }
+
+  /// Set proxy, for example, `https://127.0.0.1:8080`, `socks5://127.0.0.1:9000`, default is `None`
+  pub fn proxy(mut self, proxy: impl Into<String>) -> Self {
+    self.proxy = Some(proxy.into());
+    self
+  }
+
+  /// Set timeout, for example, `Duration::from_secs(5)`, default is `None`
+  pub fn timeout(mut self, timeout: Duration) -> Self {
+    self.timeout = Some(timeout);
+    self
+  }
+
+  /// Set api endpoint, default is `https://api.linode.com/v4`
+  pub fn endpoint(mut self, endpoint: impl Into<String>) -> Self {
+    self.endpoint = Some(endpoint.into());
+    self
+  }
+}
+
+impl LinodeOption {
+  #[inline]
+  pub(crate) fn env_auth_token() -> Result<String> {
+    env_single_var(["LINODE_TOKEN", "EASY_ACME_LINODE_TOKEN"])
+  }
+}
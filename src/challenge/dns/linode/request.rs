@@ -0,0 +1,54 @@
+use serde::Serialize;
+
+use crate::challenge::dns::RecordType;
+
+#[derive(Debug, Serialize)]
+pub struct LinodeCreateRecordReq<'a> {
+  #[serde(skip_serializing)]
+  pub(crate) domain_id: u64,
+
+  pub(crate) name: &'a str,
+
+  pub(crate) target: &'a str,
+
+  #[serde(skip_serializing_if = "Option::is_none")]
+  ttl_sec: Option<u64>,
+
+  #[serde(rename = "type")]
+  rtype: RecordType,
+}
+
+impl<'a> LinodeCreateRecordReq<'a> {
+  /// `name` is relative to domain, for example, `_acme-challenge`, empty for the domain itself
+  pub fn new(domain_id: u64, name: &'a str, value: &'a str) -> Self {
+    Self {
+      domain_id,
+      name,
+      target: value,
+      ttl_sec: None,
+      rtype: RecordType::TXT,
+    }
+  }
+
+  /// Set TTL, rounded up by Linode to one of its accepted values, default is TTL of domain
+  pub fn ttl(mut self, ttl: u64) -> Self {
+    self.ttl_sec = Some(ttl);
+    self
+  }
+}
+
+#[derive(Debug)]
+pub struct LinodeDeleteRecordReq {
+  pub(crate) domain_id: u64,
+
+  pub(crate) record_id: u64,
+}
+
+impl LinodeDeleteRecordReq {
+  pub fn new(domain_id: u64, record_id: u64) -> Self {
+    Self {
+      domain_id,
+      record_id,
+    }
+  }
+}
@@ -0,0 +1,78 @@
+use serde::Deserialize;
+
+use crate::errors::{PlainTextSnafu, Result};
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub(crate) enum LinodeRes<T> {
+  Failure(FailureData),
+  Success(T),
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct PageData<T> {
+  #[serde(default = "Vec::new")]
+  pub(crate) data: Vec<T>,
+
+  pub(crate) page: u64,
+
+  pub(crate) pages: u64,
+}
+
+/// DNS record returned by [`crate::challenge::dns::linode::LinodeClient::list_records`]
+#[derive(Debug, Clone, Deserialize)]
+pub struct LinodeRecord {
+  pub id: u64,
+
+  /// Name relative to domain, for example, `_acme-challenge`
+  pub name: String,
+
+  pub target: String,
+
+  #[serde(rename = "type")]
+  pub rtype: String,
+
+  pub ttl_sec: Option<u64>,
+}
+
+/// Domain returned by [`crate::challenge::dns::linode::LinodeClient::list_domains`]
+#[derive(Debug, Clone, Deserialize)]
+pub struct LinodeDomain {
+  pub id: u64,
+
+  pub domain: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct FailureData {
+  errors: Vec<ErrorData>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ErrorData {
+  reason: String,
+
+  field: Option<String>,
+}
+
+impl<T> LinodeRes<T> {
+  pub fn unwrap_data(self) -> Result<T> {
+    match self {
+      LinodeRes::Success(data) => Ok(data),
+      LinodeRes::Failure(failure) => {
+        let reasons: Vec<String> = failure
+          .errors
+          .iter()
+          .map(|e| match &e.field {
+            Some(field) => format!("{}: {}", field, e.reason),
+            None => e.reason.clone(),
+          })
+          .collect();
+        PlainTextSnafu {
+          message: format!("Linode Error: {}", reasons.join(", ")),
+        }
+        .fail()
+      }
+    }
+  }
+}
@@ -1,15 +1,210 @@
+use futures_util::future::BoxFuture;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 
+use crate::{errors::Result, util::base64_url_unpadded_str};
+
+pub mod acme_dns;
 pub mod aliyun;
+pub mod azure;
 pub mod cloudflare;
+pub mod digitalocean;
+pub mod dnspod;
+pub mod exec;
+pub mod gandi;
+pub mod google_cloud;
+pub mod hetzner;
+pub mod huaweicloud;
+pub mod linode;
+pub mod ovh;
+pub mod porkbun;
+pub mod powerdns;
+pub mod propagation;
+pub mod resolver;
+pub mod rfc2136;
+pub mod route53;
+pub mod vultr;
+
+mod persist;
+pub use persist::{DnsPersistPolicy, DnsPersistRecord, persist_name};
+
+mod registry;
+pub use registry::{DnsProviderConfig, DnsProviderFactory, DnsProviderRegistry};
+
+mod solver;
+pub use solver::{DnsChallengeRecord, DnsSolver};
+
+/// Common operations of DNS providers, used by [`DnsSolver`] to manage TXT records of challenges
+pub trait DnsChallengeClient: Send + Sync {
+  /// Whether `zone` (for example, `example.com`) is hosted by this provider
+  fn has_zone<'a>(&'a self, zone: &'a str) -> BoxFuture<'a, Result<bool>>;
+
+  /// Create TXT record with `value` at `fqdn` in `zone`, existing record with the same value is
+  /// reused, other values at `fqdn` are kept, for example, values of `example.com` and
+  /// `*.example.com` are both at `_acme-challenge.example.com`
+  fn create_txt<'a>(
+    &'a self,
+    zone: &'a str,
+    fqdn: &'a str,
+    value: &'a str,
+  ) -> BoxFuture<'a, Result<TxtRecord>>;
+
+  /// Delete TXT record created by [`Self::create_txt`], other values at `fqdn` are kept
+  fn delete_txt<'a>(&'a self, record: &'a TxtRecord) -> BoxFuture<'a, Result<()>>;
+}
+
+/// Boxed provider, for example, the one constructed by [`DnsProviderRegistry`]
+impl<T: DnsChallengeClient + ?Sized> DnsChallengeClient for Box<T> {
+  fn has_zone<'a>(&'a self, zone: &'a str) -> BoxFuture<'a, Result<bool>> {
+    (**self).has_zone(zone)
+  }
+
+  fn create_txt<'a>(
+    &'a self,
+    zone: &'a str,
+    fqdn: &'a str,
+    value: &'a str,
+  ) -> BoxFuture<'a, Result<TxtRecord>> {
+    (**self).create_txt(zone, fqdn, value)
+  }
+
+  fn delete_txt<'a>(&'a self, record: &'a TxtRecord) -> BoxFuture<'a, Result<()>> {
+    (**self).delete_txt(record)
+  }
+}
+
+/// TXT record created by [`DnsChallengeClient::create_txt`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TxtRecord {
+  /// Zone of record, for example, `example.com`
+  pub zone: String,
+
+  /// Full name of record, for example, `_acme-challenge.example.com`
+  pub fqdn: String,
+
+  pub value: String,
+
+  /// Id of record in provider, empty if provider has no such concept
+  pub id: String,
+}
+
+impl TxtRecord {
+  pub fn new(
+    zone: impl Into<String>,
+    fqdn: impl Into<String>,
+    value: impl Into<String>,
+    id: impl Into<String>,
+  ) -> Self {
+    TxtRecord {
+      zone: zone.into(),
+      fqdn: fqdn.into(),
+      value: value.into(),
+      id: id.into(),
+    }
+  }
+
+  /// Name of record relative to zone, `@` if it is the zone apex
+  pub fn relative_name(&self) -> &str {
+    relative_name(&self.fqdn, &self.zone)
+  }
+}
+
+/// Name of TXT record for `domain`, for example, `_acme-challenge.example.com` of `example.com`,
+/// wildcard domain shares the name of its base domain, `*.example.com` is validated at
+/// `_acme-challenge.example.com` too
+pub fn challenge_name(domain: &str) -> String {
+  let domain = domain.strip_prefix("*.").unwrap_or(domain);
+  format!("_acme-challenge.{}", domain.trim_end_matches('.'))
+}
+
+/// Value of TXT record of dns-01 challenge, base64url of SHA-256 digest of `key_authorization`
+pub fn challenge_value(key_authorization: &str) -> String {
+  base64_url_unpadded_str(Sha256::digest(key_authorization))
+}
 
-// pub trait DnsChallengeClient {
-//   async fn create_record();
-//
-//   async fn delete_record();
-// }
+/// Name of `fqdn` relative to `zone`, for example, `_acme-challenge` of
+/// `_acme-challenge.example.com` in `example.com`, and `@` if they are the same
+pub fn relative_name<'a>(fqdn: &'a str, zone: &str) -> &'a str {
+  let fqdn = fqdn.trim_end_matches('.');
+  let zone = zone.trim_end_matches('.');
+  if fqdn.eq_ignore_ascii_case(zone) {
+    return "@";
+  }
+  match fqdn.len().checked_sub(zone.len() + 1) {
+    Some(split)
+      if split > 0
+        && fqdn.get(split..split + 1) == Some(".")
+        && fqdn[split + 1..].eq_ignore_ascii_case(zone) =>
+    {
+      &fqdn[..split]
+    }
+    _ => fqdn,
+  }
+}
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum RecordType {
+  A,
+  NS,
+  CNAME,
+  SOA,
   TXT,
+  AAAA,
+}
+
+impl RecordType {
+  /// Type value in DNS message, see [RFC 1035](https://www.rfc-editor.org/rfc/rfc1035#section-3.2.2)
+  pub fn code(&self) -> u16 {
+    match self {
+      RecordType::A => 1,
+      RecordType::NS => 2,
+      RecordType::CNAME => 5,
+      RecordType::SOA => 6,
+      RecordType::TXT => 16,
+      RecordType::AAAA => 28,
+    }
+  }
+
+  pub fn from_code(code: u16) -> Option<Self> {
+    let rtype = match code {
+      1 => RecordType::A,
+      2 => RecordType::NS,
+      5 => RecordType::CNAME,
+      6 => RecordType::SOA,
+      16 => RecordType::TXT,
+      28 => RecordType::AAAA,
+      _ => return None,
+    };
+    Some(rtype)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn challenge_value_without_padding() {
+    let value = challenge_value(
+      "evaGxfADs6pSRb2LAv9IZf17Dt3juxGJ-PCt92wr-oA.9jg46WB3rR_AHD-EBXdN7cBkH1WOu0tA3M9fm21mqTI",
+    );
+    assert_eq!(value, "lCM7cZyQXcVHK2nnW3jjAhNT3Fvm18UN-kWZZknKoYM");
+  }
+
+  #[test]
+  fn relative_name_of_zone() {
+    assert_eq!(
+      relative_name("_acme-challenge.example.com", "example.com"),
+      "_acme-challenge"
+    );
+    assert_eq!(relative_name("Example.com.", "example.com"), "@");
+    assert_eq!(
+      relative_name("_acme-challenge.example.net", "example.com"),
+      "_acme-challenge.example.net"
+    );
+    assert_eq!(
+      relative_name("badexample.com", "example.com"),
+      "badexample.com"
+    );
+  }
 }
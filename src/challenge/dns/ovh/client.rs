@@ -0,0 +1,383 @@
+use std::sync::Mutex;
+
+use futures_util::future::BoxFuture;
+use http::{HeaderName, Method, StatusCode, header::CONTENT_TYPE};
+use jiff::Timestamp;
+use reqwest::{Client, ClientBuilder};
+use serde::{Serialize, de::DeserializeOwned};
+use sha1::{Digest, Sha1};
+use snafu::ResultExt;
+
+use crate::{
+  challenge::dns::{
+    DnsChallengeClient, TxtRecord,
+    ovh::{OvhCreateRecordReq, OvhOption, OvhRecord, response::FailureData},
+    relative_name,
+  },
+  errors::{PlainTextSnafu, ReqwestClientSnafu, Result},
+  util::{json_serialize, str_to_header_value},
+};
+
+/// Client for OVH api, requests are signed with application secret and consumer key
+#[derive(Debug)]
+pub struct OvhClient {
+  endpoint: String,
+  application_key: String,
+  application_secret: String,
+  consumer_key: String,
+  time_delta: Mutex<Option<i64>>,
+  client: Client,
+}
+
+impl OvhClient {
+  pub fn new(option: OvhOption) -> Result<Self> {
+    let endpoint = option.endpoint_url();
+    let OvhOption {
+      application_key,
+      application_secret,
+      consumer_key,
+      proxy,
+      timeout,
+      ..
+    } = option;
+    let mut client = ClientBuilder::new();
+    if let Some(timeout) = timeout {
+      client = client.timeout(timeout);
+    }
+    if let Some(proxy) = proxy {
+      let proxy = reqwest::Proxy::all(proxy).context(ReqwestClientSnafu)?;
+      client = client.proxy(proxy);
+    }
+    let client = client.build().context(ReqwestClientSnafu)?;
+    Ok(Self {
+      endpoint,
+      application_key,
+      application_secret,
+      consumer_key,
+      time_delta: Mutex::new(None),
+      client,
+    })
+  }
+
+  pub fn new_from_env() -> Result<Self> {
+    Self::new(OvhOption::new_from_env()?)
+  }
+
+  /// Create DNS TXT record, [`Self::refresh_zone`] must be called to apply it
+  pub async fn create_record(&self, req: OvhCreateRecordReq<'_>) -> Result<u64> {
+    let path = format!("/domain/zone/{}/record", req.zone);
+    let record: OvhRecord = self.exec_request(Method::POST, &path, Some(&req)).await?;
+    Ok(record.id)
+  }
+
+  /// Delete DNS record, [`Self::refresh_zone`] must be called to apply it
+  pub async fn delete_record(&self, zone: &str, record_id: u64) -> Result<()> {
+    let path = format!("/domain/zone/{}/record/{}", zone, record_id);
+    self.exec_request(Method::DELETE, &path, None::<&()>).await
+  }
+
+  /// Ids of TXT records `sub_domain`, relative to zone
+  pub async fn list_records(&self, zone: &str, sub_domain: &str) -> Result<Vec<u64>> {
+    let path = format!(
+      "/domain/zone/{}/record?fieldType=TXT&subDomain={}",
+      zone, sub_domain
+    );
+    self.exec_request(Method::GET, &path, None::<&()>).await
+  }
+
+  pub async fn record(&self, zone: &str, record_id: u64) -> Result<OvhRecord> {
+    let path = format!("/domain/zone/{}/record/{}", zone, record_id);
+    self.exec_request(Method::GET, &path, None::<&()>).await
+  }
+
+  /// Apply changes of zone
+  pub async fn refresh_zone(&self, zone: &str) -> Result<()> {
+    let path = format!("/domain/zone/{}/refresh", zone);
+    self.exec_request(Method::POST, &path, None::<&()>).await
+  }
+
+  /// Append value to the TXT records of `req`'s name, the existing record is reused if it has the
+  /// same value
+  pub async fn append_record(&self, req: OvhCreateRecordReq<'_>) -> Result<u64> {
+    for record_id in self.list_records(req.zone, req.sub_domain).await? {
+      let record = self.record(req.zone, record_id).await?;
+      if record.target.trim_matches('"') == req.target {
+        return Ok(record_id);
+      }
+    }
+    self.create_record(req).await
+  }
+
+  /// Whether DNS zone `zone` is in the account
+  pub async fn has_domain(&self, zone: &str) -> Result<bool> {
+    let path = format!("/domain/zone/{}", zone);
+    let res = self.send_request(Method::GET, &path, None).await?;
+    match res.status() {
+      StatusCode::NOT_FOUND | StatusCode::FORBIDDEN => Ok(false),
+      status if status.is_success() => Ok(true),
+      _ => Self::parse_response::<bool>(res).await,
+    }
+  }
+}
+
+impl DnsChallengeClient for OvhClient {
+  fn has_zone<'a>(&'a self, zone: &'a str) -> BoxFuture<'a, Result<bool>> {
+    Box::pin(async move { self.has_domain(zone).await })
+  }
+
+  fn create_txt<'a>(
+    &'a self,
+    zone: &'a str,
+    fqdn: &'a str,
+    value: &'a str,
+  ) -> BoxFuture<'a, Result<TxtRecord>> {
+    Box::pin(async move {
+      let sub_domain = match relative_name(fqdn, zone) {
+        "@" => "",
+        name => name,
+      };
+      let req = OvhCreateRecordReq::new(zone, sub_domain, value).ttl(60);
+      let record_id = self.append_record(req).await?;
+      self.refresh_zone(zone).await?;
+      Ok(TxtRecord::new(zone, fqdn, value, record_id.to_string()))
+    })
+  }
+
+  fn delete_txt<'a>(&'a self, record: &'a TxtRecord) -> BoxFuture<'a, Result<()>> {
+    Box::pin(async move {
+      let Ok(record_id) = record.id.parse() else {
+        return PlainTextSnafu {
+          message: format!("OVH Error: invalid record id {}", record.id),
+        }
+        .fail();
+      };
+      self.delete_record(&record.zone, record_id).await?;
+      self.refresh_zone(&record.zone).await
+    })
+  }
+}
+
+impl OvhClient {
+  async fn exec_request<R>(
+    &self,
+    method: Method,
+    path: &str,
+    req: Option<&impl Serialize>,
+  ) -> Result<R>
+  where
+    R: DeserializeOwned,
+  {
+    let body = match req {
+      Some(req) => Some(json_serialize(req)?),
+      None => None,
+    };
+    let res = self.send_request(method, path, body).await?;
+    Self::parse_response(res).await
+  }
+
+  /// Send request with [signature](https://help.ovhcloud.com/csm/en-api-getting-started-ovhcloud-api?id=kb_article_view&sysparm_article=KB0042784)
+  /// of timestamp in server clock
+  async fn send_request(
+    &self,
+    method: Method,
+    path: &str,
+    body: Option<String>,
+  ) -> Result<reqwest::Response> {
+    let url = format!("{}{}", self.endpoint, path);
+    let body = body.unwrap_or_default();
+    let timestamp = Timestamp::now().as_second() + self.time_delta().await?;
+    let signature = self.signature(&method, &url, &body, timestamp);
+    let timestamp = timestamp.to_string();
+    let mut builder = self
+      .client
+      .request(method, &url)
+      .header(
+        HeaderName::from_static("x-ovh-application"),
+        str_to_header_value(&self.application_key)?,
+      )
+      .header(
+        HeaderName::from_static("x-ovh-consumer"),
+        str_to_header_value(&self.consumer_key)?,
+      )
+      .header(
+        HeaderName::from_static("x-ovh-timestamp"),
+        str_to_header_value(&timestamp)?,
+      )
+      .header(
+        HeaderName::from_static("x-ovh-signature"),
+        str_to_header_value(&signature)?,
+      );
+    if !body.is_empty() {
+      builder = builder.header(CONTENT_TYPE, "application/json").body(body);
+    }
+    builder.send().await.context(ReqwestClientSnafu)
+  }
+
+  /// `$1$` + SHA1 of `AS+CK+METHOD+URL+BODY+TIMESTAMP`
+  fn signature(&self, method: &Method, url: &str, body: &str, timestamp: i64) -> String {
+    let data = format!(
+      "{}+{}+{}+{}+{}+{}",
+      self.application_secret,
+      self.consumer_key,
+      method.as_str(),
+      url,
+      body,
+      timestamp
+    );
+    format!("$1${}", hex::encode(Sha1::digest(data)))
+  }
+
+  /// Server time minus local time in seconds, fetched from `/auth/time` once like the official
+  /// clients do, so requests are not rejected for skew of local clock
+  async fn time_delta(&self) -> Result<i64> {
+    if let Some(delta) = *self.lock_time_delta() {
+      return Ok(delta);
+    }
+    let url = format!("{}/auth/time", self.endpoint);
+    let res = self
+      .client
+      .get(url)
+      .send()
+      .await
+      .context(ReqwestClientSnafu)?;
+    let server_time: i64 = Self::parse_response(res).await?;
+    let delta = server_time - Timestamp::now().as_second();
+    *self.lock_time_delta() = Some(delta);
+    Ok(delta)
+  }
+
+  fn lock_time_delta(&self) -> std::sync::MutexGuard<'_, Option<i64>> {
+    self
+      .time_delta
+      .lock()
+      .unwrap_or_else(|err| err.into_inner())
+  }
+
+  async fn parse_response<R>(res: reqwest::Response) -> Result<R>
+  where
+    R: DeserializeOwned,
+  {
+    let status = res.status();
+    let text = res.text().await.context(ReqwestClientSnafu)?;
+    if status.is_success() {
+      // some apis respond with empty body
+      let text = if text.is_empty() { "null" } else { &text };
+      if let Ok(data) = serde_json::from_str(text) {
+        return Ok(data);
+      }
+    }
+    let message = match serde_json::from_str::<FailureData>(&text) {
+      Ok(failure) => match failure.class {
+        Some(class) => format!("{}, {}", class, failure.message),
+        None => failure.message,
+      },
+      Err(_) => text,
+    };
+    PlainTextSnafu {
+      message: format!("OVH Error: {}, {}", status, message),
+    }
+    .fail()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::sync::{Arc, Mutex};
+
+  use super::*;
+  use crate::test_util::{StubResponse, request_body, stub_server};
+
+  const SERVER_TIME: i64 = 1457018875;
+
+  #[test]
+  fn signature_of_fixed_timestamp() {
+    let client = OvhClient::new(OvhOption::new("ak", "as", "ck")).unwrap();
+    let url = "https://eu.api.ovh.com/1.0/domain/zone/example.com";
+    assert_eq!(
+      client.signature(&Method::GET, url, "", SERVER_TIME),
+      "$1$c0b1b6dd15e99db04e5e30f194bc9af7a1adf692"
+    );
+    let body = r#"{"fieldType":"TXT","subDomain":"_acme-challenge","target":"new","ttl":60}"#;
+    assert_eq!(
+      client.signature(&Method::POST, &format!("{}/record", url), body, SERVER_TIME),
+      "$1$21c862f499032b69470bd820a9cc3fbff0c46aa9"
+    );
+  }
+
+  /// Stub OVH api with clock of [`SERVER_TIME`], zone `example.com` has TXT record `1` with value
+  /// `old` at `_acme-challenge`. Signature of every request is checked, request lines and bodies
+  /// are recorded
+  async fn stub_ovh(requests: Arc<Mutex<Vec<String>>>) -> String {
+    stub_server(move |req| {
+      let line = req.lines().next().unwrap();
+      let line = line.strip_suffix(" HTTP/1.1").unwrap();
+      let body = request_body(req);
+      requests.lock().unwrap().push(format!("{} {}", line, body));
+      if line == "GET /auth/time" {
+        return StubResponse::ok(SERVER_TIME.to_string());
+      }
+      let header = |name: &str| {
+        req
+          .lines()
+          .find_map(|l| l.strip_prefix(&format!("{}: ", name)))
+          .unwrap()
+      };
+      assert_eq!(header("x-ovh-application"), "ak");
+      assert_eq!(header("x-ovh-consumer"), "ck");
+      let timestamp = header("x-ovh-timestamp");
+      let skew = timestamp.parse::<i64>().unwrap() - SERVER_TIME;
+      assert!((0..5).contains(&skew), "timestamp {} is not in server clock", timestamp);
+      let (method, path) = line.split_once(' ').unwrap();
+      let url = format!("http://{}{}", header("host"), path);
+      let data = format!("as+ck+{}+{}+{}+{}", method, url, body, timestamp);
+      let signature = format!("$1${}", hex::encode(Sha1::digest(data)));
+      assert_eq!(header("x-ovh-signature"), signature);
+      match line {
+        "GET /domain/zone/example.com" => StubResponse::ok(r#"{"name":"example.com"}"#),
+        "GET /domain/zone/example.com/record?fieldType=TXT&subDomain=_acme-challenge" => {
+          StubResponse::ok("[1]")
+        }
+        "GET /domain/zone/example.com/record/1" => StubResponse::ok(
+          r#"{"id":1,"zone":"example.com","fieldType":"TXT","subDomain":"_acme-challenge","target":"\"old\"","ttl":60}"#,
+        ),
+        "POST /domain/zone/example.com/record" => StubResponse::ok(
+          r#"{"id":2,"zone":"example.com","fieldType":"TXT","subDomain":"_acme-challenge","target":"\"new\"","ttl":60}"#,
+        ),
+        "POST /domain/zone/example.com/refresh" | "DELETE /domain/zone/example.com/record/2" => {
+          StubResponse::ok("null")
+        }
+        _ => StubResponse::new(
+          "404 Not Found",
+          r#"{"class":"Client::NotFound","message":"This service does not exist"}"#,
+        ),
+      }
+    })
+    .await
+  }
+
+  #[tokio::test]
+  async fn create_and_delete_txt() {
+    let requests = Arc::new(Mutex::new(vec![]));
+    let endpoint = stub_ovh(requests.clone()).await;
+    let client = OvhClient::new(OvhOption::new("ak", "as", "ck").endpoint(endpoint)).unwrap();
+    assert!(client.has_zone("example.com").await.unwrap());
+    assert!(!client.has_zone("example.org").await.unwrap());
+
+    let record = client
+      .create_txt("example.com", "_acme-challenge.example.com", "new")
+      .await
+      .unwrap();
+    assert_eq!(record.id, "2");
+    client.delete_txt(&record).await.unwrap();
+
+    let requests = requests.lock().unwrap();
+    // clock is synced once
+    let syncs = requests.iter().filter(|r| r.starts_with("GET /auth/time"));
+    assert_eq!(syncs.count(), 1);
+    assert_eq!(requests.len(), 9);
+    assert!(requests[5].starts_with("POST /domain/zone/example.com/record "));
+    assert!(requests[5].contains(r#""subDomain":"_acme-challenge""#));
+    assert!(requests[5].contains(r#""target":"new""#));
+    assert!(requests[7].starts_with("DELETE /domain/zone/example.com/record/2 "));
+  }
+}
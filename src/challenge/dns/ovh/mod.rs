@@ -0,0 +1,15 @@
+//! Implement of [OVHcloud](https://www.ovhcloud.com/) DNS challenge
+//!
+//! Link: <https://eu.api.ovh.com/console/?section=%2Fdomain>
+
+mod client;
+pub use client::OvhClient;
+
+mod response;
+pub use response::OvhRecord;
+
+mod request;
+pub use request::OvhCreateRecordReq;
+
+mod option;
+pub use option::OvhOption;
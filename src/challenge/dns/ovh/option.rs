@@ -0,0 +1,94 @@
+use std::time::Duration;
+
+use crate::{errors::Result, util::env_single_var};
+
+/// Options for create an [`crate::challenge::dns::ovh::OvhClient`] instance
+#[derive(Debug)]
+pub struct OvhOption {
+  pub(crate) endpoint: String,
+  pub(crate) application_key: String,
+  pub(crate) application_secret: String,
+  pub(crate) consumer_key: String,
+  pub(crate) proxy: Option<String>,
+  pub(crate) timeout: Option<Duration>,
+}
+
+impl OvhOption {
+  /// Create option with application key, application secret and consumer key, the consumer key
+  /// must be granted `GET`, `POST` and `DELETE` on `/domain/zone/*`
+  pub fn new(
+    application_key: impl Into<String>,
+    application_secret: impl Into<String>,
+    consumer_key: impl Into<String>,
+  ) -> Self {
+    OvhOption {
+      endpoint: "ovh-eu".to_string(),
+      application_key: application_key.into(),
+      application_secret: application_secret.into(),
+      consumer_key: consumer_key.into(),
+      proxy: None,
+      timeout: None,
+    }
+  }
+
+  /// Create option from environment variable, variable names are __OVH_APPLICATION_KEY__ (or
+  /// __OVH_AK__), __OVH_APPLICATION_SECRET__ (or __OVH_AS__), __OVH_CONSUMER_KEY__ (or
+  /// __OVH_CK__) and optional __OVH_ENDPOINT__ (or __OVH_END_POINT__), with __EASY_ACME_OVH___
+  /// prefixed alternatives, for example, __EASY_ACME_OVH_APPLICATION_KEY__
+  pub fn new_from_env() -> Result<Self> {
+    let option = Self::new(
+      env_single_var([
+        "OVH_APPLICATION_KEY",
+        "OVH_AK",
+        "EASY_ACME_OVH_APPLICATION_KEY",
+      ])?,
+      env_single_var([
+        "OVH_APPLICATION_SECRET",
+        "OVH_AS",
+        "EASY_ACME_OVH_APPLICATION_SECRET",
+      ])?,
+      env_single_var(["OVH_CONSUMER_KEY", "OVH_CK", "EASY_ACME_OVH_CONSUMER_KEY"])?,
+    );
+    match env_single_var(["OVH_ENDPOINT", "OVH_END_POINT", "EASY_ACME_OVH_ENDPOINT"]) {
+      Ok(endpoint) => Ok(option.endpoint(endpoint)),
+      Err(_) => Ok(option),
+    }
+  }
+
+  /// Set endpoint, either name of `ovh-eu`, `ovh-ca`, `ovh-us`, `kimsufi-eu`, `kimsufi-ca`,
+  /// `soyoustart-eu`, `soyoustart-ca`, or url like `https://eu.api.ovh.com/1.0`, default is
+  /// `ovh-eu`
+  pub fn endpoint(mut self, endpoint: impl Into<String>) -> Self {
+    self.endpoint = endpoint.into();
+    self
+  }
+
+  /// Set proxy, for example, `https://127.0.0.1:8080`, `socks5://127.0.0.1:9000`, default is `None`
+  pub fn proxy(mut self, proxy: impl Into<String>) -> Self {
+    self.proxy = Some(proxy.into());
+    self
+  }
+
+  /// Set timeout, for example, `Duration::from_secs(5)`, default is `None`
+  pub fn timeout(mut self, timeout: Duration) -> Self {
+    self.timeout = Some(timeout);
+    self
+  }
+}
+
+impl OvhOption {
+  /// Url of endpoint name, or the endpoint itself if it is not a known name
+  pub(crate) fn endpoint_url(&self) -> String {
+    let url = match self.endpoint.as_str() {
+      "ovh-eu" => "https://eu.api.ovh.com/1.0",
+      "ovh-ca" => "https://ca.api.ovh.com/1.0",
+      "ovh-us" => "https://api.us.ovhcloud.com/1.0",
+      "kimsufi-eu" => "https://eu.api.kimsufi.com/1.0",
+      "kimsufi-ca" => "https://ca.api.kimsufi.com/1.0",
+      "soyoustart-eu" => "https://eu.api.soyoustart.com/1.0",
+      "soyoustart-ca" => "https://ca.api.soyoustart.com/1.0",
+      url => url,
+    };
+    url.trim_end_matches('/').to_string()
+  }
+}
@@ -0,0 +1,37 @@
+use serde::Serialize;
+
+use crate::challenge::dns::RecordType;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OvhCreateRecordReq<'a> {
+  #[serde(skip_serializing)]
+  pub(crate) zone: &'a str,
+
+  pub(crate) field_type: RecordType,
+
+  pub(crate) sub_domain: &'a str,
+
+  pub(crate) target: &'a str,
+
+  #[serde(skip_serializing_if = "Option::is_none")]
+  ttl: Option<u64>,
+}
+
+impl<'a> OvhCreateRecordReq<'a> {
+  /// `sub_domain` is relative to `zone`, for example, `_acme-challenge`, empty for the zone itself
+  pub fn new(zone: &'a str, sub_domain: &'a str, value: &'a str) -> Self {
+    Self {
+      zone,
+      field_type: RecordType::TXT,
+      sub_domain,
+      target: value,
+      ttl: None,
+    }
+  }
+
+  pub fn ttl(mut self, ttl: u64) -> Self {
+    self.ttl = Some(ttl);
+    self
+  }
+}
@@ -0,0 +1,26 @@
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct FailureData {
+  pub(crate) class: Option<String>,
+
+  pub(crate) message: String,
+}
+
+/// DNS record returned by [`crate::challenge::dns::ovh::OvhClient::record`]
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OvhRecord {
+  pub id: u64,
+
+  pub zone: String,
+
+  pub field_type: String,
+
+  /// Name relative to zone, for example, `_acme-challenge`
+  pub sub_domain: String,
+
+  pub target: String,
+
+  pub ttl: Option<u64>,
+}
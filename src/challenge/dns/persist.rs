@@ -0,0 +1,139 @@
+use crate::challenge::dns::resolver::name_eq;
+
+/// Scope of persistent record
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DnsPersistPolicy {
+  /// Record also authorizes subdomains and wildcard of the domain
+  Wildcard,
+}
+
+/// Value of persistent TXT record of
+/// [dns-persist-01](https://datatracker.ietf.org/doc/draft-ietf-acme-dns-persist/) challenge,
+/// which authorizes an ACME account of the CA to issue certificates for the domain, for example,
+/// `letsencrypt.org; accounturi=https://acme-v02.api.letsencrypt.org/acme/acct/1234`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DnsPersistRecord {
+  /// Issuer domain name of CA, one of `issuer-domain-names` in challenge
+  pub issuer: String,
+
+  /// Url of ACME account
+  pub account_uri: String,
+
+  /// Scope of authorization, `None` authorizes the domain itself only
+  pub policy: Option<DnsPersistPolicy>,
+
+  /// Unix timestamp in seconds after which the record is not valid
+  pub persist_until: Option<i64>,
+}
+
+impl DnsPersistRecord {
+  /// Create record authorizing account at `account_uri` of CA `issuer`, without policy and
+  /// expiration
+  pub fn new(issuer: impl Into<String>, account_uri: impl Into<String>) -> Self {
+    DnsPersistRecord {
+      issuer: issuer.into(),
+      account_uri: account_uri.into(),
+      policy: None,
+      persist_until: None,
+    }
+  }
+
+  /// Set policy, default is `None` (the domain itself only)
+  pub fn policy(mut self, policy: DnsPersistPolicy) -> Self {
+    self.policy = Some(policy);
+    self
+  }
+
+  /// Set expiration as unix timestamp in seconds, default is `None` (never expires)
+  pub fn persist_until(mut self, timestamp: i64) -> Self {
+    self.persist_until = Some(timestamp);
+    self
+  }
+
+  /// TXT value of record, issuer domain name followed by `;` separated parameters
+  pub fn value(&self) -> String {
+    let mut value = format!(
+      "{}; accounturi={}",
+      self.issuer.trim_end_matches('.').to_lowercase(),
+      self.account_uri
+    );
+    if let Some(DnsPersistPolicy::Wildcard) = self.policy {
+      value.push_str("; policy=wildcard");
+    }
+    if let Some(timestamp) = self.persist_until {
+      value.push_str(&format!("; persistUntil={}", timestamp));
+    }
+    value
+  }
+
+  /// Parse TXT value, `None` if it has no issuer or account, unknown parameters are ignored
+  pub fn parse(value: &str) -> Option<Self> {
+    let mut parts = value.split(';').map(str::trim);
+    let issuer = parts.next().filter(|issuer| !issuer.is_empty())?;
+    let mut record = DnsPersistRecord::new(issuer, "");
+    for part in parts {
+      let Some((key, value)) = part.split_once('=') else {
+        continue;
+      };
+      match key.trim().to_lowercase().as_str() {
+        "accounturi" => record.account_uri = value.trim().to_string(),
+        "policy" if value.trim().eq_ignore_ascii_case("wildcard") => {
+          record.policy = Some(DnsPersistPolicy::Wildcard)
+        }
+        "persistuntil" => record.persist_until = value.trim().parse().ok(),
+        _ => {}
+      }
+    }
+    if record.account_uri.is_empty() {
+      return None;
+    }
+    Some(record)
+  }
+
+  /// Whether `other` is of the same issuer and account
+  pub fn is_same_account(&self, other: &DnsPersistRecord) -> bool {
+    name_eq(&self.issuer, &other.issuer) && self.account_uri == other.account_uri
+  }
+
+  /// Whether the record is not valid at `now`, unix timestamp in seconds
+  pub fn is_expired(&self, now: i64) -> bool {
+    self.persist_until.is_some_and(|until| until < now)
+  }
+}
+
+/// Name of persistent TXT record for `domain`, for example, `_validation-persist.example.com` of
+/// `example.com` and `*.example.com`
+pub fn persist_name(domain: &str) -> String {
+  let domain = domain.trim_end_matches('.');
+  format!(
+    "_validation-persist.{}",
+    domain.strip_prefix("*.").unwrap_or(domain)
+  )
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn format_and_parse() {
+    let record = DnsPersistRecord::new("LetsEncrypt.org.", "https://example.com/acct/1")
+      .policy(DnsPersistPolicy::Wildcard)
+      .persist_until(1767225600);
+    let value = record.value();
+    assert_eq!(
+      value,
+      "letsencrypt.org; accounturi=https://example.com/acct/1; policy=wildcard; \
+       persistUntil=1767225600"
+    );
+    let parsed = DnsPersistRecord::parse(&value).unwrap();
+    assert!(parsed.is_same_account(&record));
+    assert_eq!(parsed.policy, Some(DnsPersistPolicy::Wildcard));
+    assert_eq!(parsed.persist_until, Some(1767225600));
+    assert!(DnsPersistRecord::parse("letsencrypt.org").is_none());
+    assert_eq!(
+      persist_name("*.example.com"),
+      "_validation-persist.example.com"
+    );
+  }
+}
@@ -0,0 +1,230 @@
+use futures_util::future::BoxFuture;
+use reqwest::{Client, ClientBuilder};
+use serde::{Serialize, de::DeserializeOwned};
+use snafu::ResultExt;
+
+use crate::{
+  challenge::dns::{
+    DnsChallengeClient, TxtRecord,
+    porkbun::{
+      PorkbunCreateRecordReq, PorkbunOption, PorkbunRecord,
+      request::PorkbunReq,
+      response::{CreateData, PorkbunRes, RecordListData},
+    },
+    relative_name,
+  },
+  errors::{PlainTextSnafu, ReqwestClientSnafu, Result},
+};
+
+const API_ENDPOINT: &str = "https://api.porkbun.com/api/json/v3";
+
+#[derive(Debug)]
+pub struct PorkbunClient {
+  api_key: String,
+  secret_api_key: String,
+  endpoint: String,
+  client: Client,
+}
+
+impl PorkbunClient {
+  pub fn new(option: PorkbunOption) -> Result<Self> {
+    let PorkbunOption {
+      api_key,
+      secret_api_key,
+      proxy,
+      timeout,
+      endpoint,
+    } = option;
+    let mut client = ClientBuilder::new();
+    if let Some(timeout) = timeout {
+      client = client.timeout(timeout);
+    }
+    if let Some(proxy) = proxy {
+      let proxy = reqwest::Proxy::all(proxy).context(ReqwestClientSnafu)?;
+      client = client.proxy(proxy);
+    }
+    let client = client.build().context(ReqwestClientSnafu)?;
+    Ok(Self {
+      api_key,
+      secret_api_key,
+      endpoint: endpoint.unwrap_or_else(|| API_ENDPOINT.to_string()),
+      client,
+    })
+  }
+
+  pub fn new_from_env() -> Result<Self> {
+    Self::new(PorkbunOption::new_from_env()?)
+  }
+
+  pub async fn create_record(&self, req: PorkbunCreateRecordReq<'_>) -> Result<String> {
+    let url = format!("{}/dns/create/{}", self.endpoint, req.domain);
+    let data: CreateData = self.exec_request(&url, req).await?;
+    // id is number in response, but string in record list
+    let id = match data.id {
+      serde_json::Value::String(id) => id,
+      id => id.to_string(),
+    };
+    Ok(id)
+  }
+
+  pub async fn delete_record(&self, domain: &str, record_id: &str) -> Result<()> {
+    let url = format!("{}/dns/delete/{}/{}", self.endpoint, domain, record_id);
+    let _: serde_json::Value = self.exec_request(&url, ()).await?;
+    Ok(())
+  }
+
+  /// List TXT records of `name`, relative to domain, empty for the domain itself
+  pub async fn list_records(&self, domain: &str, name: &str) -> Result<Vec<PorkbunRecord>> {
+    let url = format!(
+      "{}/dns/retrieveByNameType/{}/TXT/{}",
+      self.endpoint, domain, name
+    );
+    let data: RecordListData = self.exec_request(&url, ()).await?;
+    Ok(data.records)
+  }
+
+  /// Append value to the TXT records of `req`'s name, the existing record is reused if it has the
+  /// same value
+  pub async fn append_record(&self, req: PorkbunCreateRecordReq<'_>) -> Result<String> {
+    let records = self.list_records(req.domain, req.name).await?;
+    if let Some(record) = records.into_iter().find(|r| r.content == req.content) {
+      return Ok(record.id);
+    }
+    self.create_record(req).await
+  }
+
+  /// Whether `domain` is in the account with api access enabled
+  pub async fn has_domain(&self, domain: &str) -> Result<bool> {
+    let url = format!("{}/dns/retrieve/{}", self.endpoint, domain);
+    let res: PorkbunRes<serde_json::Value> = self.send_request(&url, ()).await?;
+    Ok(matches!(res, PorkbunRes::Success(_)))
+  }
+}
+
+impl DnsChallengeClient for PorkbunClient {
+  fn has_zone<'a>(&'a self, zone: &'a str) -> BoxFuture<'a, Result<bool>> {
+    Box::pin(async move { self.has_domain(zone).await })
+  }
+
+  fn create_txt<'a>(
+    &'a self,
+    zone: &'a str,
+    fqdn: &'a str,
+    value: &'a str,
+  ) -> BoxFuture<'a, Result<TxtRecord>> {
+    Box::pin(async move {
+      let name = match relative_name(fqdn, zone) {
+        "@" => "",
+        name => name,
+      };
+      let req = PorkbunCreateRecordReq::new(zone, name, value).ttl(600);
+      let record_id = self.append_record(req).await?;
+      Ok(TxtRecord::new(zone, fqdn, value, record_id))
+    })
+  }
+
+  fn delete_txt<'a>(&'a self, record: &'a TxtRecord) -> BoxFuture<'a, Result<()>> {
+    Box::pin(async move { self.delete_record(&record.zone, &record.id).await })
+  }
+}
+
+impl PorkbunClient {
+  async fn exec_request<R>(&self, url: &str, data: impl Serialize) -> Result<R>
+  where
+    R: DeserializeOwned,
+  {
+    self.send_request(url, data).await?.unwrap_data()
+  }
+
+  async fn send_request<R>(&self, url: &str, data: impl Serialize) -> Result<PorkbunRes<R>>
+  where
+    R: DeserializeOwned,
+  {
+    let req = PorkbunReq {
+      apikey: &self.api_key,
+      secretapikey: &self.secret_api_key,
+      data,
+    };
+    let text = self
+      .client
+      .post(url)
+      .json(&req)
+      .send()
+      .await
+      .context(ReqwestClientSnafu)?
+      .text()
+      .await
+      .context(ReqwestClientSnafu)?;
+    match serde_json::from_str(&text) {
+      Ok(res) => Ok(res),
+      Err(_) => PlainTextSnafu {
+        message: format!("Porkbun Error: unexpected response: {}", text),
+      }
+      .fail(),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::sync::{Arc, Mutex};
+
+  use super::*;
+  use crate::test_util::{StubResponse, request_body, stub_server};
+
+  /// Stub Porkbun api, `example.com` has TXT record `1` with value `old` at `_acme-challenge`,
+  /// request paths and bodies are recorded
+  async fn stub_porkbun(requests: Arc<Mutex<Vec<String>>>) -> String {
+    stub_server(move |req| {
+      let body = request_body(req);
+      assert!(body.contains(r#""apikey":"pk1_key","secretapikey":"sk1_secret""#));
+      let path = req.lines().next().unwrap();
+      let path = path
+        .strip_prefix("POST ")
+        .and_then(|p| p.strip_suffix(" HTTP/1.1"))
+        .unwrap();
+      requests.lock().unwrap().push(format!("{} {}", path, body));
+      let res = match path {
+        "/dns/retrieve/example.com" => r#"{"status":"SUCCESS","records":[]}"#,
+        "/dns/retrieveByNameType/example.com/TXT/_acme-challenge" => {
+          r#"{"status":"SUCCESS","records":[{"id":"1","name":"_acme-challenge.example.com","type":"TXT","content":"old","ttl":"600"}]}"#
+        }
+        "/dns/create/example.com" => r#"{"status":"SUCCESS","id":2}"#,
+        "/dns/delete/example.com/2" => r#"{"status":"SUCCESS"}"#,
+        _ => r#"{"status":"ERROR","message":"Invalid domain."}"#,
+      };
+      StubResponse::ok(res)
+    })
+    .await
+  }
+
+  #[tokio::test]
+  async fn create_and_delete_txt() {
+    let requests = Arc::new(Mutex::new(vec![]));
+    let endpoint = stub_porkbun(requests.clone()).await;
+    let option = PorkbunOption::new("pk1_key", "sk1_secret").endpoint(endpoint);
+    let client = PorkbunClient::new(option).unwrap();
+    assert!(client.has_zone("example.com").await.unwrap());
+    assert!(!client.has_zone("example.org").await.unwrap());
+
+    let existed = client
+      .create_txt("example.com", "_acme-challenge.example.com", "old")
+      .await
+      .unwrap();
+    assert_eq!(existed.id, "1");
+    let record = client
+      .create_txt("example.com", "_acme-challenge.example.com", "new")
+      .await
+      .unwrap();
+    assert_eq!(record.id, "2");
+    client.delete_txt(&record).await.unwrap();
+
+    let requests = requests.lock().unwrap();
+    assert_eq!(requests.len(), 6);
+    assert!(requests[4].starts_with("/dns/create/example.com "));
+    assert!(requests[4].contains(r#""name":"_acme-challenge""#));
+    assert!(requests[4].contains(r#""content":"new""#));
+    assert!(requests[4].contains(r#""ttl":"600""#));
+    assert!(requests[5].starts_with("/dns/delete/example.com/2 "));
+  }
+}
@@ -0,0 +1,15 @@
+//! Implement of [Porkbun](https://porkbun.com/) DNS challenge
+//!
+//! Link: <https://porkbun.com/api/json/v3/documentation>
+
+mod client;
+pub use client::PorkbunClient;
+
+mod response;
+pub use response::PorkbunRecord;
+
+mod request;
+pub use request::PorkbunCreateRecordReq;
+
+mod option;
+pub use option::PorkbunOption;
@@ -0,0 +1,54 @@
+use std::time::Duration;
+
+use crate::{errors::Result, util::env_single_var};
+
+/// Options for create a [`crate::challenge::dns::porkbun::PorkbunClient`] instance
+#[derive(Debug)]
+pub struct PorkbunOption {
+  pub(crate) api_key: String,
+  pub(crate) secret_api_key: String,
+  pub(crate) proxy: Option<String>,
+  pub(crate) timeout: Option<Duration>,
+  pub(crate) endpoint: Option<String>,
+}
+
+impl PorkbunOption {
+  /// Create option with api key and secret api key, api access must be enabled for the domain
+  pub fn new(api_key: impl Into<String>, secret_api_key: impl Into<String>) -> Self {
+    PorkbunOption {
+      api_key: api_key.into(),
+      secret_api_key: secret_api_key.into(),
+      proxy: None,
+      timeout: None,
+      endpoint: None,
+    }
+  }
+
+  /// Create option from environment variable, variable names are __PORKBUN_API_KEY__ and
+  /// __PORKBUN_SECRET_API_KEY__, or __EASY_ACME_PORKBUN_API_KEY__ and
+  /// __EASY_ACME_PORKBUN_SECRET_API_KEY__
+  pub fn new_from_env() -> Result<Self> {
+    Ok(Self::new(
+      env_single_var(["PORKBUN_API_KEY", "EASY_ACME_PORKBUN_API_KEY"])?,
+      env_single_var(["PORKBUN_SECRET_API_KEY", "EASY_ACME_PORKBUN_SECRET_API_KEY"])?,
+    ))
+  }
+
+  /// Set proxy, for example, `https://127.0.0.1:8080`, `socks5://127.0.0.1:9000`, default is `None`
+  pub fn proxy(mut self, proxy: impl Into<String>) -> Self {
+    self.proxy = Some(proxy.into());
+    self
+  }
+
+  /// Set timeout, for example, `Duration::from_secs(5)`, default is `None`
+  pub fn timeout(mut self, timeout: Duration) -> Self {
+    self.timeout = Some(timeout);
+    self
+  }
+
+  /// Set api endpoint, default is `https://api.porkbun.com/api/json/v3`
+  pub fn endpoint(mut self, endpoint: impl Into<String>) -> Self {
+    self.endpoint = Some(endpoint.into());
+    self
+  }
+}
@@ -0,0 +1,49 @@
+use serde::Serialize;
+
+use crate::challenge::dns::RecordType;
+
+/// Key pair is sent in body of every request
+#[derive(Debug, Serialize)]
+pub(crate) struct PorkbunReq<'a, T> {
+  pub(crate) apikey: &'a str,
+
+  pub(crate) secretapikey: &'a str,
+
+  #[serde(flatten)]
+  pub(crate) data: T,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PorkbunCreateRecordReq<'a> {
+  #[serde(skip_serializing)]
+  pub(crate) domain: &'a str,
+
+  /// Relative to domain, empty for the domain itself
+  pub(crate) name: &'a str,
+
+  #[serde(rename = "type")]
+  pub(crate) rtype: RecordType,
+
+  pub(crate) content: &'a str,
+
+  /// Porkbun requires ttl as string, minimum is 600
+  #[serde(skip_serializing_if = "Option::is_none")]
+  ttl: Option<String>,
+}
+
+impl<'a> PorkbunCreateRecordReq<'a> {
+  pub fn new(domain: &'a str, name: &'a str, value: &'a str) -> Self {
+    Self {
+      domain,
+      name,
+      rtype: RecordType::TXT,
+      content: value,
+      ttl: None,
+    }
+  }
+
+  pub fn ttl(mut self, ttl: u64) -> Self {
+    self.ttl = Some(ttl.to_string());
+    self
+  }
+}
@@ -0,0 +1,49 @@
+use serde::Deserialize;
+
+use crate::errors::{PlainTextSnafu, Result};
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "status")]
+pub(crate) enum PorkbunRes<T> {
+  #[serde(rename = "ERROR")]
+  Failure { message: String },
+  #[serde(rename = "SUCCESS")]
+  Success(T),
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct CreateData {
+  pub(crate) id: serde_json::Value,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct RecordListData {
+  #[serde(default)]
+  pub(crate) records: Vec<PorkbunRecord>,
+}
+
+/// DNS record returned by [`crate::challenge::dns::porkbun::PorkbunClient::list_records`]
+#[derive(Debug, Clone, Deserialize)]
+pub struct PorkbunRecord {
+  pub id: String,
+
+  /// Full name, for example, `_acme-challenge.example.com`
+  pub name: String,
+
+  #[serde(rename = "type")]
+  pub rtype: String,
+
+  pub content: String,
+}
+
+impl<T> PorkbunRes<T> {
+  pub fn unwrap_data(self) -> Result<T> {
+    match self {
+      PorkbunRes::Success(data) => Ok(data),
+      PorkbunRes::Failure { message } => PlainTextSnafu {
+        message: format!("Porkbun Error: {}", message),
+      }
+      .fail(),
+    }
+  }
+}
@@ -0,0 +1,268 @@
+use futures_util::future::BoxFuture;
+use http::{HeaderMap, HeaderName, Method, StatusCode};
+use reqwest::{Client, ClientBuilder};
+use serde::{Serialize, de::DeserializeOwned};
+use snafu::ResultExt;
+
+use crate::{
+  challenge::dns::{
+    DnsChallengeClient, TxtRecord,
+    powerdns::{
+      PowerDnsOption, PowerDnsRecordSet, PowerDnsRecordSetReq, PowerDnsZone,
+      request::{PowerDnsPatchReq, canonical_name},
+      response::FailureData,
+    },
+    resolver::name_eq,
+  },
+  errors::{PlainTextSnafu, ReqwestClientSnafu, Result},
+  util::str_to_header_value,
+};
+
+/// Client for PowerDNS authoritative server http api
+#[derive(Debug)]
+pub struct PowerDnsClient {
+  base_url: String,
+  server_id: String,
+  ttl: u32,
+  client: Client,
+}
+
+impl PowerDnsClient {
+  /// Create client with option, see [`PowerDnsOption`]
+  pub fn new(option: PowerDnsOption) -> Result<Self> {
+    let PowerDnsOption {
+      base_url,
+      api_key,
+      server_id,
+      ttl,
+      proxy,
+      timeout,
+    } = option;
+    let mut client = ClientBuilder::new();
+    if let Some(timeout) = timeout {
+      client = client.timeout(timeout);
+    }
+    if let Some(proxy) = proxy {
+      let proxy = reqwest::Proxy::all(proxy).context(ReqwestClientSnafu)?;
+      client = client.proxy(proxy);
+    }
+    let mut headers = HeaderMap::new();
+    headers.insert(
+      HeaderName::from_static("x-api-key"),
+      str_to_header_value(api_key)?,
+    );
+    client = client.default_headers(headers);
+    let client = client.build().context(ReqwestClientSnafu)?;
+    Ok(Self {
+      base_url: base_url.trim_end_matches('/').to_string(),
+      server_id,
+      ttl,
+      client,
+    })
+  }
+
+  /// Create client from environment variable, see [`PowerDnsOption::new_from_env`]
+  pub fn new_from_env() -> Result<Self> {
+    Self::new(PowerDnsOption::new_from_env()?)
+  }
+
+  /// Zone with its RRsets, `None` if the server does not host `zone`
+  pub async fn zone(&self, zone: &str) -> Result<Option<PowerDnsZone>> {
+    let url = self.zone_url(zone);
+    let res = self
+      .client
+      .get(url)
+      .send()
+      .await
+      .context(ReqwestClientSnafu)?;
+    // unknown zone responds with 404, or 422 for some versions
+    match res.status() {
+      StatusCode::NOT_FOUND | StatusCode::UNPROCESSABLE_ENTITY => Ok(None),
+      _ => Self::parse_response(res).await.map(Some),
+    }
+  }
+
+  /// Change RRsets of zone, each RRset is replaced or deleted as a whole
+  pub async fn patch_rrsets(&self, zone: &str, rrsets: &[PowerDnsRecordSetReq]) -> Result<()> {
+    let req = PowerDnsPatchReq { rrsets };
+    let _: serde_json::Value = self
+      .exec_request(Method::PATCH, &self.zone_url(zone), &req)
+      .await?;
+    Ok(())
+  }
+
+  /// TXT RRset of `fqdn` in zone
+  pub async fn txt_record_set(&self, zone: &str, fqdn: &str) -> Result<Option<PowerDnsRecordSet>> {
+    let Some(data) = self.zone(zone).await? else {
+      return PlainTextSnafu {
+        message: format!("PowerDNS Error: zone {} not found", zone),
+      }
+      .fail();
+    };
+    let rrset = data
+      .rrsets
+      .into_iter()
+      .find(|r| r.rtype == "TXT" && name_eq(&r.name, fqdn));
+    Ok(rrset)
+  }
+
+  /// Add TXT record with `value` at `fqdn`, other values of `fqdn` are kept
+  pub async fn add_txt(&self, zone: &str, fqdn: &str, value: &str) -> Result<()> {
+    let mut values = match self.txt_record_set(zone, fqdn).await? {
+      Some(rrset) => rrset.txt_values(),
+      None => vec![],
+    };
+    if values.iter().any(|v| v == value) {
+      return Ok(());
+    }
+    values.push(value.to_string());
+    let req = PowerDnsRecordSetReq::replace_txt(fqdn, self.ttl, &values);
+    self.patch_rrsets(zone, &[req]).await
+  }
+
+  /// Remove TXT record with `value` at `fqdn`, other values of `fqdn` are kept
+  pub async fn remove_txt(&self, zone: &str, fqdn: &str, value: &str) -> Result<()> {
+    let Some(rrset) = self.txt_record_set(zone, fqdn).await? else {
+      return Ok(());
+    };
+    let ttl = rrset.ttl.unwrap_or(self.ttl);
+    let mut values = rrset.txt_values();
+    let len = values.len();
+    values.retain(|v| v != value);
+    let req = if values.is_empty() {
+      PowerDnsRecordSetReq::delete_txt(fqdn)
+    } else if values.len() < len {
+      PowerDnsRecordSetReq::replace_txt(fqdn, ttl, &values)
+    } else {
+      return Ok(());
+    };
+    self.patch_rrsets(zone, &[req]).await
+  }
+}
+
+impl DnsChallengeClient for PowerDnsClient {
+  fn has_zone<'a>(&'a self, zone: &'a str) -> BoxFuture<'a, Result<bool>> {
+    Box::pin(async move { Ok(self.zone(zone).await?.is_some()) })
+  }
+
+  fn create_txt<'a>(
+    &'a self,
+    zone: &'a str,
+    fqdn: &'a str,
+    value: &'a str,
+  ) -> BoxFuture<'a, Result<TxtRecord>> {
+    Box::pin(async move {
+      self.add_txt(zone, fqdn, value).await?;
+      Ok(TxtRecord::new(zone, fqdn, value, ""))
+    })
+  }
+
+  fn delete_txt<'a>(&'a self, record: &'a TxtRecord) -> BoxFuture<'a, Result<()>> {
+    Box::pin(async move {
+      self
+        .remove_txt(&record.zone, &record.fqdn, &record.value)
+        .await
+    })
+  }
+}
+
+impl PowerDnsClient {
+  fn zone_url(&self, zone: &str) -> String {
+    format!(
+      "{}/api/v1/servers/{}/zones/{}",
+      self.base_url,
+      self.server_id,
+      canonical_name(zone)
+    )
+  }
+
+  async fn exec_request<R>(&self, method: Method, url: &str, req: &impl Serialize) -> Result<R>
+  where
+    R: DeserializeOwned,
+  {
+    let res = self
+      .client
+      .request(method, url)
+      .json(req)
+      .send()
+      .await
+      .context(ReqwestClientSnafu)?;
+    Self::parse_response(res).await
+  }
+
+  async fn parse_response<R>(res: reqwest::Response) -> Result<R>
+  where
+    R: DeserializeOwned,
+  {
+    let status = res.status();
+    let text = res.text().await.context(ReqwestClientSnafu)?;
+    if status.is_success() {
+      // patch api responds with 204 and empty body
+      let text = if text.is_empty() { "null" } else { &text };
+      if let Ok(data) = serde_json::from_str(text) {
+        return Ok(data);
+      }
+    }
+    let message = match serde_json::from_str::<FailureData>(&text) {
+      Ok(failure) => failure.error,
+      Err(_) => text,
+    };
+    PlainTextSnafu {
+      message: format!("PowerDNS Error: {}, {}", status, message),
+    }
+    .fail()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::sync::{Arc, Mutex};
+
+  use super::*;
+  use crate::test_util::{StubResponse, request_body, stub_server};
+
+  /// Stub PowerDNS server, zone `example.com.` has TXT `"old"` at `_acme-challenge`, the body of
+  /// last PATCH request is recorded
+  async fn stub_powerdns(patched: Arc<Mutex<String>>) -> String {
+    stub_server(move |req| {
+      if !req.contains("x-api-key: secret") {
+        StubResponse::new("401 Unauthorized", r#"{"error":"Unauthorized"}"#)
+      } else if req.starts_with("GET /api/v1/servers/localhost/zones/example.com. ") {
+        StubResponse::ok(
+          r#"{"id":"example.com.","name":"example.com.","rrsets":[{"name":"_acme-challenge.example.com.","type":"TXT","ttl":120,"records":[{"content":"\"old\"","disabled":false}]}]}"#,
+        )
+      } else if req.starts_with("PATCH /api/v1/servers/localhost/zones/example.com. ") {
+        *patched.lock().unwrap() = request_body(req).to_string();
+        StubResponse::new("204 No Content", "")
+      } else {
+        StubResponse::new("404 Not Found", r#"{"error":"Not Found"}"#)
+      }
+    })
+    .await
+  }
+
+  #[tokio::test]
+  async fn patch_txt_rrset() {
+    let patched = Arc::new(Mutex::new(String::new()));
+    let base_url = stub_powerdns(patched.clone()).await;
+    let client = PowerDnsClient::new(PowerDnsOption::new(&base_url, "secret")).unwrap();
+    assert!(client.has_zone("example.com").await.unwrap());
+    assert!(!client.has_zone("example.org").await.unwrap());
+
+    let record = client
+      .create_txt("example.com", "_acme-challenge.example.com", "new")
+      .await
+      .unwrap();
+    let body: serde_json::Value = serde_json::from_str(&patched.lock().unwrap()).unwrap();
+    let rrset = &body["rrsets"][0];
+    assert_eq!(rrset["name"], "_acme-challenge.example.com.");
+    assert_eq!(rrset["changetype"], "REPLACE");
+    assert_eq!(rrset["records"][0]["content"], "\"old\"");
+    assert_eq!(rrset["records"][1]["content"], "\"new\"");
+
+    let record = TxtRecord::new(&record.zone, &record.fqdn, "old", "");
+    client.delete_txt(&record).await.unwrap();
+    let body: serde_json::Value = serde_json::from_str(&patched.lock().unwrap()).unwrap();
+    assert_eq!(body["rrsets"][0]["changetype"], "DELETE");
+  }
+}
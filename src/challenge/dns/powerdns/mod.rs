@@ -0,0 +1,15 @@
+//! Implement of [PowerDNS](https://www.powerdns.com/) authoritative server DNS challenge
+//!
+//! Link: <https://doc.powerdns.com/authoritative/http-api/zone.html>
+
+mod client;
+pub use client::PowerDnsClient;
+
+mod response;
+pub use response::{PowerDnsRecord, PowerDnsRecordSet, PowerDnsZone};
+
+mod request;
+pub use request::{PowerDnsChangeType, PowerDnsRecordSetReq};
+
+mod option;
+pub use option::PowerDnsOption;
@@ -0,0 +1,67 @@
+use std::time::Duration;
+
+use crate::{errors::Result, util::env_single_var};
+
+/// Options for create a [`crate::challenge::dns::powerdns::PowerDnsClient`] instance
+#[derive(Debug)]
+pub struct PowerDnsOption {
+  pub(crate) base_url: String,
+  pub(crate) api_key: String,
+  pub(crate) server_id: String,
+  pub(crate) ttl: u32,
+  pub(crate) proxy: Option<String>,
+  pub(crate) timeout: Option<Duration>,
+}
+
+impl PowerDnsOption {
+  /// Create option with base url of the webserver, for example, `http://127.0.0.1:8081`, and the
+  /// `api-key` in PowerDNS configuration
+  pub fn new(base_url: impl Into<String>, api_key: impl Into<String>) -> Self {
+    PowerDnsOption {
+      base_url: base_url.into(),
+      api_key: api_key.into(),
+      server_id: "localhost".to_string(),
+      ttl: 60,
+      proxy: None,
+      timeout: None,
+    }
+  }
+
+  /// Create option from environment variable, variable names are __PDNS_API_URL__,
+  /// __PDNS_API_KEY__ and optional __PDNS_SERVER_NAME__, or __EASY_ACME_POWERDNS_API_URL__,
+  /// __EASY_ACME_POWERDNS_API_KEY__ and __EASY_ACME_POWERDNS_SERVER_ID__
+  pub fn new_from_env() -> Result<Self> {
+    let option = Self::new(
+      env_single_var(["PDNS_API_URL", "EASY_ACME_POWERDNS_API_URL"])?,
+      env_single_var(["PDNS_API_KEY", "EASY_ACME_POWERDNS_API_KEY"])?,
+    );
+    match env_single_var(["PDNS_SERVER_NAME", "EASY_ACME_POWERDNS_SERVER_ID"]) {
+      Ok(server_id) => Ok(option.server_id(server_id)),
+      Err(_) => Ok(option),
+    }
+  }
+
+  /// Set server id, default is `localhost`
+  pub fn server_id(mut self, server_id: impl Into<String>) -> Self {
+    self.server_id = server_id.into();
+    self
+  }
+
+  /// Set ttl of TXT record, default is 60 seconds
+  pub fn ttl(mut self, ttl: u32) -> Self {
+    self.ttl = ttl;
+    self
+  }
+
+  /// Set proxy, for example, `https://127.0.0.1:8080`, `socks5://127.0.0.1:9000`, default is `None`
+  pub fn proxy(mut self, proxy: impl Into<String>) -> Self {
+    self.proxy = Some(proxy.into());
+    self
+  }
+
+  /// Set timeout, for example, `Duration::from_secs(5)`, default is `None`
+  pub fn timeout(mut self, timeout: Duration) -> Self {
+    self.timeout = Some(timeout);
+    self
+  }
+}
@@ -0,0 +1,93 @@
+use serde::Serialize;
+
+use crate::challenge::dns::{RecordType, powerdns::PowerDnsRecord};
+
+#[derive(Debug, Serialize)]
+pub(crate) struct PowerDnsPatchReq<'a> {
+  pub(crate) rrsets: &'a [PowerDnsRecordSetReq],
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum PowerDnsChangeType {
+  /// Replace all records of the RRset
+  Replace,
+  /// Delete all records of the RRset
+  Delete,
+}
+
+/// Change of RRset, name must be canonical, with trailing dot
+#[derive(Debug, Serialize)]
+pub struct PowerDnsRecordSetReq {
+  pub(crate) name: String,
+
+  #[serde(rename = "type")]
+  pub(crate) rtype: RecordType,
+
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub(crate) ttl: Option<u32>,
+
+  pub(crate) changetype: PowerDnsChangeType,
+
+  pub(crate) records: Vec<PowerDnsRecord>,
+}
+
+impl PowerDnsRecordSetReq {
+  /// Replace TXT records of `fqdn` with `values`, values are quoted as TXT content
+  pub fn replace_txt(fqdn: &str, ttl: u32, values: &[String]) -> Self {
+    let records = values
+      .iter()
+      .map(|v| PowerDnsRecord {
+        content: quote_txt(v),
+        disabled: false,
+      })
+      .collect();
+    Self {
+      name: canonical_name(fqdn),
+      rtype: RecordType::TXT,
+      ttl: Some(ttl),
+      changetype: PowerDnsChangeType::Replace,
+      records,
+    }
+  }
+
+  /// Delete all TXT records of `fqdn`
+  pub fn delete_txt(fqdn: &str) -> Self {
+    Self {
+      name: canonical_name(fqdn),
+      rtype: RecordType::TXT,
+      ttl: None,
+      changetype: PowerDnsChangeType::Delete,
+      records: vec![],
+    }
+  }
+}
+
+/// Name with trailing dot, as PowerDNS requires, for example, `example.com.`
+pub(crate) fn canonical_name(name: &str) -> String {
+  format!("{}.", name.trim_end_matches('.'))
+}
+
+/// TXT content must be quoted, `"` and `\` in value are escaped
+pub(crate) fn quote_txt(value: &str) -> String {
+  format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Value of quoted TXT content, long content may be split to multiple strings
+pub(crate) fn unquote_txt(content: &str) -> String {
+  let mut value = String::new();
+  let mut quoted = false;
+  let mut chars = content.chars();
+  while let Some(c) = chars.next() {
+    match c {
+      '"' => quoted = !quoted,
+      '\\' if quoted => value.extend(chars.next()),
+      c if quoted => value.push(c),
+      _ => {}
+    }
+  }
+  if value.is_empty() && !content.contains('"') {
+    return content.to_string();
+  }
+  value
+}
@@ -0,0 +1,54 @@
+use serde::{Deserialize, Serialize};
+
+use crate::challenge::dns::powerdns::request::unquote_txt;
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct FailureData {
+  pub(crate) error: String,
+}
+
+/// Zone returned by [`crate::challenge::dns::powerdns::PowerDnsClient::zone`]
+#[derive(Debug, Clone, Deserialize)]
+pub struct PowerDnsZone {
+  pub id: String,
+
+  /// Canonical name, for example, `example.com.`
+  pub name: String,
+
+  #[serde(default)]
+  pub rrsets: Vec<PowerDnsRecordSet>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PowerDnsRecordSet {
+  /// Canonical name, for example, `_acme-challenge.example.com.`
+  pub name: String,
+
+  #[serde(rename = "type")]
+  pub rtype: String,
+
+  pub ttl: Option<u32>,
+
+  #[serde(default)]
+  pub records: Vec<PowerDnsRecord>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PowerDnsRecord {
+  /// Content in zone file format, TXT content is quoted
+  pub content: String,
+
+  #[serde(default)]
+  pub disabled: bool,
+}
+
+impl PowerDnsRecordSet {
+  /// Unquoted values of TXT records
+  pub fn txt_values(&self) -> Vec<String> {
+    self
+      .records
+      .iter()
+      .map(|r| unquote_txt(&r.content))
+      .collect()
+  }
+}
@@ -0,0 +1,277 @@
+//! Wait until TXT record of DNS challenge is visible on all authoritative nameservers, so the
+//! CA will not validate against a stale nameserver
+
+use std::{net::SocketAddr, time::Duration};
+
+use tokio::time::Instant;
+
+use crate::{
+  challenge::dns::{
+    RecordType,
+    resolver::{DnsResolver, name_eq},
+  },
+  errors::{PlainTextSnafu, Result},
+};
+
+/// Options for create a [`PropagationChecker`] instance
+#[derive(Debug)]
+pub struct PropagationOption {
+  pub(crate) timeout: Duration,
+  pub(crate) interval: Duration,
+  pub(crate) query_timeout: Option<Duration>,
+  pub(crate) resolver: Option<SocketAddr>,
+  pub(crate) nameservers: Option<Vec<SocketAddr>>,
+  pub(crate) nameserver_port: u16,
+}
+
+impl PropagationOption {
+  /// Create option with default values, timeout is 120 seconds and interval is 5 seconds
+  pub fn new() -> Self {
+    PropagationOption {
+      timeout: Duration::from_secs(120),
+      interval: Duration::from_secs(5),
+      query_timeout: None,
+      resolver: None,
+      nameservers: None,
+      nameserver_port: 53,
+    }
+  }
+
+  /// Set max time to wait for propagation, default is 120 seconds
+  pub fn timeout(mut self, timeout: Duration) -> Self {
+    self.timeout = timeout;
+    self
+  }
+
+  /// Set interval between two rounds of checking, default is 5 seconds
+  pub fn interval(mut self, interval: Duration) -> Self {
+    self.interval = interval;
+    self
+  }
+
+  /// Set timeout of each DNS query, default is 5 seconds
+  pub fn query_timeout(mut self, timeout: Duration) -> Self {
+    self.query_timeout = Some(timeout);
+    self
+  }
+
+  /// Set recursive nameserver used to discover zone and authoritative nameservers, default is the
+  /// first nameserver in __/etc/resolv.conf__
+  pub fn resolver(mut self, resolver: SocketAddr) -> Self {
+    self.resolver = Some(resolver);
+    self
+  }
+
+  /// Check these nameservers instead of discovering authoritative nameservers of the zone
+  pub fn nameservers(mut self, nameservers: Vec<SocketAddr>) -> Self {
+    self.nameservers = Some(nameservers);
+    self
+  }
+
+  /// Set port of discovered authoritative nameservers, default is 53
+  pub fn nameserver_port(mut self, port: u16) -> Self {
+    self.nameserver_port = port;
+    self
+  }
+}
+
+impl Default for PropagationOption {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+/// Polls authoritative nameservers directly for the expected TXT value
+#[derive(Debug)]
+pub struct PropagationChecker {
+  resolver: DnsResolver,
+  timeout: Duration,
+  interval: Duration,
+  nameservers: Option<Vec<SocketAddr>>,
+  nameserver_port: u16,
+}
+
+impl PropagationChecker {
+  /// Create checker with default option, see [`PropagationOption::new`]
+  pub fn new() -> Self {
+    Self::new_with_option(PropagationOption::new())
+  }
+
+  /// Create checker with option, see [`PropagationOption`]
+  pub fn new_with_option(option: PropagationOption) -> Self {
+    let PropagationOption {
+      timeout,
+      interval,
+      query_timeout,
+      resolver,
+      nameservers,
+      nameserver_port,
+    } = option;
+    let mut resolver = match resolver {
+      Some(server) => DnsResolver::new(server),
+      None => DnsResolver::new_from_system(),
+    };
+    if let Some(query_timeout) = query_timeout {
+      resolver = resolver.timeout(query_timeout);
+    }
+    PropagationChecker {
+      resolver,
+      timeout,
+      interval,
+      nameservers,
+      nameserver_port,
+    }
+  }
+
+  /// Resolver used by this checker
+  pub fn resolver(&self) -> &DnsResolver {
+    &self.resolver
+  }
+
+  /// Find the authoritative nameservers of zone which `fqdn` belongs to
+  pub async fn authoritative_nameservers(&self, fqdn: &str) -> Result<Vec<SocketAddr>> {
+    if let Some(nameservers) = &self.nameservers {
+      return Ok(nameservers.clone());
+    }
+    let zone = self.resolver.find_zone(fqdn).await?;
+    self.resolver.nameservers(&zone, self.nameserver_port).await
+  }
+
+  /// Wait until `value` is present in TXT records of `fqdn` on all authoritative nameservers
+  pub async fn wait_txt(&self, fqdn: &str, value: &str) -> Result<()> {
+    let deadline = Instant::now() + self.timeout;
+    let nameservers = self.authoritative_nameservers(fqdn).await?;
+    loop {
+      let pending = self.pending_nameservers(&nameservers, fqdn, value).await;
+      if pending.is_empty() {
+        return Ok(());
+      }
+      if Instant::now() + self.interval > deadline {
+        return PlainTextSnafu {
+          message: format!(
+            "DNS Error: TXT record of {} is not propagated to {} in {:?}",
+            fqdn,
+            pending.join(", "),
+            self.timeout
+          ),
+        }
+        .fail();
+      }
+      tokio::time::sleep(self.interval).await;
+    }
+  }
+
+  /// Check once whether `value` is present in TXT records of `fqdn` on all authoritative nameservers
+  pub async fn check_txt(&self, fqdn: &str, value: &str) -> Result<bool> {
+    let nameservers = self.authoritative_nameservers(fqdn).await?;
+    let pending = self.pending_nameservers(&nameservers, fqdn, value).await;
+    Ok(pending.is_empty())
+  }
+
+  /// Nameservers which do not have the value yet, with the reason
+  async fn pending_nameservers(
+    &self,
+    nameservers: &[SocketAddr],
+    fqdn: &str,
+    value: &str,
+  ) -> Vec<String> {
+    let mut pending = vec![];
+    for nameserver in nameservers {
+      let res = self
+        .resolver
+        .query(*nameserver, fqdn, RecordType::TXT, false)
+        .await;
+      match res {
+        Ok(res) => {
+          let found = res
+            .answers
+            .iter()
+            .filter(|r| name_eq(&r.name, fqdn))
+            .filter_map(|r| r.txt_value())
+            .any(|v| v == value);
+          if !found {
+            pending.push(nameserver.to_string());
+          }
+        }
+        Err(err) => pending.push(format!("{} ({})", nameserver, err)),
+      }
+    }
+    pending
+  }
+}
+
+impl Default for PropagationChecker {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::net::Ipv4Addr;
+
+  use super::*;
+  use crate::{
+    challenge::dns::resolver::message::RecordData,
+    test_util::{dns_record, dns_stub_server},
+  };
+
+  /// Stub server acts as both recursive and authoritative nameserver of `example.com`
+  async fn stub_server() -> SocketAddr {
+    dns_stub_server(|mut res| {
+      let question = res.questions[0].clone();
+      let soa = dns_record("example.com", RecordType::SOA, RecordData::Raw(vec![]));
+      match RecordType::from_code(question.rtype) {
+        Some(RecordType::SOA) if question.name == "example.com" => res.answers.push(soa),
+        Some(RecordType::SOA) => res.authorities.push(soa),
+        Some(RecordType::NS) => {
+          let ns = RecordData::NS("ns1.example.com".to_string());
+          res
+            .answers
+            .push(dns_record("example.com", RecordType::NS, ns));
+          let glue = RecordData::A(Ipv4Addr::LOCALHOST);
+          res
+            .additionals
+            .push(dns_record("ns1.example.com", RecordType::A, glue));
+        }
+        Some(RecordType::TXT) => {
+          let txt = RecordData::TXT(vec!["token".to_string()]);
+          res
+            .answers
+            .push(dns_record(&question.name, RecordType::TXT, txt));
+        }
+        _ => {}
+      }
+      res
+    })
+    .await
+  }
+
+  fn checker(server: SocketAddr) -> PropagationChecker {
+    let option = PropagationOption::new()
+      .resolver(server)
+      .nameserver_port(server.port())
+      .timeout(Duration::from_millis(300))
+      .interval(Duration::from_millis(100));
+    PropagationChecker::new_with_option(option)
+  }
+
+  #[tokio::test]
+  async fn discover_and_wait() {
+    let server = stub_server().await;
+    let checker = checker(server);
+    let nameservers = checker
+      .authoritative_nameservers("_acme-challenge.example.com")
+      .await
+      .unwrap();
+    assert_eq!(nameservers, vec![server]);
+    checker
+      .wait_txt("_acme-challenge.example.com", "token")
+      .await
+      .unwrap();
+    let res = checker
+      .wait_txt("_acme-challenge.example.com", "other")
+      .await;
+    assert!(res.is_err());
+  }
+}
@@ -0,0 +1,511 @@
+use std::{collections::BTreeMap, time::Duration};
+
+use serde::{Deserialize, Deserializer};
+use snafu::ResultExt;
+
+use crate::{
+  challenge::dns::{
+    DnsChallengeClient,
+    acme_dns::{AcmeDnsClient, AcmeDnsOption},
+    aliyun::{AliyunClient, AliyunClientOption},
+    azure::{AzureClient, AzureOption},
+    cloudflare::{CloudflareClient, CloudflareOption},
+    digitalocean::{DigitalOceanClient, DigitalOceanOption},
+    dnspod::{DnspodClient, DnspodClientOption},
+    exec::{ExecClient, ExecMode, ExecOption},
+    gandi::{GandiClient, GandiOption},
+    google_cloud::{GoogleCloudClient, GoogleCloudOption, GoogleServiceAccount},
+    hetzner::{HetznerClient, HetznerOption},
+    huaweicloud::{HuaweiCloudClient, HuaweiCloudOption},
+    linode::{LinodeClient, LinodeOption},
+    ovh::{OvhClient, OvhOption},
+    porkbun::{PorkbunClient, PorkbunOption},
+    powerdns::{PowerDnsClient, PowerDnsOption},
+    rfc2136::{Rfc2136Client, Rfc2136Option, TsigAlgorithm, TsigKey, parse_nameserver},
+    route53::{Route53Client, Route53Option},
+    vultr::{VultrClient, VultrOption},
+  },
+  errors::{PlainTextSnafu, Result, SerializeJsonSnafu},
+};
+
+/// Function constructs provider from config, see [`DnsProviderRegistry::register`]
+pub type DnsProviderFactory = fn(&DnsProviderConfig) -> Result<Box<dyn DnsChallengeClient>>;
+
+/// String-keyed configuration of a DNS provider, key `provider` is the name of provider, and
+/// other keys are credentials and settings of it, see [`DnsProviderRegistry`]
+///
+/// It can be deserialized from map of JSON, TOML and so on, numbers and booleans are converted
+/// to strings, for example, `{"provider": "cloudflare", "api_token": "xxx", "timeout": 10}`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DnsProviderConfig {
+  values: BTreeMap<String, String>,
+}
+
+impl DnsProviderConfig {
+  /// Create config of provider `name`, for example, `cloudflare`
+  pub fn new(provider: impl Into<String>) -> Self {
+    Self::default().set("provider", provider)
+  }
+
+  /// Parse config from JSON object
+  pub fn from_json(json: &str) -> Result<Self> {
+    serde_json::from_str(json).context(SerializeJsonSnafu)
+  }
+
+  /// Collect environment variables starting with `prefix`, keys are the rest of names in lower
+  /// case, for example, __EASY_ACME_DNS_PROVIDER__ and __EASY_ACME_DNS_API_TOKEN__ are `provider`
+  /// and `api_token` with prefix `EASY_ACME_DNS_`, error if value of such variable is not unicode
+  pub fn from_env(prefix: &str) -> Result<Self> {
+    let mut values = BTreeMap::new();
+    for (name, value) in std::env::vars_os() {
+      let Some(key) = name.to_str().and_then(|name| name.strip_prefix(prefix)) else {
+        continue;
+      };
+      let Ok(value) = value.into_string() else {
+        return PlainTextSnafu {
+          message: format!("Registry Error: value of {}{} is not unicode", prefix, key),
+        }
+        .fail();
+      };
+      values.insert(key.to_lowercase(), value);
+    }
+    Ok(Self { values })
+  }
+
+  /// Set value of `key`
+  pub fn set(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+    self.values.insert(key.into(), value.into());
+    self
+  }
+
+  /// Name of provider
+  pub fn provider(&self) -> Option<&str> {
+    self.get("provider")
+  }
+
+  /// Value of `key`, empty value is treated as absent
+  pub fn get(&self, key: &str) -> Option<&str> {
+    self
+      .values
+      .get(key)
+      .map(String::as_str)
+      .filter(|v| !v.is_empty())
+  }
+
+  /// Value of `key`, or error if absent
+  pub fn require(&self, key: &str) -> Result<&str> {
+    match self.get(key) {
+      Some(value) => Ok(value),
+      None => PlainTextSnafu {
+        message: format!(
+          "Registry Error: missing `{}` for provider {}",
+          key,
+          self.provider().unwrap_or_default()
+        ),
+      }
+      .fail(),
+    }
+  }
+
+  /// Value of `key` parsed as number
+  pub fn number<T: std::str::FromStr>(&self, key: &str) -> Result<Option<T>> {
+    let Some(value) = self.get(key) else {
+      return Ok(None);
+    };
+    match value.parse() {
+      Ok(number) => Ok(Some(number)),
+      Err(_) => PlainTextSnafu {
+        message: format!("Registry Error: `{}` is not a number: {}", key, value),
+      }
+      .fail(),
+    }
+  }
+
+  /// Value of `key` parsed as seconds
+  pub fn seconds(&self, key: &str) -> Result<Option<Duration>> {
+    Ok(self.number(key)?.map(Duration::from_secs))
+  }
+
+  /// Value of `key` parsed as boolean, `true`, `false`, `1` or `0`
+  pub fn flag(&self, key: &str) -> Result<Option<bool>> {
+    match self.get(key) {
+      None => Ok(None),
+      Some("true" | "1") => Ok(Some(true)),
+      Some("false" | "0") => Ok(Some(false)),
+      Some(value) => PlainTextSnafu {
+        message: format!("Registry Error: `{}` is not a boolean: {}", key, value),
+      }
+      .fail(),
+    }
+  }
+}
+
+impl<'de> Deserialize<'de> for DnsProviderConfig {
+  fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+  where
+    D: Deserializer<'de>,
+  {
+    let map = BTreeMap::<String, serde_json::Value>::deserialize(deserializer)?;
+    let values = map
+      .into_iter()
+      .filter_map(|(key, value)| match value {
+        serde_json::Value::String(value) => Some((key, value)),
+        serde_json::Value::Null => None,
+        value => Some((key, value.to_string())),
+      })
+      .collect();
+    Ok(Self { values })
+  }
+}
+
+/// Registry constructs DNS providers by name, so providers can be switched by configuration
+///
+/// Built-in providers and their keys, optional ones are in brackets, `proxy` and `timeout` (in
+/// seconds) are supported by all http based providers:
+///
+/// | provider | keys |
+/// | --- | --- |
+/// | `acme_dns` | `base_url`, [`storage_path`] |
+/// | `aliyun` | `access_key`, `access_secret` |
+/// | `azure` | `tenant_id`, `client_id`, `client_secret`, `subscription_id`, `resource_group`, [`ttl`] |
+/// | `cloudflare` | `api_token`, or `api_key` and `email` |
+/// | `digitalocean` | `token` |
+/// | `dnspod` | `secret_id`, `secret_key` |
+/// | `exec` | `program`, [`args`] (JSON array of strings), [`mode`] (`args` or `json`) |
+/// | `gandi` | `token`, [`ttl`] |
+/// | `google_cloud` | `service_account_file` or `service_account_json`, [`project_id`], [`ttl`] |
+/// | `hetzner` | `token` |
+/// | `huaweicloud` | `access_key`, `secret_key`, [`region`], [`project_id`], [`ttl`] |
+/// | `linode` | `token` |
+/// | `ovh` | `application_key`, `application_secret`, `consumer_key`, [`endpoint`] |
+/// | `porkbun` | `api_key`, `secret_api_key` |
+/// | `powerdns` | `base_url`, `api_key`, [`server_id`], [`ttl`] |
+/// | `rfc2136` | `nameserver`, [`tsig_key`, `tsig_secret`, `tsig_algorithm`], [`ttl`], [`tcp`] |
+/// | `route53` | `access_key`, `secret_key`, [`session_token`], [`region`], [`hosted_zone_id`], [`ttl`] |
+/// | `vultr` | `api_key` |
+///
+/// Names are case-insensitive, and `-` is same as `_`, for example, `acme-dns`
+pub struct DnsProviderRegistry {
+  factories: BTreeMap<String, DnsProviderFactory>,
+}
+
+impl Default for DnsProviderRegistry {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl DnsProviderRegistry {
+  /// Create registry with built-in providers
+  pub fn new() -> Self {
+    let registry = DnsProviderRegistry {
+      factories: BTreeMap::new(),
+    };
+    registry
+      .register("acme_dns", acme_dns)
+      .register("aliyun", aliyun)
+      .register("azure", azure)
+      .register("cloudflare", cloudflare)
+      .register("digitalocean", digitalocean)
+      .register("dnspod", dnspod)
+      .register("exec", exec)
+      .register("gandi", gandi)
+      .register("google_cloud", google_cloud)
+      .register("hetzner", hetzner)
+      .register("huaweicloud", huaweicloud)
+      .register("linode", linode)
+      .register("ovh", ovh)
+      .register("porkbun", porkbun)
+      .register("powerdns", powerdns)
+      .register("rfc2136", rfc2136)
+      .register("route53", route53)
+      .register("vultr", vultr)
+  }
+
+  /// Register provider `name`, built-in provider with the same name is replaced
+  pub fn register(mut self, name: &str, factory: DnsProviderFactory) -> Self {
+    self.factories.insert(normalize_name(name), factory);
+    self
+  }
+
+  /// Names of registered providers
+  pub fn names(&self) -> impl Iterator<Item = &str> {
+    self.factories.keys().map(String::as_str)
+  }
+
+  /// Construct provider named by `provider` of config
+  pub fn build(&self, config: &DnsProviderConfig) -> Result<Box<dyn DnsChallengeClient>> {
+    let Some(name) = config.provider() else {
+      return PlainTextSnafu {
+        message: "Registry Error: missing `provider` in config",
+      }
+      .fail();
+    };
+    match self.factories.get(&normalize_name(name)) {
+      Some(factory) => factory(config),
+      None => PlainTextSnafu {
+        message: format!("Registry Error: unknown provider {}", name),
+      }
+      .fail(),
+    }
+  }
+}
+
+fn normalize_name(name: &str) -> String {
+  name.trim().to_lowercase().replace('-', "_")
+}
+
+/// Apply `proxy` and `timeout` of config to option
+macro_rules! http_option {
+  ($option:expr, $config:expr) => {{
+    let mut option = $option;
+    if let Some(proxy) = $config.get("proxy") {
+      option = option.proxy(proxy);
+    }
+    if let Some(timeout) = $config.seconds("timeout")? {
+      option = option.timeout(timeout);
+    }
+    option
+  }};
+}
+
+fn acme_dns(config: &DnsProviderConfig) -> Result<Box<dyn DnsChallengeClient>> {
+  let mut option = http_option!(AcmeDnsOption::new(config.require("base_url")?), config);
+  if let Some(path) = config.get("storage_path") {
+    option = option.storage_path(path);
+  }
+  Ok(Box::new(AcmeDnsClient::new(option)?))
+}
+
+fn aliyun(config: &DnsProviderConfig) -> Result<Box<dyn DnsChallengeClient>> {
+  let option = AliyunClientOption::new(
+    config.require("access_key")?,
+    config.require("access_secret")?,
+  );
+  let option = http_option!(option, config);
+  Ok(Box::new(AliyunClient::new_with_option(option)?))
+}
+
+fn azure(config: &DnsProviderConfig) -> Result<Box<dyn DnsChallengeClient>> {
+  let option = AzureOption::new(
+    config.require("tenant_id")?,
+    config.require("client_id")?,
+    config.require("client_secret")?,
+    config.require("subscription_id")?,
+    config.require("resource_group")?,
+  );
+  let mut option = http_option!(option, config);
+  if let Some(ttl) = config.number("ttl")? {
+    option = option.ttl(ttl);
+  }
+  Ok(Box::new(AzureClient::new(option)?))
+}
+
+fn cloudflare(config: &DnsProviderConfig) -> Result<Box<dyn DnsChallengeClient>> {
+  let option = match config.get("api_token") {
+    Some(token) => CloudflareOption::new_with_token(token),
+    None => CloudflareOption::new_with_email(config.require("api_key")?, config.require("email")?),
+  };
+  let option = http_option!(option, config);
+  Ok(Box::new(CloudflareClient::new(option)?))
+}
+
+fn digitalocean(config: &DnsProviderConfig) -> Result<Box<dyn DnsChallengeClient>> {
+  let option = http_option!(DigitalOceanOption::new(config.require("token")?), config);
+  Ok(Box::new(DigitalOceanClient::new(option)?))
+}
+
+fn dnspod(config: &DnsProviderConfig) -> Result<Box<dyn DnsChallengeClient>> {
+  let option = DnspodClientOption::new(config.require("secret_id")?, config.require("secret_key")?);
+  let option = http_option!(option, config);
+  Ok(Box::new(DnspodClient::new_with_option(option)?))
+}
+
+fn exec(config: &DnsProviderConfig) -> Result<Box<dyn DnsChallengeClient>> {
+  let mut option = ExecOption::new(config.require("program")?);
+  if let Some(args) = config.get("args") {
+    let Ok(args) = serde_json::from_str::<Vec<String>>(args) else {
+      return PlainTextSnafu {
+        message: format!(
+          "Registry Error: `args` is not a JSON array of strings: {}",
+          args
+        ),
+      }
+      .fail();
+    };
+    option = args.into_iter().fold(option, ExecOption::arg);
+  }
+  option = match config.get("mode") {
+    None | Some("args") => option,
+    Some("json") => option.mode(ExecMode::Json),
+    Some(mode) => {
+      return PlainTextSnafu {
+        message: format!("Registry Error: unsupported exec mode {}", mode),
+      }
+      .fail();
+    }
+  };
+  if let Some(timeout) = config.seconds("timeout")? {
+    option = option.timeout(timeout);
+  }
+  Ok(Box::new(ExecClient::new(option)))
+}
+
+fn gandi(config: &DnsProviderConfig) -> Result<Box<dyn DnsChallengeClient>> {
+  let mut option = http_option!(GandiOption::new(config.require("token")?), config);
+  if let Some(ttl) = config.number("ttl")? {
+    option = option.ttl(ttl);
+  }
+  Ok(Box::new(GandiClient::new(option)?))
+}
+
+fn google_cloud(config: &DnsProviderConfig) -> Result<Box<dyn DnsChallengeClient>> {
+  let option = match config.get("service_account_json") {
+    Some(json) => GoogleCloudOption::new(GoogleServiceAccount::from_json(json)?),
+    None => GoogleCloudOption::new_from_file(config.require("service_account_file")?)?,
+  };
+  let mut option = http_option!(option, config);
+  if let Some(project_id) = config.get("project_id") {
+    option = option.project_id(project_id);
+  }
+  if let Some(ttl) = config.number("ttl")? {
+    option = option.ttl(ttl);
+  }
+  Ok(Box::new(GoogleCloudClient::new(option)?))
+}
+
+fn hetzner(config: &DnsProviderConfig) -> Result<Box<dyn DnsChallengeClient>> {
+  let option = http_option!(HetznerOption::new(config.require("token")?), config);
+  Ok(Box::new(HetznerClient::new(option)?))
+}
+
+fn huaweicloud(config: &DnsProviderConfig) -> Result<Box<dyn DnsChallengeClient>> {
+  let option = HuaweiCloudOption::new(config.require("access_key")?, config.require("secret_key")?);
+  let mut option = http_option!(option, config);
+  if let Some(region) = config.get("region") {
+    option = option.region(region);
+  }
+  if let Some(project_id) = config.get("project_id") {
+    option = option.project_id(project_id);
+  }
+  if let Some(ttl) = config.number("ttl")? {
+    option = option.ttl(ttl);
+  }
+  Ok(Box::new(HuaweiCloudClient::new(option)?))
+}
+
+fn linode(config: &DnsProviderConfig) -> Result<Box<dyn DnsChallengeClient>> {
+  let option = http_option!(LinodeOption::new(config.require("token")?), config);
+  Ok(Box::new(LinodeClient::new(option)?))
+}
+
+fn ovh(config: &DnsProviderConfig) -> Result<Box<dyn DnsChallengeClient>> {
+  let option = OvhOption::new(
+    config.require("application_key")?,
+    config.require("application_secret")?,
+    config.require("consumer_key")?,
+  );
+  let mut option = http_option!(option, config);
+  if let Some(endpoint) = config.get("endpoint") {
+    option = option.endpoint(endpoint);
+  }
+  Ok(Box::new(OvhClient::new(option)?))
+}
+
+fn porkbun(config: &DnsProviderConfig) -> Result<Box<dyn DnsChallengeClient>> {
+  let option = PorkbunOption::new(
+    config.require("api_key")?,
+    config.require("secret_api_key")?,
+  );
+  let option = http_option!(option, config);
+  Ok(Box::new(PorkbunClient::new(option)?))
+}
+
+fn powerdns(config: &DnsProviderConfig) -> Result<Box<dyn DnsChallengeClient>> {
+  let option = PowerDnsOption::new(config.require("base_url")?, config.require("api_key")?);
+  let mut option = http_option!(option, config);
+  if let Some(server_id) = config.get("server_id") {
+    option = option.server_id(server_id);
+  }
+  if let Some(ttl) = config.number("ttl")? {
+    option = option.ttl(ttl);
+  }
+  Ok(Box::new(PowerDnsClient::new(option)?))
+}
+
+fn rfc2136(config: &DnsProviderConfig) -> Result<Box<dyn DnsChallengeClient>> {
+  let mut option = Rfc2136Option::new(parse_nameserver(config.require("nameserver")?)?);
+  if let Some(name) = config.get("tsig_key") {
+    let algorithm = match config.get("tsig_algorithm") {
+      Some(algorithm) => algorithm.parse()?,
+      None => TsigAlgorithm::HmacSha256,
+    };
+    option = option.tsig(TsigKey::new(
+      name,
+      algorithm,
+      config.require("tsig_secret")?,
+    )?);
+  }
+  if let Some(ttl) = config.number("ttl")? {
+    option = option.ttl(ttl);
+  }
+  if let Some(tcp) = config.flag("tcp")? {
+    option = option.tcp(tcp);
+  }
+  if let Some(timeout) = config.seconds("timeout")? {
+    option = option.timeout(timeout);
+  }
+  Ok(Box::new(Rfc2136Client::new(option)))
+}
+
+fn route53(config: &DnsProviderConfig) -> Result<Box<dyn DnsChallengeClient>> {
+  let option = Route53Option::new(config.require("access_key")?, config.require("secret_key")?);
+  let mut option = http_option!(option, config);
+  if let Some(token) = config.get("session_token") {
+    option = option.session_token(token);
+  }
+  if let Some(region) = config.get("region") {
+    option = option.region(region);
+  }
+  if let Some(zone_id) = config.get("hosted_zone_id") {
+    option = option.hosted_zone_id(zone_id);
+  }
+  if let Some(ttl) = config.number("ttl")? {
+    option = option.ttl(ttl);
+  }
+  Ok(Box::new(Route53Client::new(option)?))
+}
+
+fn vultr(config: &DnsProviderConfig) -> Result<Box<dyn DnsChallengeClient>> {
+  let option = http_option!(VultrOption::new(config.require("api_key")?), config);
+  Ok(Box::new(VultrClient::new(option)?))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn build_from_json() {
+    let registry = DnsProviderRegistry::new();
+    let json = r#"{"provider": "PowerDNS", "base_url": "http://127.0.0.1:8081", "api_key": "secret", "ttl": 120}"#;
+    let config = DnsProviderConfig::from_json(json).unwrap();
+    assert_eq!(config.number::<u32>("ttl").unwrap(), Some(120));
+    assert!(registry.build(&config).is_ok());
+
+    let config = DnsProviderConfig::new("acme-dns");
+    let err = registry.build(&config).err().unwrap();
+    assert!(err.to_string().contains("missing `base_url`"));
+
+    let json = r#"{"provider": "exec", "program": "/bin/sh", "args": ["-c", "echo \"$@\"", "sh"]}"#;
+    let config = DnsProviderConfig::from_json(json).unwrap();
+    assert!(registry.build(&config).is_ok());
+    let config = config.set("args", "-c");
+    let err = registry.build(&config).err().unwrap();
+    assert!(err.to_string().contains("`args` is not a JSON array"));
+
+    let config = DnsProviderConfig::new("unknown");
+    let err = registry.build(&config).err().unwrap();
+    assert!(err.to_string().contains("unknown provider"));
+  }
+}
@@ -0,0 +1,336 @@
+use std::{
+  net::{IpAddr, Ipv4Addr, SocketAddr},
+  time::Duration,
+};
+
+use snafu::ResultExt;
+use tokio::{
+  io::{AsyncReadExt, AsyncWriteExt},
+  net::{TcpStream, UdpSocket},
+};
+
+use crate::{
+  challenge::dns::{
+    RecordType,
+    resolver::message::{DnsMessage, DnsRecord, RecordData},
+  },
+  errors::{IoOperationSnafu, PlainTextSnafu, Result},
+};
+
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5);
+const DEFAULT_NAMESERVER: IpAddr = IpAddr::V4(Ipv4Addr::new(8, 8, 8, 8));
+const RCODE_NXDOMAIN: u16 = 3;
+const MAX_CNAME_CHAIN: usize = 8;
+
+/// Stub resolver sends DNS queries to nameservers directly, over UDP and falls back to TCP when
+/// the response is truncated
+#[derive(Debug, Clone)]
+pub struct DnsResolver {
+  server: SocketAddr,
+  timeout: Duration,
+}
+
+impl DnsResolver {
+  /// Create resolver with recursive nameserver, for example, `8.8.8.8:53`
+  pub fn new(server: SocketAddr) -> Self {
+    DnsResolver {
+      server,
+      timeout: DEFAULT_TIMEOUT,
+    }
+  }
+
+  /// Create resolver with the first nameserver in __/etc/resolv.conf__, `8.8.8.8:53` is used if
+  /// the file is absent or has no nameserver
+  pub fn new_from_system() -> Self {
+    let server = std::fs::read_to_string("/etc/resolv.conf")
+      .ok()
+      .and_then(|conf| {
+        conf
+          .lines()
+          .filter_map(|line| line.trim().strip_prefix("nameserver"))
+          .find_map(|ip| ip.trim().parse::<IpAddr>().ok())
+      })
+      .unwrap_or(DEFAULT_NAMESERVER);
+    Self::new(SocketAddr::new(server, 53))
+  }
+
+  /// Set timeout of each query, default is 5 seconds
+  pub fn timeout(mut self, timeout: Duration) -> Self {
+    self.timeout = timeout;
+    self
+  }
+
+  /// Recursive nameserver of this resolver
+  pub fn server(&self) -> SocketAddr {
+    self.server
+  }
+
+  /// Lookup TXT values of `name` with the recursive nameserver
+  pub async fn lookup_txt(&self, name: &str) -> Result<Vec<String>> {
+    let records = self.lookup(name, RecordType::TXT).await?;
+    let values = records
+      .iter()
+      .filter(|r| name_eq(&r.name, name))
+      .filter_map(DnsRecord::txt_value)
+      .collect();
+    Ok(values)
+  }
+
+  /// Follow CNAME chain of `name`, returns the final target, or `name` itself if it is not an alias
+  pub async fn resolve_cname(&self, name: &str) -> Result<String> {
+    let mut current = name.trim_end_matches('.').to_lowercase();
+    for _ in 0..MAX_CNAME_CHAIN {
+      let records = self.lookup(&current, RecordType::CNAME).await?;
+      let target = records
+        .iter()
+        .filter(|r| name_eq(&r.name, &current))
+        .find_map(|r| match &r.data {
+          RecordData::CNAME(target) => Some(target.trim_end_matches('.').to_lowercase()),
+          _ => None,
+        });
+      match target {
+        Some(target) => current = target,
+        None => return Ok(current),
+      }
+    }
+    PlainTextSnafu {
+      message: format!(
+        "DNS Error: CNAME chain of {} is too long or has a loop",
+        name
+      ),
+    }
+    .fail()
+  }
+
+  /// Find the zone which `fqdn` belongs to, by walking up the labels and looking for SOA record
+  pub async fn find_zone(&self, fqdn: &str) -> Result<String> {
+    let mut candidate = fqdn.trim_end_matches('.');
+    while !candidate.is_empty() {
+      let res = self
+        .query(self.server, candidate, RecordType::SOA, true)
+        .await?;
+      let soa = RecordType::SOA.code();
+      let cname = RecordType::CNAME.code();
+      if res
+        .answers
+        .iter()
+        .any(|r| r.rtype == soa && name_eq(&r.name, candidate))
+      {
+        return Ok(candidate.to_lowercase());
+      }
+      let aliased = res
+        .answers
+        .iter()
+        .any(|r| r.rtype == cname && name_eq(&r.name, candidate));
+      if !aliased && let Some(record) = res.authorities.iter().find(|r| r.rtype == soa) {
+        return Ok(record.name.to_lowercase());
+      }
+      candidate = match candidate.split_once('.') {
+        Some((_, parent)) => parent,
+        None => break,
+      };
+    }
+    PlainTextSnafu {
+      message: format!("DNS Error: can not find zone of {}", fqdn),
+    }
+    .fail()
+  }
+
+  /// Find addresses of the authoritative nameservers of `zone`, with glue records if present
+  pub async fn nameservers(&self, zone: &str, port: u16) -> Result<Vec<SocketAddr>> {
+    let res = self.query(self.server, zone, RecordType::NS, true).await?;
+    let mut addrs = vec![];
+    for record in res.answers.iter().filter(|r| name_eq(&r.name, zone)) {
+      let RecordData::NS(ns) = &record.data else {
+        continue;
+      };
+      let mut ips: Vec<IpAddr> = res
+        .additionals
+        .iter()
+        .filter(|r| name_eq(&r.name, ns))
+        .filter_map(record_ip)
+        .collect();
+      // without glue, both IPv4 and IPv6 addresses of nameserver are looked up
+      if ips.is_empty() {
+        for rtype in [RecordType::A, RecordType::AAAA] {
+          ips.extend(self.lookup(ns, rtype).await?.iter().filter_map(record_ip));
+        }
+      }
+      addrs.extend(ips.into_iter().map(|ip| SocketAddr::new(ip, port)));
+    }
+    if addrs.is_empty() {
+      return PlainTextSnafu {
+        message: format!("DNS Error: can not find nameservers of {}", zone),
+      }
+      .fail();
+    }
+    Ok(addrs)
+  }
+
+  /// Query `name` with the recursive nameserver, returns records in answer section
+  pub(crate) async fn lookup(&self, name: &str, rtype: RecordType) -> Result<Vec<DnsRecord>> {
+    let res = self.query(self.server, name, rtype, true).await?;
+    Ok(res.answers)
+  }
+
+  /// Send query to `server`, `recursion` should be `false` when querying authoritative nameserver
+  pub(crate) async fn query(
+    &self,
+    server: SocketAddr,
+    name: &str,
+    rtype: RecordType,
+    recursion: bool,
+  ) -> Result<DnsMessage> {
+    let req = DnsMessage::query(rand::random(), name, rtype, recursion);
+    let res = self.exchange(server, &req, false).await?;
+    match res.rcode() {
+      0 | RCODE_NXDOMAIN => Ok(res),
+      rcode => PlainTextSnafu {
+        message: format!(
+          "DNS Error: query {} {:?} to {} failed, rcode: {}",
+          name, rtype, server, rcode
+        ),
+      }
+      .fail(),
+    }
+  }
+
+  /// Send message to `server` and wait for the response with the same id, UDP is tried first
+  /// unless `tcp` is `true`
+  pub(crate) async fn exchange(
+    &self,
+    server: SocketAddr,
+    req: &DnsMessage,
+    tcp: bool,
+  ) -> Result<DnsMessage> {
+    Ok(self.exchange_wire(server, req, tcp).await?.0)
+  }
+
+  /// The same as [`Self::exchange`], the response is returned with its bytes as received, which
+  /// TSIG of the response is computed over
+  pub(crate) async fn exchange_wire(
+    &self,
+    server: SocketAddr,
+    req: &DnsMessage,
+    tcp: bool,
+  ) -> Result<(DnsMessage, Vec<u8>)> {
+    let payload = req.encode()?;
+    if !tcp {
+      let res = self.with_timeout(server, self.exchange_udp(server, &payload, req.id));
+      let res = res.await?;
+      if !res.0.is_truncated() {
+        return Ok(res);
+      }
+    }
+    self
+      .with_timeout(server, self.exchange_tcp(server, &payload))
+      .await
+  }
+
+  async fn with_timeout<F, T>(&self, server: SocketAddr, future: F) -> Result<T>
+  where
+    F: Future<Output = Result<T>>,
+  {
+    match tokio::time::timeout(self.timeout, future).await {
+      Ok(res) => res,
+      Err(_) => PlainTextSnafu {
+        message: format!("DNS Error: query to {} timeout", server),
+      }
+      .fail(),
+    }
+  }
+
+  async fn exchange_udp(
+    &self,
+    server: SocketAddr,
+    payload: &[u8],
+    id: u16,
+  ) -> Result<(DnsMessage, Vec<u8>)> {
+    let local = match server {
+      SocketAddr::V4(_) => SocketAddr::from(([0, 0, 0, 0], 0)),
+      SocketAddr::V6(_) => SocketAddr::from(([0u16; 8], 0)),
+    };
+    let socket = UdpSocket::bind(local).await.context(IoOperationSnafu)?;
+    socket.connect(server).await.context(IoOperationSnafu)?;
+    socket.send(payload).await.context(IoOperationSnafu)?;
+    let mut buf = vec![0u8; 4096];
+    loop {
+      let len = socket.recv(&mut buf).await.context(IoOperationSnafu)?;
+      // ignore malformed or unexpected response, and keep waiting until timeout
+      if let Ok(res) = DnsMessage::decode(&buf[..len])
+        && res.id == id
+        && res.is_response()
+      {
+        return Ok((res, buf[..len].to_vec()));
+      }
+    }
+  }
+
+  async fn exchange_tcp(
+    &self,
+    server: SocketAddr,
+    payload: &[u8],
+  ) -> Result<(DnsMessage, Vec<u8>)> {
+    let mut stream = TcpStream::connect(server).await.context(IoOperationSnafu)?;
+    let mut req = Vec::with_capacity(payload.len() + 2);
+    req.extend_from_slice(&(payload.len() as u16).to_be_bytes());
+    req.extend_from_slice(payload);
+    stream.write_all(&req).await.context(IoOperationSnafu)?;
+    let len = stream.read_u16().await.context(IoOperationSnafu)?;
+    let mut buf = vec![0u8; len as usize];
+    stream
+      .read_exact(&mut buf)
+      .await
+      .context(IoOperationSnafu)?;
+    Ok((DnsMessage::decode(&buf)?, buf))
+  }
+}
+
+fn record_ip(record: &DnsRecord) -> Option<IpAddr> {
+  match record.data {
+    RecordData::A(ip) => Some(IpAddr::V4(ip)),
+    RecordData::AAAA(ip) => Some(IpAddr::V6(ip)),
+    _ => None,
+  }
+}
+
+/// Compare domain names case-insensitively, ignoring trailing dot
+pub(crate) fn name_eq(left: &str, right: &str) -> bool {
+  left
+    .trim_end_matches('.')
+    .eq_ignore_ascii_case(right.trim_end_matches('.'))
+}
+
+#[cfg(test)]
+mod tests {
+  use std::net::{Ipv4Addr, Ipv6Addr};
+
+  use super::*;
+  use crate::test_util::{dns_record, dns_stub_server};
+
+  #[tokio::test]
+  async fn nameservers_without_glue() {
+    let server = dns_stub_server(|mut res| {
+      let question = res.questions[0].clone();
+      let data = match RecordType::from_code(question.rtype) {
+        Some(RecordType::NS) => RecordData::NS("ns1.example.net".to_string()),
+        Some(RecordType::A) => RecordData::A(Ipv4Addr::LOCALHOST),
+        Some(RecordType::AAAA) => RecordData::AAAA(Ipv6Addr::LOCALHOST),
+        _ => return res,
+      };
+      let rtype = RecordType::from_code(question.rtype).unwrap();
+      res.answers.push(dns_record(&question.name, rtype, data));
+      res
+    })
+    .await;
+    let resolver = DnsResolver::new(server);
+    let nameservers = resolver.nameservers("example.com", 53).await.unwrap();
+    assert_eq!(
+      nameservers,
+      [
+        SocketAddr::from((Ipv4Addr::LOCALHOST, 53)),
+        SocketAddr::from((Ipv6Addr::LOCALHOST, 53))
+      ]
+    );
+  }
+}
@@ -0,0 +1,401 @@
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use crate::{
+  challenge::dns::RecordType,
+  errors::{Error, PlainTextSnafu, Result},
+};
+
+pub(crate) const CLASS_IN: u16 = 1;
+pub(crate) const CLASS_NONE: u16 = 254;
+pub(crate) const CLASS_ANY: u16 = 255;
+
+const FLAG_QR: u16 = 0x8000;
+const FLAG_TC: u16 = 0x0200;
+const FLAG_RD: u16 = 0x0100;
+const OPCODE_UPDATE: u16 = 5 << 11;
+const MAX_POINTER_JUMPS: usize = 32;
+
+/// DNS message of [RFC 1035](https://www.rfc-editor.org/rfc/rfc1035#section-4), only the parts
+/// used by challenges are supported
+#[derive(Debug, Clone)]
+pub(crate) struct DnsMessage {
+  pub(crate) id: u16,
+  pub(crate) flags: u16,
+  pub(crate) questions: Vec<DnsQuestion>,
+  pub(crate) answers: Vec<DnsRecord>,
+  pub(crate) authorities: Vec<DnsRecord>,
+  pub(crate) additionals: Vec<DnsRecord>,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct DnsQuestion {
+  pub(crate) name: String,
+  pub(crate) rtype: u16,
+  pub(crate) class: u16,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct DnsRecord {
+  pub(crate) name: String,
+  pub(crate) rtype: u16,
+  pub(crate) class: u16,
+  pub(crate) ttl: u32,
+  pub(crate) data: RecordData,
+}
+
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum RecordData {
+  A(Ipv4Addr),
+  AAAA(Ipv6Addr),
+  NS(String),
+  CNAME(String),
+  TXT(Vec<String>),
+  Raw(Vec<u8>),
+}
+
+impl DnsMessage {
+  pub(crate) fn query(id: u16, name: &str, rtype: RecordType, recursion: bool) -> Self {
+    let question = DnsQuestion {
+      name: name.to_string(),
+      rtype: rtype.code(),
+      class: CLASS_IN,
+    };
+    DnsMessage {
+      id,
+      flags: if recursion { FLAG_RD } else { 0 },
+      questions: vec![question],
+      answers: vec![],
+      authorities: vec![],
+      additionals: vec![],
+    }
+  }
+
+  /// UPDATE message of [RFC 2136](https://www.rfc-editor.org/rfc/rfc2136#section-2), zone section
+  /// is kept in `questions`, prerequisite section in `answers` and update section in `authorities`
+  pub(crate) fn update(id: u16, zone: &str) -> Self {
+    let zone = DnsQuestion {
+      name: zone.to_string(),
+      rtype: RecordType::SOA.code(),
+      class: CLASS_IN,
+    };
+    DnsMessage {
+      id,
+      flags: OPCODE_UPDATE,
+      questions: vec![zone],
+      answers: vec![],
+      authorities: vec![],
+      additionals: vec![],
+    }
+  }
+
+  pub(crate) fn is_response(&self) -> bool {
+    self.flags & FLAG_QR != 0
+  }
+
+  pub(crate) fn is_truncated(&self) -> bool {
+    self.flags & FLAG_TC != 0
+  }
+
+  pub(crate) fn rcode(&self) -> u16 {
+    self.flags & 0x000F
+  }
+
+  pub(crate) fn encode(&self) -> Result<Vec<u8>> {
+    let mut buf = Vec::with_capacity(512);
+    buf.extend_from_slice(&self.id.to_be_bytes());
+    buf.extend_from_slice(&self.flags.to_be_bytes());
+    for count in [
+      self.questions.len(),
+      self.answers.len(),
+      self.authorities.len(),
+      self.additionals.len(),
+    ] {
+      buf.extend_from_slice(&(count as u16).to_be_bytes());
+    }
+    for question in &self.questions {
+      encode_name(&mut buf, &question.name)?;
+      buf.extend_from_slice(&question.rtype.to_be_bytes());
+      buf.extend_from_slice(&question.class.to_be_bytes());
+    }
+    for record in self
+      .answers
+      .iter()
+      .chain(&self.authorities)
+      .chain(&self.additionals)
+    {
+      record.encode(&mut buf)?;
+    }
+    Ok(buf)
+  }
+
+  pub(crate) fn decode(buf: &[u8]) -> Result<Self> {
+    let mut reader = Reader { buf, pos: 0 };
+    let id = reader.u16()?;
+    let flags = reader.u16()?;
+    let counts = [reader.u16()?, reader.u16()?, reader.u16()?, reader.u16()?];
+    let mut questions = Vec::with_capacity(counts[0] as usize);
+    for _ in 0..counts[0] {
+      questions.push(DnsQuestion {
+        name: reader.name()?,
+        rtype: reader.u16()?,
+        class: reader.u16()?,
+      });
+    }
+    let mut sections = [vec![], vec![], vec![]];
+    for (section, count) in sections.iter_mut().zip(&counts[1..]) {
+      for _ in 0..*count {
+        section.push(reader.record()?);
+      }
+    }
+    let [answers, authorities, additionals] = sections;
+    Ok(DnsMessage {
+      id,
+      flags,
+      questions,
+      answers,
+      authorities,
+      additionals,
+    })
+  }
+}
+
+impl DnsRecord {
+  pub(crate) fn encode(&self, buf: &mut Vec<u8>) -> Result<()> {
+    encode_name(buf, &self.name)?;
+    buf.extend_from_slice(&self.rtype.to_be_bytes());
+    buf.extend_from_slice(&self.class.to_be_bytes());
+    buf.extend_from_slice(&self.ttl.to_be_bytes());
+    let mut data = Vec::new();
+    match &self.data {
+      RecordData::A(ip) => data.extend_from_slice(&ip.octets()),
+      RecordData::AAAA(ip) => data.extend_from_slice(&ip.octets()),
+      RecordData::NS(name) | RecordData::CNAME(name) => encode_name(&mut data, name)?,
+      RecordData::TXT(values) => {
+        for value in values {
+          encode_txt(&mut data, value);
+        }
+      }
+      RecordData::Raw(raw) => data.extend_from_slice(raw),
+    }
+    buf.extend_from_slice(&(data.len() as u16).to_be_bytes());
+    buf.extend_from_slice(&data);
+    Ok(())
+  }
+
+  /// Value of TXT record, multiple strings are concatenated as RFC 7208 does
+  pub(crate) fn txt_value(&self) -> Option<String> {
+    match &self.data {
+      RecordData::TXT(values) => Some(values.concat()),
+      _ => None,
+    }
+  }
+}
+
+/// Offset of the last record in message `buf`, where TSIG record of signed message starts
+pub(crate) fn last_record_offset(buf: &[u8]) -> Result<usize> {
+  let mut reader = Reader { buf, pos: 4 };
+  let counts = [reader.u16()?, reader.u16()?, reader.u16()?, reader.u16()?];
+  for _ in 0..counts[0] {
+    reader.name()?;
+    reader.bytes(4)?;
+  }
+  let records = counts[1..].iter().map(|&c| c as usize).sum::<usize>();
+  for _ in 1..records {
+    reader.record()?;
+  }
+  Ok(reader.pos)
+}
+
+/// Encode domain name without compression, trailing dot is optional
+pub(crate) fn encode_name(buf: &mut Vec<u8>, name: &str) -> Result<()> {
+  for label in name
+    .trim_end_matches('.')
+    .split('.')
+    .filter(|l| !l.is_empty())
+  {
+    if label.len() > 63 {
+      return PlainTextSnafu {
+        message: format!("DNS Error: label '{}' is longer than 63 bytes", label),
+      }
+      .fail();
+    }
+    buf.push(label.len() as u8);
+    buf.extend_from_slice(label.as_bytes());
+  }
+  buf.push(0);
+  Ok(())
+}
+
+/// Encode TXT value as character-strings, values longer than 255 bytes are split
+pub(crate) fn encode_txt(buf: &mut Vec<u8>, value: &str) {
+  let bytes = value.as_bytes();
+  if bytes.is_empty() {
+    buf.push(0);
+  }
+  for chunk in bytes.chunks(255) {
+    buf.push(chunk.len() as u8);
+    buf.extend_from_slice(chunk);
+  }
+}
+
+struct Reader<'a> {
+  buf: &'a [u8],
+  pos: usize,
+}
+
+impl Reader<'_> {
+  fn bytes(&mut self, len: usize) -> Result<&[u8]> {
+    let end = self.pos + len;
+    if end > self.buf.len() {
+      return Err(truncated_error());
+    }
+    let bytes = &self.buf[self.pos..end];
+    self.pos = end;
+    Ok(bytes)
+  }
+
+  fn u8(&mut self) -> Result<u8> {
+    Ok(self.bytes(1)?[0])
+  }
+
+  fn u16(&mut self) -> Result<u16> {
+    let bytes = self.bytes(2)?;
+    Ok(u16::from_be_bytes([bytes[0], bytes[1]]))
+  }
+
+  fn u32(&mut self) -> Result<u32> {
+    let bytes = self.bytes(4)?;
+    Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+  }
+
+  fn name(&mut self) -> Result<String> {
+    let mut labels: Vec<String> = vec![];
+    let mut pos = self.pos;
+    let mut jumps = 0;
+    let mut end = None;
+    loop {
+      let len = *self.buf.get(pos).ok_or_else(truncated_error)? as usize;
+      match len & 0xC0 {
+        0x00 if len == 0 => {
+          end.get_or_insert(pos + 1);
+          break;
+        }
+        0x00 => {
+          let label = self
+            .buf
+            .get(pos + 1..pos + 1 + len)
+            .ok_or_else(truncated_error)?;
+          labels.push(String::from_utf8_lossy(label).to_string());
+          pos += 1 + len;
+        }
+        0xC0 => {
+          let low = *self.buf.get(pos + 1).ok_or_else(truncated_error)? as usize;
+          end.get_or_insert(pos + 2);
+          jumps += 1;
+          if jumps > MAX_POINTER_JUMPS {
+            return PlainTextSnafu {
+              message: "DNS Error: too many compression pointers".to_string(),
+            }
+            .fail();
+          }
+          pos = ((len & 0x3F) << 8) | low;
+        }
+        _ => {
+          return PlainTextSnafu {
+            message: "DNS Error: unsupported label type".to_string(),
+          }
+          .fail();
+        }
+      }
+    }
+    self.pos = end.unwrap_or(pos);
+    Ok(labels.join("."))
+  }
+
+  fn record(&mut self) -> Result<DnsRecord> {
+    let name = self.name()?;
+    let rtype = self.u16()?;
+    let class = self.u16()?;
+    let ttl = self.u32()?;
+    let len = self.u16()? as usize;
+    let start = self.pos;
+    let data = match RecordType::from_code(rtype) {
+      Some(RecordType::A) if len == 4 => {
+        let b = self.bytes(4)?;
+        RecordData::A(Ipv4Addr::new(b[0], b[1], b[2], b[3]))
+      }
+      Some(RecordType::AAAA) if len == 16 => {
+        let mut octets = [0u8; 16];
+        octets.copy_from_slice(self.bytes(16)?);
+        RecordData::AAAA(Ipv6Addr::from(octets))
+      }
+      Some(RecordType::NS) => RecordData::NS(self.name()?),
+      Some(RecordType::CNAME) => RecordData::CNAME(self.name()?),
+      Some(RecordType::TXT) => {
+        let mut values = vec![];
+        while self.pos < start + len {
+          let size = self.u8()? as usize;
+          values.push(String::from_utf8_lossy(self.bytes(size)?).to_string());
+        }
+        RecordData::TXT(values)
+      }
+      _ => RecordData::Raw(self.bytes(len)?.to_vec()),
+    };
+    self.pos = start + len;
+    Ok(DnsRecord {
+      name,
+      rtype,
+      class,
+      ttl,
+      data,
+    })
+  }
+}
+
+fn truncated_error() -> Error {
+  PlainTextSnafu {
+    message: "DNS Error: message is truncated",
+  }
+  .build()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn encode_and_decode() {
+    let mut message =
+      DnsMessage::query(0x1234, "_acme-challenge.example.com", RecordType::TXT, true);
+    message.flags |= FLAG_QR;
+    message.answers.push(DnsRecord {
+      name: "_acme-challenge.example.com".to_string(),
+      rtype: RecordType::TXT.code(),
+      class: CLASS_IN,
+      ttl: 60,
+      data: RecordData::TXT(vec!["a".repeat(300)]),
+    });
+    let decoded = DnsMessage::decode(&message.encode().unwrap()).unwrap();
+    assert_eq!(decoded.id, 0x1234);
+    assert!(decoded.is_response());
+    assert_eq!(decoded.questions[0].name, "_acme-challenge.example.com");
+    assert_eq!(decoded.answers[0].txt_value(), Some("a".repeat(300)));
+  }
+
+  #[test]
+  fn decode_compressed_name() {
+    let mut buf = vec![0, 1, 0x80, 0, 0, 1, 0, 1, 0, 0, 0, 0];
+    encode_name(&mut buf, "example.com").unwrap();
+    buf.extend_from_slice(&[0, 2, 0, 1]);
+    buf.extend_from_slice(&[
+      0xC0, 12, 0, 2, 0, 1, 0, 0, 0, 60, 0, 6, 3, b'n', b's', b'1', 0xC0, 12,
+    ]);
+    let decoded = DnsMessage::decode(&buf).unwrap();
+    assert_eq!(decoded.answers[0].name, "example.com");
+    assert_eq!(
+      decoded.answers[0].data,
+      RecordData::NS("ns1.example.com".to_string())
+    );
+  }
+}
@@ -0,0 +1,9 @@
+//! Minimal DNS client used to inspect records before and after challenges
+//!
+//! Link: <https://www.rfc-editor.org/rfc/rfc1035>
+
+mod client;
+pub use client::DnsResolver;
+pub(crate) use client::name_eq;
+
+pub(crate) mod message;
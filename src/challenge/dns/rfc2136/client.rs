@@ -0,0 +1,297 @@
+use std::net::SocketAddr;
+
+use futures_util::future::BoxFuture;
+use jiff::Timestamp;
+
+use crate::{
+  challenge::dns::{
+    DnsChallengeClient, RecordType, TxtRecord,
+    resolver::{
+      DnsResolver,
+      message::{CLASS_IN, CLASS_NONE, DnsMessage, DnsRecord, RecordData},
+      name_eq,
+    },
+    rfc2136::{Rfc2136Option, TsigKey, tsig::response_error},
+  },
+  errors::{PlainTextSnafu, Result},
+};
+
+/// Client sends dynamic update to the primary nameserver of zone
+#[derive(Debug)]
+pub struct Rfc2136Client {
+  nameserver: SocketAddr,
+  tsig: Option<TsigKey>,
+  ttl: u32,
+  tcp: bool,
+  resolver: DnsResolver,
+}
+
+impl Rfc2136Client {
+  /// Create client with option, see [`Rfc2136Option`]
+  pub fn new(option: Rfc2136Option) -> Self {
+    let Rfc2136Option {
+      nameserver,
+      tsig,
+      ttl,
+      tcp,
+      timeout,
+    } = option;
+    let mut resolver = DnsResolver::new(nameserver);
+    if let Some(timeout) = timeout {
+      resolver = resolver.timeout(timeout);
+    }
+    Rfc2136Client {
+      nameserver,
+      tsig,
+      ttl,
+      tcp,
+      resolver,
+    }
+  }
+
+  /// Create client from environment variable, see [`Rfc2136Option::new_from_env`]
+  pub fn new_from_env() -> Result<Self> {
+    Ok(Self::new(Rfc2136Option::new_from_env()?))
+  }
+
+  /// Add TXT record with `value` at `fqdn`, other values of `fqdn` are kept
+  pub async fn add_txt(&self, zone: &str, fqdn: &str, value: &str) -> Result<()> {
+    let mut message = DnsMessage::update(rand::random(), zone);
+    message
+      .authorities
+      .push(txt_record(fqdn, CLASS_IN, self.ttl, value));
+    self.send_update(message).await
+  }
+
+  /// Remove TXT record with `value` at `fqdn`, other values of `fqdn` are kept
+  pub async fn remove_txt(&self, zone: &str, fqdn: &str, value: &str) -> Result<()> {
+    let mut message = DnsMessage::update(rand::random(), zone);
+    // class NONE means deleting the RR from RRset, https://www.rfc-editor.org/rfc/rfc2136#section-2.5.4
+    message
+      .authorities
+      .push(txt_record(fqdn, CLASS_NONE, 0, value));
+    self.send_update(message).await
+  }
+
+  /// Send signed update, the response must be signed by the same key, unless it reports TSIG
+  /// error, which is sent unsigned
+  async fn send_update(&self, mut message: DnsMessage) -> Result<()> {
+    let request_mac = match &self.tsig {
+      Some(key) => Some(key.sign(&mut message, unix_now(), None)?),
+      None => None,
+    };
+    let (res, wire) = self
+      .resolver
+      .exchange_wire(self.nameserver, &message, self.tcp)
+      .await?;
+    let tsig_error = match response_error(&res) {
+      Some(error) if error != 0 => format!(", TSIG error: {}", error),
+      _ => String::new(),
+    };
+    if let (Some(key), Some(request_mac)) = (&self.tsig, &request_mac)
+      && tsig_error.is_empty()
+    {
+      key.verify(&res, &wire, request_mac, unix_now())?;
+    }
+    let rcode = res.rcode();
+    if rcode == 0 {
+      return Ok(());
+    }
+    PlainTextSnafu {
+      message: format!(
+        "RFC2136 Error: update {} rejected by {}, rcode: {}{}",
+        message.questions[0].name,
+        self.nameserver,
+        rcode_name(rcode),
+        tsig_error
+      ),
+    }
+    .fail()
+  }
+}
+
+impl DnsChallengeClient for Rfc2136Client {
+  fn has_zone<'a>(&'a self, zone: &'a str) -> BoxFuture<'a, Result<bool>> {
+    Box::pin(async move {
+      let req = DnsMessage::query(rand::random(), zone, RecordType::SOA, false);
+      let res = self
+        .resolver
+        .exchange(self.nameserver, &req, self.tcp)
+        .await?;
+      let soa = RecordType::SOA.code();
+      let hosted = res.rcode() == 0
+        && res
+          .answers
+          .iter()
+          .any(|r| r.rtype == soa && name_eq(&r.name, zone));
+      Ok(hosted)
+    })
+  }
+
+  fn create_txt<'a>(
+    &'a self,
+    zone: &'a str,
+    fqdn: &'a str,
+    value: &'a str,
+  ) -> BoxFuture<'a, Result<TxtRecord>> {
+    Box::pin(async move {
+      self.add_txt(zone, fqdn, value).await?;
+      Ok(TxtRecord::new(zone, fqdn, value, ""))
+    })
+  }
+
+  fn delete_txt<'a>(&'a self, record: &'a TxtRecord) -> BoxFuture<'a, Result<()>> {
+    Box::pin(async move {
+      self
+        .remove_txt(&record.zone, &record.fqdn, &record.value)
+        .await
+    })
+  }
+}
+
+fn txt_record(fqdn: &str, class: u16, ttl: u32, value: &str) -> DnsRecord {
+  DnsRecord {
+    name: fqdn.to_string(),
+    rtype: RecordType::TXT.code(),
+    class,
+    ttl,
+    data: RecordData::TXT(vec![value.to_string()]),
+  }
+}
+
+fn unix_now() -> u64 {
+  Timestamp::now().as_second().max(0) as u64
+}
+
+fn rcode_name(rcode: u16) -> String {
+  let name = match rcode {
+    1 => "FORMERR",
+    2 => "SERVFAIL",
+    3 => "NXDOMAIN",
+    4 => "NOTIMP",
+    5 => "REFUSED",
+    6 => "YXDOMAIN",
+    7 => "YXRRSET",
+    8 => "NXRRSET",
+    9 => "NOTAUTH",
+    10 => "NOTZONE",
+    _ => return rcode.to_string(),
+  };
+  name.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{
+    challenge::dns::rfc2136::TsigAlgorithm,
+    test_util::dns_stub_server,
+    util::{base64_decode, sha2_hmac},
+  };
+
+  const SECRET: &str = "c2VjcmV0LWtleS1mb3ItdGVzdGluZy1vbmx5LTAwMDA=";
+  const OTHER_SECRET: &str = "b3RoZXIta2V5LWZvci10ZXN0aW5nLW9ubHktMDAwMDA=";
+
+  /// Stub primary nameserver, verifies MAC of TSIG with [`SECRET`] and answers NOTAUTH with
+  /// BADSIG error on mismatch, as RFC 8945 section 5.2.2 does, otherwise the response is signed
+  /// and then passed to `tamper`
+  async fn stub_server<F>(mut tamper: F) -> SocketAddr
+  where
+    F: FnMut(&mut DnsMessage) + Send + 'static,
+  {
+    dns_stub_server(move |mut req| {
+      let tsig = req.additionals.pop().unwrap();
+      let RecordData::Raw(rdata) = &tsig.data else {
+        unreachable!()
+      };
+      // algorithm name is 13 bytes, then time (6), fudge (2), mac size (2), mac (32)
+      let mut data = req.encode().unwrap();
+      data.extend_from_slice(&[8, b'a', b'c', b'm', b'e', b'-', b'k', b'e', b'y', 0]);
+      data.extend_from_slice(&[0, 255, 0, 0, 0, 0]);
+      data.extend_from_slice(&rdata[..13 + 8]);
+      data.extend_from_slice(&[0, 0, 0, 0]);
+      let secret = base64_decode(SECRET).unwrap();
+      let expected = sha2_hmac(secret, &data).unwrap();
+      req.authorities.clear();
+      if rdata[23..55] != expected[..] {
+        // unsigned TSIG of error response, mac size is 0 and error is BADSIG (16)
+        let mut rdata = rdata[..13 + 8].to_vec();
+        rdata.extend_from_slice(&[0, 0]);
+        rdata.extend_from_slice(&req.id.to_be_bytes());
+        rdata.extend_from_slice(&[0, 16, 0, 0]);
+        req.flags |= 9;
+        req.additionals.push(DnsRecord {
+          data: RecordData::Raw(rdata),
+          ..tsig
+        });
+        return req;
+      }
+      let mut time_signed = [0u8; 8];
+      time_signed[2..].copy_from_slice(&rdata[13..19]);
+      let key = TsigKey::new("acme-key", TsigAlgorithm::HmacSha256, SECRET).unwrap();
+      req.flags |= 0x8000;
+      key
+        .sign(
+          &mut req,
+          u64::from_be_bytes(time_signed),
+          Some(&rdata[23..55]),
+        )
+        .unwrap();
+      tamper(&mut req);
+      req
+    })
+    .await
+  }
+
+  #[tokio::test]
+  async fn signed_update() {
+    let server = stub_server(|_| {}).await;
+    let key = TsigKey::new("acme-key", TsigAlgorithm::HmacSha256, SECRET).unwrap();
+    let client = Rfc2136Client::new(Rfc2136Option::new(server).tsig(key));
+    client
+      .add_txt("example.com", "_acme-challenge.example.com", "token")
+      .await
+      .unwrap();
+    client
+      .remove_txt("example.com", "_acme-challenge.example.com", "token")
+      .await
+      .unwrap();
+
+    let key = TsigKey::new("acme-key", TsigAlgorithm::HmacSha256, OTHER_SECRET).unwrap();
+    let client = Rfc2136Client::new(Rfc2136Option::new(server).tsig(key));
+    let res = client
+      .add_txt("example.com", "_acme-challenge.example.com", "token")
+      .await;
+    let err = res.unwrap_err().to_string();
+    assert!(err.contains("rcode: NOTAUTH, TSIG error: 16"));
+  }
+
+  #[tokio::test]
+  async fn reject_tampered_response() {
+    let key = TsigKey::new("acme-key", TsigAlgorithm::HmacSha256, SECRET).unwrap();
+    // NOERROR turned into REFUSED after signing
+    let server = stub_server(|res| res.flags |= 5).await;
+    let client = Rfc2136Client::new(Rfc2136Option::new(server).tsig(key.clone()));
+    let res = client
+      .add_txt("example.com", "_acme-challenge.example.com", "token")
+      .await;
+    assert!(
+      res
+        .unwrap_err()
+        .to_string()
+        .contains("TSIG Error: bad MAC of response")
+    );
+
+    let server = stub_server(|res| res.additionals.clear()).await;
+    let client = Rfc2136Client::new(Rfc2136Option::new(server).tsig(key));
+    let res = client
+      .add_txt("example.com", "_acme-challenge.example.com", "token")
+      .await;
+    assert!(
+      res
+        .unwrap_err()
+        .to_string()
+        .contains("TSIG Error: response is not signed")
+    );
+  }
+}
@@ -0,0 +1,12 @@
+//! Implement of [RFC 2136](https://www.rfc-editor.org/rfc/rfc2136) dynamic DNS update, signed
+//! with [TSIG](https://www.rfc-editor.org/rfc/rfc8945), works with BIND, Knot, PowerDNS and so on
+
+mod client;
+pub use client::Rfc2136Client;
+
+mod option;
+pub use option::Rfc2136Option;
+pub(crate) use option::parse_nameserver;
+
+mod tsig;
+pub use tsig::{TsigAlgorithm, TsigKey};
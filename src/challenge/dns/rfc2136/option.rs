@@ -0,0 +1,110 @@
+use std::{
+  net::{IpAddr, SocketAddr},
+  time::Duration,
+};
+
+use crate::{
+  challenge::dns::rfc2136::{TsigAlgorithm, TsigKey},
+  errors::{PlainTextSnafu, Result},
+  util::env_single_var,
+};
+
+/// Options for create an [`crate::challenge::dns::rfc2136::Rfc2136Client`] instance
+#[derive(Debug)]
+pub struct Rfc2136Option {
+  pub(crate) nameserver: SocketAddr,
+  pub(crate) tsig: Option<TsigKey>,
+  pub(crate) ttl: u32,
+  pub(crate) tcp: bool,
+  pub(crate) timeout: Option<Duration>,
+}
+
+impl Rfc2136Option {
+  /// Create option with primary nameserver accepting updates, for example, `10.0.0.53:53`
+  pub fn new(nameserver: SocketAddr) -> Self {
+    Rfc2136Option {
+      nameserver,
+      tsig: None,
+      ttl: 60,
+      tcp: false,
+      timeout: None,
+    }
+  }
+
+  /// Create option from environment variable, variable name for nameserver is
+  /// __RFC2136_NAMESERVER__ or __EASY_ACME_RFC2136_NAMESERVER__, and for TSIG (optional) are
+  /// __RFC2136_TSIG_KEY__, __RFC2136_TSIG_SECRET__ and __RFC2136_TSIG_ALGORITHM__ (default is
+  /// `hmac-sha256.`), with __EASY_ACME_RFC2136___ prefixed alternatives
+  pub fn new_from_env() -> Result<Self> {
+    let nameserver = parse_nameserver(&Self::env_nameserver()?)?;
+    let mut option = Self::new(nameserver);
+    if let Ok(name) = Self::env_tsig_key() {
+      let algorithm = match Self::env_tsig_algorithm() {
+        Ok(algorithm) => algorithm.parse()?,
+        Err(_) => TsigAlgorithm::HmacSha256,
+      };
+      option = option.tsig(TsigKey::new(name, algorithm, &Self::env_tsig_secret()?)?);
+    }
+    Ok(option)
+  }
+
+  /// Sign updates with TSIG key, default is `None` (unsigned)
+  pub fn tsig(mut self, key: TsigKey) -> Self {
+    self.tsig = Some(key);
+    self
+  }
+
+  /// Set TTL of TXT record, default is 60 seconds
+  pub fn ttl(mut self, ttl: u32) -> Self {
+    self.ttl = ttl;
+    self
+  }
+
+  /// Send updates over TCP instead of UDP, default is `false`
+  pub fn tcp(mut self, tcp: bool) -> Self {
+    self.tcp = tcp;
+    self
+  }
+
+  /// Set timeout, for example, `Duration::from_secs(5)`, default is 5 seconds
+  pub fn timeout(mut self, timeout: Duration) -> Self {
+    self.timeout = Some(timeout);
+    self
+  }
+}
+
+impl Rfc2136Option {
+  #[inline]
+  pub(crate) fn env_nameserver() -> Result<String> {
+    env_single_var(["RFC2136_NAMESERVER", "EASY_ACME_RFC2136_NAMESERVER"])
+  }
+
+  #[inline]
+  pub(crate) fn env_tsig_key() -> Result<String> {
+    env_single_var(["RFC2136_TSIG_KEY", "EASY_ACME_RFC2136_TSIG_KEY"])
+  }
+
+  #[inline]
+  pub(crate) fn env_tsig_secret() -> Result<String> {
+    env_single_var(["RFC2136_TSIG_SECRET", "EASY_ACME_RFC2136_TSIG_SECRET"])
+  }
+
+  #[inline]
+  pub(crate) fn env_tsig_algorithm() -> Result<String> {
+    env_single_var(["RFC2136_TSIG_ALGORITHM", "EASY_ACME_RFC2136_TSIG_ALGORITHM"])
+  }
+}
+
+/// Parse `ip`, `ip:port` or `[ipv6]:port`, port is 53 if absent
+pub(crate) fn parse_nameserver(value: &str) -> Result<SocketAddr> {
+  if let Ok(addr) = value.parse::<SocketAddr>() {
+    return Ok(addr);
+  }
+  match value.trim_matches(['[', ']']).parse::<IpAddr>() {
+    Ok(ip) => Ok(SocketAddr::new(ip, 53)),
+    Err(_) => PlainTextSnafu {
+      message: format!("Error: invalid nameserver address {}", value),
+    }
+    .fail(),
+  }
+}
@@ -0,0 +1,246 @@
+use std::str::FromStr;
+
+use crate::{
+  challenge::dns::resolver::{
+    message::{CLASS_ANY, DnsMessage, DnsRecord, RecordData, encode_name, last_record_offset},
+    name_eq,
+  },
+  errors::{PlainTextSnafu, Result},
+  util::{base64_decode, sha2_hmac, sha512_hmac},
+};
+
+const TYPE_TSIG: u16 = 250;
+const DEFAULT_FUDGE: u16 = 300;
+
+/// HMAC algorithm of TSIG key
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TsigAlgorithm {
+  HmacSha256,
+  HmacSha512,
+}
+
+impl TsigAlgorithm {
+  /// Algorithm name in TSIG record, for example, `hmac-sha256.`
+  pub fn name(&self) -> &'static str {
+    match self {
+      TsigAlgorithm::HmacSha256 => "hmac-sha256.",
+      TsigAlgorithm::HmacSha512 => "hmac-sha512.",
+    }
+  }
+
+  fn sign(&self, secret: &[u8], data: &[u8]) -> Result<Vec<u8>> {
+    match self {
+      TsigAlgorithm::HmacSha256 => sha2_hmac(secret, data),
+      TsigAlgorithm::HmacSha512 => sha512_hmac(secret, data),
+    }
+  }
+}
+
+impl FromStr for TsigAlgorithm {
+  type Err = crate::errors::Error;
+
+  /// Parse algorithm name, both `hmac-sha256` and `hmac-sha256.` are accepted
+  fn from_str(name: &str) -> Result<Self> {
+    match name.trim_end_matches('.').to_lowercase().as_str() {
+      "hmac-sha256" => Ok(TsigAlgorithm::HmacSha256),
+      "hmac-sha512" => Ok(TsigAlgorithm::HmacSha512),
+      _ => PlainTextSnafu {
+        message: format!("Error: unsupported TSIG algorithm {}", name),
+      }
+      .fail(),
+    }
+  }
+}
+
+/// TSIG key, the same as `key` clause of BIND config
+#[derive(Debug, Clone)]
+pub struct TsigKey {
+  pub(crate) name: String,
+  pub(crate) algorithm: TsigAlgorithm,
+  pub(crate) secret: Vec<u8>,
+}
+
+impl TsigKey {
+  /// Create key with name, algorithm and base64 encoded secret, for example, output of
+  /// `tsig-keygen -a hmac-sha256 acme-key`
+  pub fn new(name: impl AsRef<str>, algorithm: TsigAlgorithm, secret: &str) -> Result<Self> {
+    let key = TsigKey {
+      name: name.as_ref().trim_end_matches('.').to_lowercase(),
+      algorithm,
+      secret: base64_decode(secret)?,
+    };
+    Ok(key)
+  }
+
+  /// Append TSIG record to additional section of `message`, `time_signed` is unix seconds,
+  /// `request_mac` is given when signing response
+  ///
+  /// `return`: MAC of the TSIG record
+  pub(crate) fn sign(
+    &self,
+    message: &mut DnsMessage,
+    time_signed: u64,
+    request_mac: Option<&[u8]>,
+  ) -> Result<Vec<u8>> {
+    let variables = TsigVariables {
+      time_signed,
+      fudge: DEFAULT_FUDGE,
+      error: 0,
+      other: &[],
+    };
+    let mac = self.mac(request_mac, &message.encode()?, &variables)?;
+
+    let mut rdata = vec![];
+    encode_name(&mut rdata, self.algorithm.name())?;
+    rdata.extend_from_slice(&time_signed.to_be_bytes()[2..]);
+    rdata.extend_from_slice(&DEFAULT_FUDGE.to_be_bytes());
+    rdata.extend_from_slice(&(mac.len() as u16).to_be_bytes());
+    rdata.extend_from_slice(&mac);
+    rdata.extend_from_slice(&message.id.to_be_bytes());
+    rdata.extend_from_slice(&[0, 0, 0, 0]);
+    message.additionals.push(DnsRecord {
+      name: self.name.clone(),
+      rtype: TYPE_TSIG,
+      class: CLASS_ANY,
+      ttl: 0,
+      data: RecordData::Raw(rdata),
+    });
+    Ok(mac)
+  }
+
+  /// Check TSIG of `response` to request signed with `request_mac`, `wire` is the response as
+  /// received and `now` is unix seconds, see
+  /// [RFC 8945](https://www.rfc-editor.org/rfc/rfc8945#section-5.3)
+  pub(crate) fn verify(
+    &self,
+    response: &DnsMessage,
+    wire: &[u8],
+    request_mac: &[u8],
+    now: u64,
+  ) -> Result<()> {
+    let Some(record) = response.additionals.last().filter(|r| r.rtype == TYPE_TSIG) else {
+      return verify_error("response is not signed");
+    };
+    let RecordData::Raw(rdata) = &record.data else {
+      return verify_error("malformed TSIG record");
+    };
+    let Some(tsig) = TsigRdata::parse(rdata) else {
+      return verify_error("malformed TSIG record");
+    };
+    let mut algorithm = vec![];
+    encode_name(&mut algorithm, self.algorithm.name())?;
+    if !name_eq(&record.name, &self.name) || !tsig.algorithm.eq_ignore_ascii_case(&algorithm) {
+      return verify_error("response is signed by another key");
+    }
+    if now.abs_diff(tsig.time_signed) > tsig.fudge as u64 {
+      return verify_error("time signed of response is out of fudge");
+    }
+    // message without TSIG record and with the original id, counted in the MAC
+    let mut message = wire[..last_record_offset(wire)?].to_vec();
+    message[..2].copy_from_slice(&tsig.original_id.to_be_bytes());
+    let additionals = u16::from_be_bytes([message[10], message[11]]) - 1;
+    message[10..12].copy_from_slice(&additionals.to_be_bytes());
+    let variables = TsigVariables {
+      time_signed: tsig.time_signed,
+      fudge: tsig.fudge,
+      error: tsig.error,
+      other: tsig.other,
+    };
+    if self.mac(Some(request_mac), &message, &variables)? != tsig.mac {
+      return verify_error("bad MAC of response");
+    }
+    Ok(())
+  }
+
+  /// MAC of message without TSIG record, prefixed by MAC of request when signing response, see
+  /// [RFC 8945](https://www.rfc-editor.org/rfc/rfc8945#section-4.3)
+  fn mac(
+    &self,
+    request_mac: Option<&[u8]>,
+    message: &[u8],
+    variables: &TsigVariables,
+  ) -> Result<Vec<u8>> {
+    let mut data = vec![];
+    if let Some(request_mac) = request_mac {
+      data.extend_from_slice(&(request_mac.len() as u16).to_be_bytes());
+      data.extend_from_slice(request_mac);
+    }
+    data.extend_from_slice(message);
+    // TSIG variables, https://www.rfc-editor.org/rfc/rfc8945#section-4.3.3
+    encode_name(&mut data, &self.name)?;
+    data.extend_from_slice(&CLASS_ANY.to_be_bytes());
+    data.extend_from_slice(&0u32.to_be_bytes());
+    encode_name(&mut data, self.algorithm.name())?;
+    data.extend_from_slice(&variables.time_signed.to_be_bytes()[2..]);
+    data.extend_from_slice(&variables.fudge.to_be_bytes());
+    data.extend_from_slice(&variables.error.to_be_bytes());
+    data.extend_from_slice(&(variables.other.len() as u16).to_be_bytes());
+    data.extend_from_slice(variables.other);
+    self.algorithm.sign(&self.secret, &data)
+  }
+}
+
+struct TsigVariables<'a> {
+  time_signed: u64,
+  fudge: u16,
+  error: u16,
+  other: &'a [u8],
+}
+
+/// Fields of TSIG RDATA, algorithm name is in wire format
+struct TsigRdata<'a> {
+  algorithm: &'a [u8],
+  time_signed: u64,
+  fudge: u16,
+  mac: &'a [u8],
+  original_id: u16,
+  error: u16,
+  other: &'a [u8],
+}
+
+impl<'a> TsigRdata<'a> {
+  fn parse(rdata: &'a [u8]) -> Option<Self> {
+    let mut pos = 0;
+    while let Some(&len) = rdata.get(pos) {
+      pos += 1 + len as usize;
+      if len == 0 {
+        break;
+      }
+    }
+    let algorithm = rdata.get(..pos)?;
+    let u16_at = |pos: usize| Some(u16::from_be_bytes([*rdata.get(pos)?, *rdata.get(pos + 1)?]));
+    let mut time = [0u8; 8];
+    time[2..].copy_from_slice(rdata.get(pos..pos + 6)?);
+    let fudge = u16_at(pos + 6)?;
+    let mac_size = u16_at(pos + 8)? as usize;
+    pos += 10;
+    let mac = rdata.get(pos..pos + mac_size)?;
+    pos += mac_size;
+    let other_len = u16_at(pos + 4)? as usize;
+    Some(TsigRdata {
+      algorithm,
+      time_signed: u64::from_be_bytes(time),
+      fudge,
+      mac,
+      original_id: u16_at(pos)?,
+      error: u16_at(pos + 2)?,
+      other: rdata.get(pos + 6..pos + 6 + other_len)?,
+    })
+  }
+}
+
+/// Error field of TSIG record in response, `None` if response is not signed
+pub(crate) fn response_error(message: &DnsMessage) -> Option<u16> {
+  let record = message.additionals.iter().find(|r| r.rtype == TYPE_TSIG)?;
+  let RecordData::Raw(rdata) = &record.data else {
+    return None;
+  };
+  Some(TsigRdata::parse(rdata)?.error)
+}
+
+fn verify_error(reason: &str) -> Result<()> {
+  PlainTextSnafu {
+    message: format!("TSIG Error: {}", reason),
+  }
+  .fail()
+}
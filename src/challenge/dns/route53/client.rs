@@ -0,0 +1,388 @@
+use std::time::Duration;
+
+use futures_util::future::BoxFuture;
+use http::{
+  HeaderMap, HeaderName, Method,
+  header::{AUTHORIZATION, CONTENT_TYPE, HOST},
+};
+use jiff::{Timestamp, tz::TimeZone};
+use reqwest::{Client, ClientBuilder};
+use serde::de::DeserializeOwned;
+use snafu::ResultExt;
+use tokio::time::Instant;
+
+use crate::{
+  challenge::dns::{
+    DnsChallengeClient, TxtRecord,
+    route53::{
+      Route53Action, Route53ChangeInfo, Route53ChangeReq, Route53HostedZone, Route53Option,
+      Route53RecordSet,
+      response::{ChangeData, ErrorData, HostedZonesData, RecordSetsData},
+      signer::SigV4Signer,
+    },
+  },
+  errors::{DeserializeXmlSnafu, PlainTextSnafu, ReqwestClientSnafu, Result, SerializeXmlSnafu},
+  util::str_to_header_value,
+};
+
+const API_VERSION: &str = "2013-04-01";
+
+/// Client for Route 53 api, requests are signed with Signature Version 4
+#[derive(Debug)]
+pub struct Route53Client {
+  access_key: String,
+  secret_key: String,
+  session_token: Option<String>,
+  region: String,
+  hosted_zone_id: Option<String>,
+  endpoint: String,
+  host: String,
+  ttl: u64,
+  wait_timeout: Duration,
+  wait_interval: Duration,
+  client: Client,
+}
+
+impl Route53Client {
+  /// Create client with option, see [`Route53Option`]
+  pub fn new(option: Route53Option) -> Result<Self> {
+    let Route53Option {
+      access_key,
+      secret_key,
+      session_token,
+      region,
+      hosted_zone_id,
+      endpoint,
+      ttl,
+      wait_timeout,
+      wait_interval,
+      proxy,
+      timeout,
+    } = option;
+    let mut client = ClientBuilder::new();
+    if let Some(timeout) = timeout {
+      client = client.timeout(timeout);
+    }
+    if let Some(proxy) = proxy {
+      let proxy = reqwest::Proxy::all(proxy).context(ReqwestClientSnafu)?;
+      client = client.proxy(proxy);
+    }
+    let client = client.build().context(ReqwestClientSnafu)?;
+    let endpoint = endpoint.trim_end_matches('/').to_string();
+    let host = endpoint
+      .split_once("://")
+      .map_or(endpoint.as_str(), |(_, host)| host)
+      .to_string();
+    Ok(Self {
+      access_key,
+      secret_key,
+      session_token,
+      region,
+      hosted_zone_id,
+      endpoint,
+      host,
+      ttl,
+      wait_timeout,
+      wait_interval,
+      client,
+    })
+  }
+
+  /// Create client from environment variable, see [`Route53Option::new_from_env`]
+  pub fn new_from_env() -> Result<Self> {
+    Self::new(Route53Option::new_from_env()?)
+  }
+
+  /// List public and private hosted zones with `name`, for example, `example.com`
+  pub async fn list_hosted_zones(&self, name: &str) -> Result<Vec<Route53HostedZone>> {
+    let name = format!("{}.", name.trim_end_matches('.'));
+    let path = format!("/{}/hostedzonesbyname", API_VERSION);
+    let query = [("dnsname", name.as_str()), ("maxitems", "10")];
+    let data: HostedZonesData = self.exec_request(Method::GET, &path, &query, None).await?;
+    let zones = data
+      .zones
+      .zones
+      .into_iter()
+      .filter(|z| z.name.eq_ignore_ascii_case(&name))
+      .collect();
+    Ok(zones)
+  }
+
+  /// Id of hosted zone `zone`, configured [`Route53Option::hosted_zone_id`] is preferred
+  pub async fn hosted_zone_id(&self, zone: &str) -> Result<String> {
+    if let Some(zone_id) = &self.hosted_zone_id {
+      return Ok(zone_id.clone());
+    }
+    match self.list_hosted_zones(zone).await?.first() {
+      Some(zone) => Ok(zone.short_id().to_string()),
+      None => PlainTextSnafu {
+        message: format!("Route53 Error: hosted zone {} not found", zone),
+      }
+      .fail(),
+    }
+  }
+
+  /// List TXT record set of `name` in hosted zone, `None` if it does not exist
+  pub async fn list_record_sets(
+    &self,
+    hosted_zone_id: &str,
+    name: &str,
+  ) -> Result<Option<Route53RecordSet>> {
+    let name = format!("{}.", name.trim_end_matches('.'));
+    let path = format!("/{}/hostedzone/{}/rrset", API_VERSION, hosted_zone_id);
+    let query = [("name", name.as_str()), ("type", "TXT"), ("maxitems", "1")];
+    let data: RecordSetsData = self.exec_request(Method::GET, &path, &query, None).await?;
+    let set = data
+      .sets
+      .sets
+      .into_iter()
+      .find(|s| s.rtype == "TXT" && s.name.eq_ignore_ascii_case(&name));
+    Ok(set)
+  }
+
+  /// Submit change of record set, the change is `PENDING` until it is propagated to all Route 53
+  /// nameservers, see [`Self::wait_change`]
+  pub async fn change_record_sets(&self, req: Route53ChangeReq<'_>) -> Result<Route53ChangeInfo> {
+    let path = format!("/{}/hostedzone/{}/rrset", API_VERSION, req.hosted_zone_id);
+    let body = quick_xml::se::to_string(&req.to_body()).context(SerializeXmlSnafu)?;
+    let data: ChangeData = self
+      .exec_request(Method::POST, &path, &[], Some(body))
+      .await?;
+    Ok(data.info)
+  }
+
+  /// Get status of change, `PENDING` or `INSYNC`
+  pub async fn get_change(&self, change_id: &str) -> Result<Route53ChangeInfo> {
+    let change_id = change_id.trim_start_matches("/change/");
+    let path = format!("/{}/change/{}", API_VERSION, change_id);
+    let data: ChangeData = self.exec_request(Method::GET, &path, &[], None).await?;
+    Ok(data.info)
+  }
+
+  /// Wait until change becomes `INSYNC`, see [`Route53Option::wait`]
+  pub async fn wait_change(&self, change_id: &str) -> Result<()> {
+    let deadline = Instant::now() + self.wait_timeout;
+    loop {
+      let info = self.get_change(change_id).await?;
+      if info.status == "INSYNC" {
+        return Ok(());
+      }
+      if Instant::now() + self.wait_interval > deadline {
+        return PlainTextSnafu {
+          message: format!(
+            "Route53 Error: change {} is still {} after {:?}",
+            change_id, info.status, self.wait_timeout
+          ),
+        }
+        .fail();
+      }
+      tokio::time::sleep(self.wait_interval).await;
+    }
+  }
+
+  /// Add `value` to TXT record set of `fqdn` with UPSERT, other values are kept
+  pub async fn upsert_txt(&self, hosted_zone_id: &str, fqdn: &str, value: &str) -> Result<()> {
+    let mut values = match self.list_record_sets(hosted_zone_id, fqdn).await? {
+      Some(set) => set.values(),
+      None => vec![],
+    };
+    if values.iter().any(|v| v == value) {
+      return Ok(());
+    }
+    values.push(value.to_string());
+    let req =
+      Route53ChangeReq::new(hosted_zone_id, Route53Action::Upsert, fqdn, &values).ttl(self.ttl);
+    let info = self.change_record_sets(req).await?;
+    self.wait_change(&info.id).await
+  }
+
+  /// Remove `value` from TXT record set of `fqdn`, the set is deleted if no value is left
+  pub async fn remove_txt(&self, hosted_zone_id: &str, fqdn: &str, value: &str) -> Result<()> {
+    let Some(set) = self.list_record_sets(hosted_zone_id, fqdn).await? else {
+      return Ok(());
+    };
+    let values = set.values();
+    if !values.iter().any(|v| v == value) {
+      return Ok(());
+    }
+    let remain: Vec<String> = values.iter().filter(|v| *v != value).cloned().collect();
+    // DELETE must match the current record set exactly, including TTL
+    let ttl = set.ttl.unwrap_or(self.ttl);
+    let req = if remain.is_empty() {
+      Route53ChangeReq::new(hosted_zone_id, Route53Action::Delete, fqdn, &values).ttl(ttl)
+    } else {
+      Route53ChangeReq::new(hosted_zone_id, Route53Action::Upsert, fqdn, &remain).ttl(ttl)
+    };
+    let info = self.change_record_sets(req).await?;
+    self.wait_change(&info.id).await
+  }
+}
+
+impl DnsChallengeClient for Route53Client {
+  fn has_zone<'a>(&'a self, zone: &'a str) -> BoxFuture<'a, Result<bool>> {
+    Box::pin(async move {
+      if self.hosted_zone_id.is_some() {
+        return Ok(true);
+      }
+      Ok(!self.list_hosted_zones(zone).await?.is_empty())
+    })
+  }
+
+  fn create_txt<'a>(
+    &'a self,
+    zone: &'a str,
+    fqdn: &'a str,
+    value: &'a str,
+  ) -> BoxFuture<'a, Result<TxtRecord>> {
+    Box::pin(async move {
+      let zone_id = self.hosted_zone_id(zone).await?;
+      self.upsert_txt(&zone_id, fqdn, value).await?;
+      Ok(TxtRecord::new(zone, fqdn, value, zone_id))
+    })
+  }
+
+  fn delete_txt<'a>(&'a self, record: &'a TxtRecord) -> BoxFuture<'a, Result<()>> {
+    Box::pin(async move {
+      self
+        .remove_txt(&record.id, &record.fqdn, &record.value)
+        .await
+    })
+  }
+}
+
+impl Route53Client {
+  async fn exec_request<R>(
+    &self,
+    method: Method,
+    path: &str,
+    query: &[(&str, &str)],
+    body: Option<String>,
+  ) -> Result<R>
+  where
+    R: DeserializeOwned,
+  {
+    let amz_date = Timestamp::now()
+      .to_zoned(TimeZone::UTC)
+      .strftime("%Y%m%dT%H%M%SZ")
+      .to_string();
+    let mut headers = HeaderMap::new();
+    headers.insert(HOST, str_to_header_value(&self.host)?);
+    headers.insert(
+      HeaderName::from_static("x-amz-date"),
+      str_to_header_value(&amz_date)?,
+    );
+    if let Some(token) = &self.session_token {
+      headers.insert(
+        HeaderName::from_static("x-amz-security-token"),
+        str_to_header_value(token)?,
+      );
+    }
+    if body.is_some() {
+      headers.insert(CONTENT_TYPE, str_to_header_value("application/xml")?);
+    }
+    let payload = body.unwrap_or_default();
+    let signer = SigV4Signer {
+      access_key: &self.access_key,
+      secret_key: &self.secret_key,
+      region: &self.region,
+      service: "route53",
+    };
+    let auth = signer.sign(
+      method.as_str(),
+      path,
+      query,
+      &headers,
+      payload.as_bytes(),
+      &amz_date,
+    )?;
+    headers.insert(AUTHORIZATION, str_to_header_value(&auth)?);
+    let url = format!("{}{}", self.endpoint, path);
+    let res = self
+      .client
+      .request(method, url)
+      .query(query)
+      .headers(headers)
+      .body(payload)
+      .send()
+      .await
+      .context(ReqwestClientSnafu)?;
+    let status = res.status();
+    let text = res.text().await.context(ReqwestClientSnafu)?;
+    if status.is_success() {
+      return quick_xml::de::from_str(&text).context(DeserializeXmlSnafu);
+    }
+    let message = match quick_xml::de::from_str::<ErrorData>(&text) {
+      Ok(ErrorData {
+        error: Some(error), ..
+      }) => format!("{}, {}", error.code, error.message),
+      Ok(ErrorData {
+        messages: Some(messages),
+        ..
+      }) => messages.messages.join(", "),
+      _ => text,
+    };
+    PlainTextSnafu {
+      message: format!("Route53 Error: {}, {}", status, message),
+    }
+    .fail()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::test_util::{StubResponse, stub_server};
+
+  /// Stub Route 53 api, hosts `example.com` with an existing TXT value `old`
+  async fn stub_route53() -> String {
+    stub_server(|req| {
+      assert!(req.contains("AWS4-HMAC-SHA256 Credential=AKID/"));
+      let body = if req.starts_with("GET /2013-04-01/hostedzonesbyname") {
+        "<ListHostedZonesByNameResponse><HostedZones><HostedZone>\
+         <Id>/hostedzone/Z123</Id><Name>example.com.</Name>\
+         </HostedZone></HostedZones></ListHostedZonesByNameResponse>"
+      } else if req.starts_with("GET /2013-04-01/hostedzone/Z123/rrset") {
+        "<ListResourceRecordSetsResponse><ResourceRecordSets><ResourceRecordSet>\
+         <Name>_acme-challenge.example.com.</Name><Type>TXT</Type><TTL>60</TTL>\
+         <ResourceRecords><ResourceRecord><Value>\"old\"</Value></ResourceRecord>\
+         </ResourceRecords></ResourceRecordSet></ResourceRecordSets>\
+         </ListResourceRecordSetsResponse>"
+      } else if req.starts_with("POST /2013-04-01/hostedzone/Z123/rrset")
+        && req.contains("<Action>UPSERT</Action>")
+        && req.contains("<Value>\"old\"</Value>")
+        && req.contains("<Value>\"token\"</Value>")
+      {
+        "<ChangeResourceRecordSetsResponse><ChangeInfo><Id>/change/C1</Id>\
+         <Status>PENDING</Status></ChangeInfo></ChangeResourceRecordSetsResponse>"
+      } else if req.starts_with("GET /2013-04-01/change/C1") {
+        "<GetChangeResponse><ChangeInfo><Id>/change/C1</Id>\
+         <Status>INSYNC</Status></ChangeInfo></GetChangeResponse>"
+      } else {
+        return StubResponse::new(
+          "400 Bad Request",
+          "<ErrorResponse><Error><Code>InvalidInput</Code><Message>bad</Message></Error></ErrorResponse>",
+        )
+        .header("content-type", "text/xml");
+      };
+      StubResponse::ok(body).header("content-type", "text/xml")
+    })
+    .await
+  }
+
+  #[tokio::test]
+  async fn upsert_keeps_other_values() {
+    let endpoint = stub_route53().await;
+    let option = Route53Option::new("AKID", "secret")
+      .endpoint(endpoint)
+      .wait(Duration::from_secs(1), Duration::from_millis(100));
+    let client = Route53Client::new(option).unwrap();
+    assert!(client.has_zone("example.com").await.unwrap());
+    let record = client
+      .create_txt("example.com", "_acme-challenge.example.com", "token")
+      .await
+      .unwrap();
+    assert_eq!(record.id, "Z123");
+    let res = client.get_change("C2").await;
+    assert!(res.unwrap_err().to_string().contains("InvalidInput"));
+  }
+}
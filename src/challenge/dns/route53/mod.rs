@@ -0,0 +1,17 @@
+//! Implement of [AWS Route 53](https://aws.amazon.com/route53/) DNS challenge
+//!
+//! Link: <https://docs.aws.amazon.com/Route53/latest/APIReference/API_ChangeResourceRecordSets.html>
+
+mod client;
+pub use client::Route53Client;
+
+mod option;
+pub use option::Route53Option;
+
+mod request;
+pub use request::{Route53Action, Route53ChangeReq};
+
+mod response;
+pub use response::{Route53ChangeInfo, Route53HostedZone, Route53RecordSet};
+
+mod signer;
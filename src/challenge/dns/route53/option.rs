@@ -0,0 +1,140 @@
+use std::time::Duration;
+
+use crate::{errors::Result, util::env_single_var};
+
+/// Options for create a [`crate::challenge::dns::route53::Route53Client`] instance
+#[derive(Debug)]
+pub struct Route53Option {
+  pub(crate) access_key: String,
+  pub(crate) secret_key: String,
+  pub(crate) session_token: Option<String>,
+  pub(crate) region: String,
+  pub(crate) hosted_zone_id: Option<String>,
+  pub(crate) endpoint: String,
+  pub(crate) ttl: u64,
+  pub(crate) wait_timeout: Duration,
+  pub(crate) wait_interval: Duration,
+  pub(crate) proxy: Option<String>,
+  pub(crate) timeout: Option<Duration>,
+}
+
+impl Route53Option {
+  /// Create option with AWS access key, this key must have privilege of
+  /// `route53:ListHostedZonesByName`, `route53:ListResourceRecordSets`,
+  /// `route53:ChangeResourceRecordSets` and `route53:GetChange`
+  pub fn new(access_key: impl Into<String>, secret_key: impl Into<String>) -> Self {
+    Route53Option {
+      access_key: access_key.into(),
+      secret_key: secret_key.into(),
+      session_token: None,
+      region: "us-east-1".to_string(),
+      hosted_zone_id: None,
+      endpoint: "https://route53.amazonaws.com".to_string(),
+      ttl: 60,
+      wait_timeout: Duration::from_secs(180),
+      wait_interval: Duration::from_secs(5),
+      proxy: None,
+      timeout: None,
+    }
+  }
+
+  /// Create option from environment variable, variable name for access key is
+  /// __AWS_ACCESS_KEY_ID__, for secret key is __AWS_SECRET_ACCESS_KEY__, and optional ones are
+  /// __AWS_SESSION_TOKEN__ and __AWS_HOSTED_ZONE_ID__, with __EASY_ACME_ROUTE53___ prefixed
+  /// alternatives, for example, __EASY_ACME_ROUTE53_ACCESS_KEY_ID__. Region is only read from
+  /// __EASY_ACME_ROUTE53_REGION__, since __AWS_REGION__ of other services is not the signing
+  /// region of Route 53
+  pub fn new_from_env() -> Result<Self> {
+    let mut option = Self::new(Self::env_access_key()?, Self::env_secret_key()?);
+    if let Ok(token) = Self::env_session_token() {
+      option = option.session_token(token);
+    }
+    if let Ok(region) = Self::env_region() {
+      option = option.region(region);
+    }
+    if let Ok(zone_id) = Self::env_hosted_zone_id() {
+      option = option.hosted_zone_id(zone_id);
+    }
+    Ok(option)
+  }
+
+  /// Set session token of temporary credentials, default is `None`
+  pub fn session_token(mut self, token: impl Into<String>) -> Self {
+    self.session_token = Some(token.into());
+    self
+  }
+
+  /// Set region used in signature, default is `us-east-1`. Route 53 is a global service signed
+  /// in `us-east-1`, change it only for other partitions, for example, `cn-northwest-1` of China
+  pub fn region(mut self, region: impl Into<String>) -> Self {
+    self.region = region.into();
+    self
+  }
+
+  /// Use this hosted zone instead of looking up by name, for example, `Z1D633PJN98FT9`
+  pub fn hosted_zone_id(mut self, zone_id: impl Into<String>) -> Self {
+    self.hosted_zone_id = Some(zone_id.into());
+    self
+  }
+
+  /// Set api endpoint, default is `https://route53.amazonaws.com`
+  pub fn endpoint(mut self, endpoint: impl Into<String>) -> Self {
+    self.endpoint = endpoint.into();
+    self
+  }
+
+  /// Set TTL of TXT record, default is 60 seconds
+  pub fn ttl(mut self, ttl: u64) -> Self {
+    self.ttl = ttl;
+    self
+  }
+
+  /// Set max time and interval to wait for change becoming `INSYNC`, default is 180 and 5 seconds
+  pub fn wait(mut self, timeout: Duration, interval: Duration) -> Self {
+    self.wait_timeout = timeout;
+    self.wait_interval = interval;
+    self
+  }
+
+  /// Set proxy, for example, `https://127.0.0.1:8080`, `socks5://127.0.0.1:9000`, default is `None`
+  pub fn proxy(mut self, proxy: impl Into<String>) -> Self {
+    self.proxy = Some(proxy.into());
+    self
+  }
+
+  /// Set timeout, for example, `Duration::from_secs(5)`, default is `None`
+  pub fn timeout(mut self, timeout: Duration) -> Self {
+    self.timeout = Some(timeout);
+    self
+  }
+}
+
+impl Route53Option {
+  #[inline]
+  pub(crate) fn env_access_key() -> Result<String> {
+    env_single_var(["AWS_ACCESS_KEY_ID", "EASY_ACME_ROUTE53_ACCESS_KEY_ID"])
+  }
+
+  #[inline]
+  pub(crate) fn env_secret_key() -> Result<String> {
+    env_single_var([
+      "AWS_SECRET_ACCESS_KEY",
+      "EASY_ACME_ROUTE53_SECRET_ACCESS_KEY",
+    ])
+  }
+
+  #[inline]
+  pub(crate) fn env_session_token() -> Result<String> {
+    env_single_var(["AWS_SESSION_TOKEN", "EASY_ACME_ROUTE53_SESSION_TOKEN"])
+  }
+
+  #[inline]
+  pub(crate) fn env_region() -> Result<String> {
+    env_single_var(["EASY_ACME_ROUTE53_REGION"])
+  }
+
+  #[inline]
+  pub(crate) fn env_hosted_zone_id() -> Result<String> {
+    env_single_var(["AWS_HOSTED_ZONE_ID", "EASY_ACME_ROUTE53_HOSTED_ZONE_ID"])
+  }
+}
@@ -0,0 +1,157 @@
+use serde::Serialize;
+
+use crate::challenge::dns::RecordType;
+
+const XMLNS: &str = "https://route53.amazonaws.com/doc/2013-04-01/";
+
+/// Action of [`Route53ChangeReq`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum Route53Action {
+  #[serde(rename = "CREATE")]
+  Create,
+
+  #[serde(rename = "DELETE")]
+  Delete,
+
+  #[serde(rename = "UPSERT")]
+  Upsert,
+}
+
+/// Request of [`crate::challenge::dns::route53::Route53Client::change_record_sets`], the whole
+/// TXT record set of `name` is replaced by `values`
+#[derive(Debug)]
+pub struct Route53ChangeReq<'a> {
+  pub(crate) hosted_zone_id: &'a str,
+  action: Route53Action,
+  name: &'a str,
+  values: &'a [String],
+  ttl: u64,
+  comment: Option<&'a str>,
+}
+
+impl<'a> Route53ChangeReq<'a> {
+  pub fn new(
+    hosted_zone_id: &'a str,
+    action: Route53Action,
+    name: &'a str,
+    values: &'a [String],
+  ) -> Self {
+    Self {
+      hosted_zone_id,
+      action,
+      name,
+      values,
+      ttl: 60,
+      comment: None,
+    }
+  }
+
+  pub fn ttl(mut self, ttl: u64) -> Self {
+    self.ttl = ttl;
+    self
+  }
+
+  pub fn comment(mut self, comment: &'a str) -> Self {
+    self.comment = Some(comment);
+    self
+  }
+
+  pub(crate) fn to_body(&self) -> ChangeRecordSetsBody {
+    let records = self
+      .values
+      .iter()
+      .map(|value| ResourceRecord {
+        value: quote_txt(value),
+      })
+      .collect();
+    let record_set = ResourceRecordSet {
+      name: self.name.to_string(),
+      rtype: RecordType::TXT,
+      ttl: self.ttl,
+      records: ResourceRecords { records },
+    };
+    ChangeRecordSetsBody {
+      xmlns: XMLNS,
+      batch: ChangeBatch {
+        comment: self.comment.map(str::to_string),
+        changes: Changes {
+          changes: vec![Change {
+            action: self.action,
+            record_set,
+          }],
+        },
+      },
+    }
+  }
+}
+
+/// TXT value of Route 53 is quoted, and long value is split into strings of 255 characters
+fn quote_txt(value: &str) -> String {
+  let chunks: Vec<String> = value
+    .as_bytes()
+    .chunks(255)
+    .map(|chunk| format!("\"{}\"", String::from_utf8_lossy(chunk)))
+    .collect();
+  chunks.join(" ")
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename = "ChangeResourceRecordSetsRequest")]
+pub(crate) struct ChangeRecordSetsBody {
+  #[serde(rename = "@xmlns")]
+  xmlns: &'static str,
+
+  #[serde(rename = "ChangeBatch")]
+  batch: ChangeBatch,
+}
+
+#[derive(Debug, Serialize)]
+struct ChangeBatch {
+  #[serde(rename = "Comment", skip_serializing_if = "Option::is_none")]
+  comment: Option<String>,
+
+  #[serde(rename = "Changes")]
+  changes: Changes,
+}
+
+#[derive(Debug, Serialize)]
+struct Changes {
+  #[serde(rename = "Change")]
+  changes: Vec<Change>,
+}
+
+#[derive(Debug, Serialize)]
+struct Change {
+  #[serde(rename = "Action")]
+  action: Route53Action,
+
+  #[serde(rename = "ResourceRecordSet")]
+  record_set: ResourceRecordSet,
+}
+
+#[derive(Debug, Serialize)]
+struct ResourceRecordSet {
+  #[serde(rename = "Name")]
+  name: String,
+
+  #[serde(rename = "Type")]
+  rtype: RecordType,
+
+  #[serde(rename = "TTL")]
+  ttl: u64,
+
+  #[serde(rename = "ResourceRecords")]
+  records: ResourceRecords,
+}
+
+#[derive(Debug, Serialize)]
+struct ResourceRecords {
+  #[serde(rename = "ResourceRecord")]
+  records: Vec<ResourceRecord>,
+}
+
+#[derive(Debug, Serialize)]
+struct ResourceRecord {
+  #[serde(rename = "Value")]
+  value: String,
+}
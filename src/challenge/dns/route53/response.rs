@@ -0,0 +1,137 @@
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct HostedZonesData {
+  #[serde(rename = "HostedZones")]
+  pub(crate) zones: HostedZones,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct HostedZones {
+  #[serde(rename = "HostedZone", default)]
+  pub(crate) zones: Vec<Route53HostedZone>,
+}
+
+/// Hosted zone returned by [`crate::challenge::dns::route53::Route53Client::list_hosted_zones`]
+#[derive(Debug, Clone, Deserialize)]
+pub struct Route53HostedZone {
+  /// Id of zone, for example, `/hostedzone/Z1D633PJN98FT9`
+  #[serde(rename = "Id")]
+  pub id: String,
+
+  /// Name of zone with trailing dot, for example, `example.com.`
+  #[serde(rename = "Name")]
+  pub name: String,
+}
+
+impl Route53HostedZone {
+  /// Id without `/hostedzone/` prefix
+  pub fn short_id(&self) -> &str {
+    self.id.trim_start_matches("/hostedzone/")
+  }
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct ChangeData {
+  #[serde(rename = "ChangeInfo")]
+  pub(crate) info: Route53ChangeInfo,
+}
+
+/// Change returned by [`crate::challenge::dns::route53::Route53Client::change_record_sets`]
+#[derive(Debug, Clone, Deserialize)]
+pub struct Route53ChangeInfo {
+  /// Id of change, for example, `/change/C2682N5HXP0BZ4`
+  #[serde(rename = "Id")]
+  pub id: String,
+
+  /// `PENDING` or `INSYNC`
+  #[serde(rename = "Status")]
+  pub status: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct RecordSetsData {
+  #[serde(rename = "ResourceRecordSets")]
+  pub(crate) sets: RecordSets,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct RecordSets {
+  #[serde(rename = "ResourceRecordSet", default)]
+  pub(crate) sets: Vec<Route53RecordSet>,
+}
+
+/// Record set returned by [`crate::challenge::dns::route53::Route53Client::list_record_sets`]
+#[derive(Debug, Clone, Deserialize)]
+pub struct Route53RecordSet {
+  #[serde(rename = "Name")]
+  pub name: String,
+
+  #[serde(rename = "Type")]
+  pub rtype: String,
+
+  #[serde(rename = "TTL")]
+  pub ttl: Option<u64>,
+
+  #[serde(rename = "ResourceRecords")]
+  records: Option<ResourceRecords>,
+}
+
+impl Route53RecordSet {
+  /// TXT values without quotes
+  pub fn values(&self) -> Vec<String> {
+    let Some(records) = &self.records else {
+      return vec![];
+    };
+    records
+      .records
+      .iter()
+      .map(|r| unquote_txt(&r.value))
+      .collect()
+  }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct ResourceRecords {
+  #[serde(rename = "ResourceRecord", default)]
+  records: Vec<ResourceRecord>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct ResourceRecord {
+  #[serde(rename = "Value")]
+  value: String,
+}
+
+/// `"abc" "def"` to `abcdef`
+fn unquote_txt(value: &str) -> String {
+  value
+    .split("\" \"")
+    .map(|s| s.trim_matches('"'))
+    .collect::<Vec<_>>()
+    .concat()
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct ErrorData {
+  #[serde(rename = "Error")]
+  pub(crate) error: Option<ErrorDetail>,
+
+  #[serde(rename = "Messages")]
+  pub(crate) messages: Option<ErrorMessages>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct ErrorDetail {
+  #[serde(rename = "Code")]
+  pub(crate) code: String,
+
+  #[serde(rename = "Message", default)]
+  pub(crate) message: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct ErrorMessages {
+  #[serde(rename = "Message", default)]
+  pub(crate) messages: Vec<String>,
+}
@@ -0,0 +1,91 @@
+use http::HeaderMap;
+
+use crate::{
+  errors::Result,
+  util::{canonical_request, sha2_hex, sha2_hmac, uri_encode},
+};
+
+/// [Signature Version 4](https://docs.aws.amazon.com/IAM/latest/UserGuide/reference_sigv-create-signed-request.html)
+/// of AWS api
+#[derive(Debug)]
+pub(crate) struct SigV4Signer<'a> {
+  pub(crate) access_key: &'a str,
+  pub(crate) secret_key: &'a str,
+  pub(crate) region: &'a str,
+  pub(crate) service: &'a str,
+}
+
+impl SigV4Signer<'_> {
+  /// Value of `Authorization` header, `headers` must contain `host` and `x-amz-date`, and
+  /// `amz_date` is formatted as `%Y%m%dT%H%M%SZ`
+  pub(crate) fn sign(
+    &self,
+    method: &str,
+    path: &str,
+    query: &[(&str, &str)],
+    headers: &HeaderMap,
+    payload: &[u8],
+    amz_date: &str,
+  ) -> Result<String> {
+    let canonical_uri = path
+      .split('/')
+      .map(uri_encode)
+      .collect::<Vec<_>>()
+      .join("/");
+    let (canonical_request, signed_headers) =
+      canonical_request(method, &canonical_uri, query, headers, payload);
+
+    let date = &amz_date[..8];
+    let scope = format!("{}/{}/{}/aws4_request", date, self.region, self.service);
+    let str_to_sign = format!(
+      "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+      amz_date,
+      scope,
+      sha2_hex(canonical_request)
+    );
+    let key = sha2_hmac(format!("AWS4{}", self.secret_key), date.as_bytes())?;
+    let key = sha2_hmac(key, self.region.as_bytes())?;
+    let key = sha2_hmac(key, self.service.as_bytes())?;
+    let key = sha2_hmac(key, b"aws4_request")?;
+    let signature = hex::encode(sha2_hmac(key, str_to_sign.as_bytes())?);
+    Ok(format!(
+      "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+      self.access_key, scope, signed_headers, signature
+    ))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use http::HeaderValue;
+
+  use super::*;
+
+  /// Example of AWS document, `GET https://iam.amazonaws.com/?Action=ListUsers&Version=2010-05-08`
+  #[test]
+  fn sign_example_request() {
+    let signer = SigV4Signer {
+      access_key: "AKIDEXAMPLE",
+      secret_key: "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY",
+      region: "us-east-1",
+      service: "iam",
+    };
+    let mut headers = HeaderMap::new();
+    headers.insert(
+      "content-type",
+      HeaderValue::from_static("application/x-www-form-urlencoded; charset=utf-8"),
+    );
+    headers.insert("host", HeaderValue::from_static("iam.amazonaws.com"));
+    headers.insert("x-amz-date", HeaderValue::from_static("20150830T123600Z"));
+    let query = [("Version", "2010-05-08"), ("Action", "ListUsers")];
+    let auth = signer
+      .sign("GET", "/", &query, &headers, b"", "20150830T123600Z")
+      .unwrap();
+    assert_eq!(
+      auth,
+      "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20150830/us-east-1/iam/aws4_request, \
+       SignedHeaders=content-type;host;x-amz-date, \
+       Signature=5d672d79c15b13162d9279b0855cfba6789a8edb4c82c400e06b5924a6f2b5d7"
+    );
+  }
+}
@@ -0,0 +1,563 @@
+use std::{collections::HashMap, net::IpAddr, sync::Mutex};
+
+use futures_util::future::{BoxFuture, try_join_all};
+use jiff::Timestamp;
+
+use crate::{
+  acme::{AcmeChallengeType, AcmeIdentifier},
+  challenge::{
+    ChallengeSolver,
+    dns::{
+      DnsChallengeClient, DnsPersistRecord, TxtRecord, challenge_name, challenge_value,
+      persist_name, propagation::PropagationChecker, resolver::DnsResolver,
+    },
+  },
+  errors::{PlainTextSnafu, Result},
+};
+
+/// Present and clean up TXT records of DNS challenges with configured providers.
+///
+/// `_acme-challenge` name delegated by CNAME (like [acme-dns](https://github.com/joohoi/acme-dns))
+/// is followed, and the record is created at the final target, by the provider hosting the zone
+/// of target.
+///
+/// Providers added by [`Self::route`] are selected by the longest domain suffix matching the
+/// record name, others are asked in order whether they host the zone, a provider failing to
+/// answer is skipped.
+///
+/// As a [`ChallengeSolver`] of dns-01, records are kept by token until cleanup, and wildcard
+/// domain is validated at the name of its base domain, so `example.com` and `*.example.com`
+/// present two values at `_acme-challenge.example.com`.
+pub struct DnsSolver {
+  clients: Vec<Box<dyn DnsChallengeClient>>,
+  routes: Vec<(String, usize)>,
+  resolver: DnsResolver,
+  follow_cname: bool,
+  propagation: Option<PropagationChecker>,
+  presented: Mutex<HashMap<String, DnsChallengeRecord>>,
+}
+
+/// TXT record presented by [`DnsSolver::present`]
+#[derive(Debug, Clone)]
+pub struct DnsChallengeRecord {
+  pub(crate) client: usize,
+  pub record: TxtRecord,
+}
+
+impl DnsSolver {
+  /// Create solver with resolver used to follow CNAME and find zone
+  pub fn new(resolver: DnsResolver) -> Self {
+    DnsSolver {
+      clients: vec![],
+      routes: vec![],
+      resolver,
+      follow_cname: true,
+      propagation: None,
+      presented: Mutex::new(HashMap::new()),
+    }
+  }
+
+  /// Add provider, providers are asked in order whether they host the zone
+  pub fn client(mut self, client: impl DnsChallengeClient + 'static) -> Self {
+    self.clients.push(Box::new(client));
+    self
+  }
+
+  /// Add provider for names under domain `suffix`, for example, `example.com` matches
+  /// `_acme-challenge.example.com` and `_acme-challenge.www.example.com`, but not
+  /// `_acme-challenge.badexample.com`. Suffix is matched against the name where TXT record is
+  /// created, so a CNAME target should be routed by its own domain
+  pub fn route(mut self, suffix: &str, client: impl DnsChallengeClient + 'static) -> Self {
+    let suffix = suffix.trim_matches('.').to_lowercase();
+    self.routes.push((suffix, self.clients.len()));
+    self.clients.push(Box::new(client));
+    self
+  }
+
+  /// Whether to follow CNAME of `_acme-challenge` name, default is `true`
+  pub fn follow_cname(mut self, follow: bool) -> Self {
+    self.follow_cname = follow;
+    self
+  }
+
+  /// Wait for records presented as [`ChallengeSolver`] to propagate to authoritative
+  /// nameservers, default is `None` (no waiting)
+  pub fn propagation(mut self, checker: PropagationChecker) -> Self {
+    self.propagation = Some(checker);
+    self
+  }
+
+  /// Name where TXT record of `domain` should be created, CNAME is followed if enabled
+  pub async fn challenge_target(&self, domain: &str) -> Result<String> {
+    self.resolve_target(challenge_name(domain)).await
+  }
+
+  /// Create TXT record with `value` for `domain`, for example, `example.com` or `*.example.com`
+  pub async fn present(&self, domain: &str, value: &str) -> Result<DnsChallengeRecord> {
+    check_not_ip(domain)?;
+    let target = self.challenge_target(domain).await?;
+    let (index, zone) = self.provider_of(domain, &target).await?;
+    let record = self.clients[index]
+      .create_txt(&zone, &target, value)
+      .await?;
+    Ok(DnsChallengeRecord {
+      client: index,
+      record,
+    })
+  }
+
+  /// Publish persistent record of dns-persist-01 challenge for `domain`, `None` if a record of
+  /// the same issuer, account, policy and expiration is published already and not expired.
+  /// Other records of the same issuer and account are deleted, and `persist_until` in the past
+  /// is rejected. The record is kept for later renewals, so it is not necessary to clean up
+  pub async fn setup_persist(
+    &self,
+    domain: &str,
+    record: &DnsPersistRecord,
+  ) -> Result<Option<DnsChallengeRecord>> {
+    check_not_ip(domain)?;
+    let now = Timestamp::now().as_second();
+    if record.is_expired(now) {
+      return PlainTextSnafu {
+        message: format!(
+          "Error: persistent record of {} expired already at {}",
+          domain,
+          record.persist_until.unwrap_or_default()
+        ),
+      }
+      .fail();
+    }
+    let target = self.resolve_target(persist_name(domain)).await?;
+    let mut stale = vec![];
+    for value in self.resolver.lookup_txt(&target).await? {
+      let Some(published) = DnsPersistRecord::parse(&value) else {
+        continue;
+      };
+      if !published.is_same_account(record) {
+        continue;
+      }
+      if published.policy == record.policy
+        && published.persist_until == record.persist_until
+        && !published.is_expired(now)
+      {
+        return Ok(None);
+      }
+      stale.push(value);
+    }
+    let (index, zone) = self.provider_of(domain, &target).await?;
+    let client = &self.clients[index];
+    // existing record of the same value is reused by provider, which gives id to delete it
+    for value in stale {
+      let stale = client.create_txt(&zone, &target, &value).await?;
+      client.delete_txt(&stale).await?;
+    }
+    let record = client.create_txt(&zone, &target, &record.value()).await?;
+    Ok(Some(DnsChallengeRecord {
+      client: index,
+      record,
+    }))
+  }
+
+  async fn resolve_target(&self, name: String) -> Result<String> {
+    if !self.follow_cname {
+      return Ok(name);
+    }
+    self.resolver.resolve_cname(&name).await
+  }
+
+  /// Index of provider hosting `target`, and zone of `target`
+  async fn provider_of(&self, domain: &str, target: &str) -> Result<(usize, String)> {
+    let zone = self.resolver.find_zone(target).await?;
+    if let Some(index) = self.route_of(target) {
+      return Ok((index, zone));
+    }
+    // a provider failing to answer, for example, without privilege of the zone, should not stop
+    // asking the others
+    let mut errors = vec![];
+    for (index, client) in self.clients.iter().enumerate() {
+      if self.routes.iter().any(|(_, i)| *i == index) {
+        continue;
+      }
+      match client.has_zone(&zone).await {
+        Ok(true) => return Ok((index, zone)),
+        Ok(false) => {}
+        Err(err) => errors.push(err.to_string()),
+      }
+    }
+    if !self.routes.is_empty() && self.clients.len() == self.routes.len() {
+      let suffixes = self
+        .routes
+        .iter()
+        .map(|(suffix, _)| suffix.as_str())
+        .collect::<Vec<_>>();
+      return PlainTextSnafu {
+        message: format!(
+          "Error: no DNS provider routed for {} of {}, routes are: {}",
+          target,
+          domain,
+          suffixes.join(", ")
+        ),
+      }
+      .fail();
+    }
+    let mut message = format!("Error: no DNS provider hosts zone {} of {}", zone, target);
+    if !errors.is_empty() {
+      message = format!("{}, providers failed: {}", message, errors.join("; "));
+    }
+    PlainTextSnafu { message }.fail()
+  }
+
+  /// Delete TXT record created by [`Self::present`]
+  pub async fn cleanup(&self, record: &DnsChallengeRecord) -> Result<()> {
+    match self.clients.get(record.client) {
+      Some(client) => client.delete_txt(&record.record).await,
+      None => PlainTextSnafu {
+        message: format!("Error: DNS provider of {} not found", record.record.fqdn),
+      }
+      .fail(),
+    }
+  }
+}
+
+impl ChallengeSolver for DnsSolver {
+  fn challenge_type(&self) -> AcmeChallengeType {
+    AcmeChallengeType::DNS
+  }
+
+  fn present<'a>(
+    &'a self,
+    identifier: &'a AcmeIdentifier,
+    token: &'a str,
+    key_authorization: &'a str,
+  ) -> BoxFuture<'a, Result<()>> {
+    Box::pin(async move {
+      let value = challenge_value(key_authorization);
+      let record = DnsSolver::present(self, &identifier.value, &value).await?;
+      let mut presented = self.presented.lock().unwrap_or_else(|e| e.into_inner());
+      presented.insert(token.to_string(), record);
+      Ok(())
+    })
+  }
+
+  fn wait_ready<'a>(&'a self) -> BoxFuture<'a, Result<()>> {
+    Box::pin(async move {
+      let Some(checker) = &self.propagation else {
+        return Ok(());
+      };
+      let records = {
+        let presented = self.presented.lock().unwrap_or_else(|e| e.into_inner());
+        presented
+          .values()
+          .map(|r| r.record.clone())
+          .collect::<Vec<_>>()
+      };
+      let waits = records
+        .iter()
+        .map(|record| checker.wait_txt(&record.fqdn, &record.value));
+      try_join_all(waits).await?;
+      Ok(())
+    })
+  }
+
+  fn cleanup<'a>(
+    &'a self,
+    _identifier: &'a AcmeIdentifier,
+    token: &'a str,
+    _key_authorization: &'a str,
+  ) -> BoxFuture<'a, Result<()>> {
+    Box::pin(async move {
+      let record = {
+        let mut presented = self.presented.lock().unwrap_or_else(|e| e.into_inner());
+        presented.remove(token)
+      };
+      match record {
+        Some(record) => DnsSolver::cleanup(self, &record).await,
+        None => Ok(()),
+      }
+    })
+  }
+}
+
+impl DnsSolver {
+  /// Index of provider routed by the longest suffix of `name`
+  fn route_of(&self, name: &str) -> Option<usize> {
+    let name = name.trim_end_matches('.').to_lowercase();
+    self
+      .routes
+      .iter()
+      .filter(|(suffix, _)| {
+        name == *suffix
+          || name
+            .strip_suffix(suffix.as_str())
+            .is_some_and(|prefix| prefix.ends_with('.'))
+      })
+      .max_by_key(|(suffix, _)| suffix.len())
+      .map(|(_, index)| *index)
+  }
+}
+
+fn check_not_ip(domain: &str) -> Result<()> {
+  if domain.trim_matches(['[', ']']).parse::<IpAddr>().is_ok() {
+    return PlainTextSnafu {
+      message: format!(
+        "Error: DNS challenge can not validate IP address {}",
+        domain
+      ),
+    }
+    .fail();
+  }
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use std::{
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+  };
+
+  use futures_util::future::BoxFuture;
+
+  use super::*;
+  use crate::{
+    challenge::dns::{
+      DnsPersistPolicy, RecordType,
+      resolver::message::{DnsMessage, RecordData},
+    },
+    test_util::{dns_record, dns_stub_server},
+  };
+
+  struct NoopClient;
+
+  impl DnsChallengeClient for NoopClient {
+    fn has_zone<'a>(&'a self, _zone: &'a str) -> BoxFuture<'a, Result<bool>> {
+      Box::pin(async move { Ok(false) })
+    }
+
+    fn create_txt<'a>(
+      &'a self,
+      zone: &'a str,
+      fqdn: &'a str,
+      value: &'a str,
+    ) -> BoxFuture<'a, Result<TxtRecord>> {
+      Box::pin(async move { Ok(TxtRecord::new(zone, fqdn, value, "")) })
+    }
+
+    fn delete_txt<'a>(&'a self, _record: &'a TxtRecord) -> BoxFuture<'a, Result<()>> {
+      Box::pin(async move { Ok(()) })
+    }
+  }
+
+  /// Provider hosting `zone` and recording created and deleted records, asking it fails if
+  /// `zone` is `None`
+  struct StubClient {
+    zone: Option<&'static str>,
+    created: Arc<Mutex<Vec<TxtRecord>>>,
+    deleted: Arc<Mutex<Vec<TxtRecord>>>,
+  }
+
+  impl StubClient {
+    fn new(zone: Option<&'static str>) -> Self {
+      StubClient {
+        zone,
+        created: Arc::new(Mutex::new(vec![])),
+        deleted: Arc::new(Mutex::new(vec![])),
+      }
+    }
+  }
+
+  impl DnsChallengeClient for StubClient {
+    fn has_zone<'a>(&'a self, zone: &'a str) -> BoxFuture<'a, Result<bool>> {
+      Box::pin(async move {
+        match self.zone {
+          Some(hosted) => Ok(hosted == zone),
+          None => PlainTextSnafu {
+            message: "Stub Error: access denied",
+          }
+          .fail(),
+        }
+      })
+    }
+
+    fn create_txt<'a>(
+      &'a self,
+      zone: &'a str,
+      fqdn: &'a str,
+      value: &'a str,
+    ) -> BoxFuture<'a, Result<TxtRecord>> {
+      Box::pin(async move {
+        let record = TxtRecord::new(zone, fqdn, value, "id");
+        self.created.lock().unwrap().push(record.clone());
+        Ok(record)
+      })
+    }
+
+    fn delete_txt<'a>(&'a self, record: &'a TxtRecord) -> BoxFuture<'a, Result<()>> {
+      Box::pin(async move {
+        self.deleted.lock().unwrap().push(record.clone());
+        Ok(())
+      })
+    }
+  }
+
+  /// Stub recursive nameserver, `_acme-challenge.example.com` is delegated by CNAME chain to
+  /// `_acme-challenge.auth.example.org` in zone `auth.example.org`
+  async fn stub_nameserver() -> SocketAddr {
+    dns_stub_server(|mut res: DnsMessage| {
+      let question = res.questions[0].clone();
+      let cname = match question.name.as_str() {
+        "_acme-challenge.example.com" => Some("alias.example.net"),
+        "alias.example.net" => Some("_acme-challenge.auth.example.org"),
+        _ => None,
+      };
+      if let Some(target) = cname {
+        let data = RecordData::CNAME(target.to_string());
+        res
+          .answers
+          .push(dns_record(&question.name, RecordType::CNAME, data));
+      } else if question.rtype == RecordType::SOA.code() {
+        let soa = dns_record("auth.example.org", RecordType::SOA, RecordData::Raw(vec![]));
+        match question.name.as_str() {
+          "auth.example.org" => res.answers.push(soa),
+          _ => res.authorities.push(soa),
+        }
+      }
+      res
+    })
+    .await
+  }
+
+  #[test]
+  fn longest_suffix_route() {
+    let resolver = DnsResolver::new(SocketAddr::from(([127, 0, 0, 1], 53)));
+    let solver = DnsSolver::new(resolver)
+      .route("example.com", NoopClient)
+      .route("cn.example.com.", NoopClient)
+      .route("example.net", NoopClient);
+    assert_eq!(solver.route_of("_acme-challenge.example.com"), Some(0));
+    assert_eq!(
+      solver.route_of("_acme-challenge.www.CN.example.com."),
+      Some(1)
+    );
+    assert_eq!(solver.route_of("example.net"), Some(2));
+    assert_eq!(solver.route_of("_acme-challenge.badexample.com"), None);
+  }
+
+  #[tokio::test]
+  async fn follow_cname_and_skip_failed_provider() {
+    let resolver = DnsResolver::new(stub_nameserver().await);
+    let hosting = StubClient::new(Some("auth.example.org"));
+    let created = hosting.created.clone();
+    let solver = DnsSolver::new(resolver.clone())
+      .client(StubClient::new(None))
+      .client(StubClient::new(Some("example.com")))
+      .client(hosting);
+    assert_eq!(
+      solver.challenge_target("*.example.com").await.unwrap(),
+      "_acme-challenge.auth.example.org"
+    );
+
+    let record = solver.present("*.example.com", "value").await.unwrap();
+    assert_eq!(record.client, 2);
+    assert_eq!(record.record.zone, "auth.example.org");
+    assert_eq!(record.record.fqdn, "_acme-challenge.auth.example.org");
+    assert_eq!(created.lock().unwrap().len(), 1);
+
+    let solver = DnsSolver::new(resolver.clone()).follow_cname(false);
+    assert_eq!(
+      solver.challenge_target("example.com").await.unwrap(),
+      "_acme-challenge.example.com"
+    );
+
+    let solver = DnsSolver::new(resolver).client(StubClient::new(None));
+    let err = solver.present("example.com", "value").await.unwrap_err();
+    let err = err.to_string();
+    assert!(err.contains("no DNS provider hosts zone auth.example.org"));
+    assert!(err.contains("Stub Error: access denied"));
+  }
+
+  /// Stub recursive nameserver of zone `example.com`, `_validation-persist` records of subdomains
+  /// `current`, `expired` and `policy` are published with `values`
+  async fn stub_persist_nameserver(values: [String; 3]) -> SocketAddr {
+    dns_stub_server(move |mut res: DnsMessage| {
+      let question = res.questions[0].clone();
+      if question.rtype == RecordType::SOA.code() {
+        let soa = dns_record("example.com", RecordType::SOA, RecordData::Raw(vec![]));
+        match question.name.as_str() {
+          "example.com" => res.answers.push(soa),
+          _ => res.authorities.push(soa),
+        }
+      } else if question.rtype == RecordType::TXT.code() {
+        let value = match question.name.as_str() {
+          "_validation-persist.current.example.com" => Some(&values[0]),
+          "_validation-persist.expired.example.com" => Some(&values[1]),
+          "_validation-persist.policy.example.com" => Some(&values[2]),
+          _ => None,
+        };
+        if let Some(value) = value {
+          let data = RecordData::TXT(vec![value.clone()]);
+          res
+            .answers
+            .push(dns_record(&question.name, RecordType::TXT, data));
+        }
+      }
+      res
+    })
+    .await
+  }
+
+  #[tokio::test]
+  async fn setup_persist_unless_published() {
+    let account = DnsPersistRecord::new("ca.example", "https://ca.example/acct/1");
+    let wanted = account
+      .clone()
+      .policy(DnsPersistPolicy::Wildcard)
+      .persist_until(4102444800);
+    let expired = wanted.clone().persist_until(1);
+    let values = [wanted.value(), expired.value(), account.value()];
+    let resolver = DnsResolver::new(stub_persist_nameserver(values).await);
+    let client = StubClient::new(Some("example.com"));
+    let created = client.created.clone();
+    let deleted = client.deleted.clone();
+    let solver = DnsSolver::new(resolver).client(client);
+
+    let published = solver.setup_persist("current.example.com", &wanted).await;
+    assert!(published.unwrap().is_none());
+
+    let record = solver.setup_persist("expired.example.com", &wanted).await;
+    let record = record.unwrap().unwrap();
+    assert_eq!(
+      record.record.fqdn,
+      "_validation-persist.expired.example.com"
+    );
+    assert_eq!(record.record.value, wanted.value());
+
+    let record = solver.setup_persist("*.policy.example.com", &wanted).await;
+    let record = record.unwrap().unwrap();
+    assert_eq!(record.record.fqdn, "_validation-persist.policy.example.com");
+    assert_eq!(record.record.value, wanted.value());
+
+    let record = solver.setup_persist("new.example.com", &wanted).await;
+    let record = record.unwrap().unwrap();
+    assert_eq!(record.record.fqdn, "_validation-persist.new.example.com");
+    let err = solver.setup_persist("new.example.com", &expired).await;
+    assert!(err.unwrap_err().to_string().contains("expired already"));
+
+    // stale records of the account are deleted before the new one is created
+    let deleted = deleted.lock().unwrap();
+    let deleted = deleted.iter().map(|r| r.value.as_str()).collect::<Vec<_>>();
+    assert_eq!(deleted, [expired.value(), account.value()]);
+    let created = created.lock().unwrap();
+    let created = created.iter().map(|r| r.value.as_str()).collect::<Vec<_>>();
+    assert_eq!(
+      created,
+      [
+        expired.value(),
+        wanted.value(),
+        account.value(),
+        wanted.value(),
+        wanted.value()
+      ]
+    );
+  }
+}
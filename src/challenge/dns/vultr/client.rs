@@ -0,0 +1,266 @@
+use futures_util::future::BoxFuture;
+use http::{HeaderMap, Method, header::AUTHORIZATION};
+use reqwest::{Client, ClientBuilder};
+use serde::{Serialize, de::DeserializeOwned};
+use snafu::ResultExt;
+
+use crate::{
+  challenge::dns::{
+    DnsChallengeClient, TxtRecord, relative_name,
+    vultr::{
+      VultrCreateRecordReq, VultrDeleteRecordReq, VultrDomain, VultrOption, VultrRecord,
+      response::{DomainData, RecordData, RecordListData, VultrRes},
+    },
+  },
+  errors::{PlainTextSnafu, ReqwestClientSnafu, Result},
+  util::str_to_header_value,
+};
+
+const API_ENDPOINT: &str = "https://api.vultr.com/v2";
+
+/// Client for Vultr DNS api
+#[derive(Debug)]
+pub struct VultrClient {
+  client: Client,
+  endpoint: String,
+}
+
+impl VultrClient {
+  /// Create client with option, see [`VultrOption`]
+  pub fn new(option: VultrOption) -> Result<Self> {
+    let VultrOption {
+      api_key,
+      proxy,
+      timeout,
+      endpoint,
+    } = option;
+    let mut client = ClientBuilder::new();
+    if let Some(timeout) = timeout {
+      client = client.timeout(timeout);
+    }
+    if let Some(proxy) = proxy {
+      let proxy = reqwest::Proxy::all(proxy).context(ReqwestClientSnafu)?;
+      client = client.proxy(proxy);
+    }
+    let mut headers = HeaderMap::new();
+    headers.insert(
+      AUTHORIZATION,
+      str_to_header_value(format!("Bearer {}", api_key))?,
+    );
+    client = client.default_headers(headers);
+    let client = client.build().context(ReqwestClientSnafu)?;
+    Ok(Self {
+      client,
+      endpoint: endpoint.unwrap_or_else(|| API_ENDPOINT.to_string()),
+    })
+  }
+
+  /// Create client from environment variable, see [`VultrOption::new_from_env`]
+  pub fn new_from_env() -> Result<Self> {
+    Self::new(VultrOption::new_from_env()?)
+  }
+
+  /// Create DNS TXT record
+  ///
+  /// `return`: record id or error
+  pub async fn create_record(&self, req: VultrCreateRecordReq<'_>) -> Result<String> {
+    let url = format!("{}/domains/{}/records", self.endpoint, req.domain);
+    let data: RecordData = self.exec_request(Method::POST, &url, Some(&req)).await?;
+    Ok(data.record.id)
+  }
+
+  /// Delete DNS TXT record
+  pub async fn delete_record(&self, req: VultrDeleteRecordReq<'_>) -> Result<()> {
+    let url = format!(
+      "{}/domains/{}/records/{}",
+      self.endpoint, req.domain, req.record_id
+    );
+    self.exec_request(Method::DELETE, &url, None::<&()>).await
+  }
+
+  /// List all DNS records of domain
+  pub async fn list_records(&self, domain: &str) -> Result<Vec<VultrRecord>> {
+    let url = format!("{}/domains/{}/records", self.endpoint, domain);
+    let mut records = vec![];
+    let mut cursor = String::new();
+    loop {
+      let query = [("per_page", "500"), ("cursor", cursor.as_str())];
+      let data: RecordListData = self.exec_request(Method::GET, &url, Some(&query)).await?;
+      records.extend(data.records);
+      match data.meta.and_then(|m| m.links) {
+        Some(links) if !links.next.is_empty() => cursor = links.next,
+        _ => return Ok(records),
+      }
+    }
+  }
+
+  /// Append value to the TXT records of `req`'s name, the existing record is reused if it has the
+  /// same value
+  pub async fn append_record(&self, req: VultrCreateRecordReq<'_>) -> Result<String> {
+    let records = self.list_records(req.domain).await?;
+    let existed = records
+      .into_iter()
+      .find(|r| r.rtype == "TXT" && r.name.eq_ignore_ascii_case(req.name) && r.data == req.data);
+    if let Some(record) = existed {
+      return Ok(record.id);
+    }
+    self.create_record(req).await
+  }
+
+  /// Get domain, `None` if it is not in the account
+  pub async fn domain(&self, domain: &str) -> Result<Option<VultrDomain>> {
+    let url = format!("{}/domains/{}", self.endpoint, domain);
+    let res = self
+      .send_request::<DomainData>(self.client.get(url))
+      .await?;
+    match res {
+      VultrRes::Failure(failure) if failure.status == 404 => Ok(None),
+      res => Ok(Some(res.unwrap_data()?.domain)),
+    }
+  }
+}
+
+impl DnsChallengeClient for VultrClient {
+  fn has_zone<'a>(&'a self, zone: &'a str) -> BoxFuture<'a, Result<bool>> {
+    Box::pin(async move { Ok(self.domain(zone).await?.is_some()) })
+  }
+
+  fn create_txt<'a>(
+    &'a self,
+    zone: &'a str,
+    fqdn: &'a str,
+    value: &'a str,
+  ) -> BoxFuture<'a, Result<TxtRecord>> {
+    Box::pin(async move {
+      let name = match relative_name(fqdn, zone) {
+        "@" => "",
+        name => name,
+      };
+      let req = VultrCreateRecordReq::new(zone, name, value).ttl(120);
+      let record_id = self.append_record(req).await?;
+      Ok(TxtRecord::new(zone, fqdn, value, record_id))
+    })
+  }
+
+  fn delete_txt<'a>(&'a self, record: &'a TxtRecord) -> BoxFuture<'a, Result<()>> {
+    Box::pin(async move {
+      self
+        .delete_record(VultrDeleteRecordReq::new(&record.zone, &record.id))
+        .await
+    })
+  }
+}
+
+impl VultrClient {
+  async fn exec_request<R>(
+    &self,
+    method: Method,
+    url: &str,
+    req: Option<&impl Serialize>,
+  ) -> Result<R>
+  where
+    R: DeserializeOwned,
+  {
+    let mut builder = self.client.request(method.clone(), url);
+    if let Some(req) = req {
+      builder = if method == Method::GET {
+        builder.query(req)
+      } else {
+        builder.json(req)
+      };
+    }
+    self.send_request(builder).await?.unwrap_data()
+  }
+
+  async fn send_request<R>(&self, builder: reqwest::RequestBuilder) -> Result<VultrRes<R>>
+  where
+    R: DeserializeOwned,
+  {
+    let text = builder
+      .send()
+      .await
+      .context(ReqwestClientSnafu)?
+      .text()
+      .await
+      .context(ReqwestClientSnafu)?;
+    // delete api responds with 204 and empty body
+    let text = if text.is_empty() { "null" } else { &text };
+    match serde_json::from_str(text) {
+      Ok(res) => Ok(res),
+      Err(_) => PlainTextSnafu {
+        message: format!("Vultr Error: unexpected response: {}", text),
+      }
+      .fail(),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::sync::{Arc, Mutex};
+
+  use super::*;
+  use crate::test_util::{StubResponse, request_body, stub_server};
+
+  /// Stub Vultr api, `example.com` has TXT record `1` with value `old` at `_acme-challenge`, the
+  /// records are listed in two pages, request lines and bodies are recorded
+  async fn stub_vultr(requests: Arc<Mutex<Vec<String>>>) -> String {
+    stub_server(move |req| {
+      assert!(req.contains("authorization: Bearer key"));
+      let line = req.lines().next().unwrap();
+      let line = line.strip_suffix(" HTTP/1.1").unwrap();
+      requests
+        .lock()
+        .unwrap()
+        .push(format!("{} {}", line, request_body(req)));
+      let (method, path) = line.split_once(' ').unwrap();
+      let (path, query) = path.split_once('?').unwrap_or((path, ""));
+      match (method, path) {
+        ("GET", "/domains/example.com") => {
+          StubResponse::ok(r#"{"domain":{"domain":"example.com","date_created":"2020-10-10T01:56:20+00:00"}}"#)
+        }
+        ("GET", "/domains/example.com/records") if query.contains("cursor=next") => StubResponse::ok(
+          r#"{"records":[{"id":"1","type":"TXT","name":"_acme-challenge","data":"\"old\"","ttl":120}],"meta":{"total":2,"links":{"next":"","prev":"prev"}}}"#,
+        ),
+        ("GET", "/domains/example.com/records") => StubResponse::ok(
+          r#"{"records":[{"id":"3","type":"A","name":"","data":"192.0.2.1","ttl":300}],"meta":{"total":2,"links":{"next":"next","prev":""}}}"#,
+        ),
+        ("POST", "/domains/example.com/records") => StubResponse::new(
+          "201 Created",
+          r#"{"record":{"id":"2","type":"TXT","name":"_acme-challenge","data":"\"new\"","ttl":120}}"#,
+        ),
+        ("DELETE", "/domains/example.com/records/2") => StubResponse::new("204 No Content", ""),
+        _ => StubResponse::new("404 Not Found", r#"{"error":"Not Found","status":404}"#),
+      }
+    })
+    .await
+  }
+
+  #[tokio::test]
+  async fn create_and_delete_txt() {
+    let requests = Arc::new(Mutex::new(vec![]));
+    let endpoint = stub_vultr(requests.clone()).await;
+    let client = VultrClient::new(VultrOption::new("key").endpoint(endpoint)).unwrap();
+    assert!(client.has_zone("example.com").await.unwrap());
+    assert!(!client.has_zone("example.org").await.unwrap());
+
+    let existed = client
+      .create_txt("example.com", "_acme-challenge.example.com", "old")
+      .await
+      .unwrap();
+    assert_eq!(existed.id, "1");
+    let record = client
+      .create_txt("example.com", "_acme-challenge.example.com", "new")
+      .await
+      .unwrap();
+    assert_eq!(record.id, "2");
+    client.delete_txt(&record).await.unwrap();
+
+    let requests = requests.lock().unwrap();
+    assert_eq!(requests.len(), 8);
+    assert!(requests[6].starts_with("POST /domains/example.com/records "));
+    assert!(requests[6].contains(r#""name":"_acme-challenge""#));
+    assert!(requests[6].contains(r#""data":"\"new\"""#));
+    assert!(requests[7].starts_with("DELETE /domains/example.com/records/2 "));
+  }
+}
@@ -0,0 +1,15 @@
+//! Implement of [Vultr](https://www.vultr.com/) DNS challenge
+//!
+//! Link: <https://www.vultr.com/api/#tag/dns>
+
+mod client;
+pub use client::VultrClient;
+
+mod response;
+pub use response::{VultrDomain, VultrRecord};
+
+mod request;
+pub use request::{VultrCreateRecordReq, VultrDeleteRecordReq};
+
+mod option;
+pub use option::VultrOption;
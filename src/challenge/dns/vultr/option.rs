@@ -0,0 +1,55 @@
+use std::time::Duration;
+
+use crate::{errors::Result, util::env_single_var};
+
+/// Options for create a [`crate::challenge::dns::vultr::VultrClient`] instance
+#[derive(Debug)]
+pub struct VultrOption {
+  pub(crate) api_key: String,
+  pub(crate) proxy: Option<String>,
+  pub(crate) timeout: Option<Duration>,
+  pub(crate) endpoint: Option<String>,
+}
+
+impl VultrOption {
+  /// Create option with Vultr api key, access control of this key must allow the client ip
+  pub fn new(api_key: impl Into<String>) -> Self {
+    VultrOption {
+      api_key: api_key.into(),
+      proxy: None,
+      timeout: None,
+      endpoint: None,
+    }
+  }
+
+  /// Create option from environment variable, variable name for api key is __VULTR_API_KEY__ or
+  /// __EASY_ACME_VULTR_API_KEY__
+  pub fn new_from_env() -> Result<Self> {
+    Ok(Self::new(Self::env_api_key()?))
+  }
+
+  /// Set proxy, for example, `https://127.0.0.1:8080`, `socks5://127.0.0.1:9000`, default is `None`
+  pub fn proxy(mut self, proxy: impl Into<String>) -> Self {
+    self.proxy = Some(proxy.into());
+    self
+  }
+
+  /// Set timeout, for example, `Duration::from_secs(5)`, default is `None`
+  pub fn timeout(mut self, timeout: Duration) -> Self {
+    self.timeout = Some(timeout);
+    self
+  }
+
+  /// Set api endpoint, default is `https://api.vultr.com/v2`
+  pub fn endpoint(mut self, endpoint: impl Into<String>) -> Self {
+    self.endpoint = Some(endpoint.into());
+    self
+  }
+}
+
+impl VultrOption {
+  #[inline]
+  pub(crate) fn env_api_key() -> Result<String> {
+    env_single_var(["VULTR_API_KEY", "EASY_ACME_VULTR_API_KEY"])
+  }
+}
@@ -0,0 +1,51 @@
+use serde::Serialize;
+
+use crate::challenge::dns::RecordType;
+
+#[derive(Debug, Serialize)]
+pub struct VultrCreateRecordReq<'a> {
+  #[serde(skip_serializing)]
+  pub(crate) domain: &'a str,
+
+  pub(crate) name: &'a str,
+
+  /// Quoted value, Vultr requires TXT data in quotes
+  pub(crate) data: String,
+
+  #[serde(skip_serializing_if = "Option::is_none")]
+  ttl: Option<u64>,
+
+  #[serde(rename = "type")]
+  rtype: RecordType,
+}
+
+impl<'a> VultrCreateRecordReq<'a> {
+  /// `name` is relative to `domain`, for example, `_acme-challenge`, empty for the domain itself
+  pub fn new(domain: &'a str, name: &'a str, value: &str) -> Self {
+    Self {
+      domain,
+      name,
+      data: format!("\"{}\"", value.trim_matches('"')),
+      ttl: None,
+      rtype: RecordType::TXT,
+    }
+  }
+
+  pub fn ttl(mut self, ttl: u64) -> Self {
+    self.ttl = Some(ttl);
+    self
+  }
+}
+
+#[derive(Debug)]
+pub struct VultrDeleteRecordReq<'a> {
+  pub(crate) domain: &'a str,
+
+  pub(crate) record_id: &'a str,
+}
+
+impl<'a> VultrDeleteRecordReq<'a> {
+  pub fn new(domain: &'a str, record_id: &'a str) -> Self {
+    Self { domain, record_id }
+  }
+}
@@ -0,0 +1,84 @@
+use serde::Deserialize;
+
+use crate::errors::{PlainTextSnafu, Result};
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub(crate) enum VultrRes<T> {
+  Failure(FailureData),
+  Success(T),
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct RecordData {
+  pub(crate) record: VultrRecord,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct RecordListData {
+  #[serde(default)]
+  pub(crate) records: Vec<VultrRecord>,
+
+  pub(crate) meta: Option<MetaData>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct MetaData {
+  pub(crate) links: Option<LinksData>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct LinksData {
+  #[serde(default)]
+  pub(crate) next: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct DomainData {
+  pub(crate) domain: VultrDomain,
+}
+
+/// DNS record returned by [`crate::challenge::dns::vultr::VultrClient::list_records`]
+#[derive(Debug, Clone, Deserialize)]
+pub struct VultrRecord {
+  pub id: String,
+
+  /// Name relative to domain, for example, `_acme-challenge`, empty for the domain itself
+  pub name: String,
+
+  /// Quoted value of TXT record
+  pub data: String,
+
+  #[serde(rename = "type")]
+  pub rtype: String,
+
+  pub ttl: Option<u64>,
+}
+
+/// Domain returned by [`crate::challenge::dns::vultr::VultrClient::domain`]
+#[derive(Debug, Clone, Deserialize)]
+pub struct VultrDomain {
+  pub domain: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct FailureData {
+  error: String,
+
+  pub(crate) status: u16,
+}
+
+impl<T> VultrRes<T> {
+  pub fn unwrap_data(self) -> Result<T> {
+    match self {
+      VultrRes::Success(data) => Ok(data),
+      VultrRes::Failure(failure) => PlainTextSnafu {
+        message: format!(
+          "Vultr Error: status: {}, message: {}",
+          failure.status, failure.error
+        ),
+      }
+      .fail(),
+    }
+  }
+}
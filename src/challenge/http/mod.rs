@@ -1 +1,30 @@
+//! Implement of [HTTP-01](https://www.rfc-editor.org/rfc/rfc8555#section-8.3) challenge
+//!
+//! The CA fetches `http://{domain}/.well-known/acme-challenge/{token}` on port 80, and expects
+//! key authorization of the token in response body.
 
+mod server;
+pub use server::HttpChallengeServer;
+
+#[cfg(feature = "tower")]
+mod service;
+#[cfg(feature = "tower")]
+pub use service::{HttpChallengeLayer, HttpChallengeService};
+
+mod store;
+pub use store::HttpTokenStore;
+
+mod webroot;
+pub use webroot::{HttpWebrootFile, HttpWebrootSolver};
+
+/// Path prefix of challenge requests
+pub const CHALLENGE_PATH: &str = "/.well-known/acme-challenge/";
+
+/// Whether `token` is a valid challenge token, only base64url characters are allowed, so it is
+/// safe to be used as file name and url path
+pub fn is_valid_token(token: &str) -> bool {
+  !token.is_empty()
+    && token
+      .bytes()
+      .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_')
+}
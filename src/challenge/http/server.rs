@@ -0,0 +1,179 @@
+use std::{net::SocketAddr, time::Duration};
+
+use futures_util::future::BoxFuture;
+use snafu::ResultExt;
+use tokio::{
+  io::{AsyncReadExt, AsyncWriteExt},
+  net::{TcpListener, TcpStream},
+  task::JoinHandle,
+};
+
+use crate::{
+  acme::{AcmeChallengeType, AcmeIdentifier},
+  challenge::{ChallengeSolver, http::HttpTokenStore},
+  errors::{IoOperationSnafu, Result},
+};
+
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+/// Pause after failed accept, errors such as running out of file descriptors last until some
+/// connections are closed
+const ACCEPT_BACKOFF: Duration = Duration::from_millis(100);
+const MAX_REQUEST_SIZE: usize = 8192;
+
+/// Embedded http server answers challenge requests with key authorizations in store, other
+/// requests are answered with `404`
+#[derive(Debug)]
+pub struct HttpChallengeServer {
+  addr: SocketAddr,
+  store: HttpTokenStore,
+  task: JoinHandle<()>,
+}
+
+impl HttpChallengeServer {
+  /// Bind `addr` and start serving, for example, `0.0.0.0:80`, port `0` binds a random port
+  pub async fn bind(addr: SocketAddr, store: HttpTokenStore) -> Result<Self> {
+    let listener = TcpListener::bind(addr).await.context(IoOperationSnafu)?;
+    let addr = listener.local_addr().context(IoOperationSnafu)?;
+    let task = tokio::spawn(serve(listener, store.clone()));
+    Ok(HttpChallengeServer { addr, store, task })
+  }
+
+  /// Address the server is listening on
+  pub fn local_addr(&self) -> SocketAddr {
+    self.addr
+  }
+
+  /// Store of the server
+  pub fn store(&self) -> &HttpTokenStore {
+    &self.store
+  }
+
+  /// Serve `key_authorization` for `token`
+  pub fn present(&self, token: impl Into<String>, key_authorization: impl Into<String>) {
+    self.store.insert(token, key_authorization);
+  }
+
+  /// Stop serving `token`
+  pub fn cleanup(&self, token: &str) {
+    self.store.remove(token);
+  }
+
+  /// Stop accepting connections and release the address, in-flight requests are dropped
+  pub async fn shutdown(mut self) {
+    self.task.abort();
+    let _ = (&mut self.task).await;
+  }
+}
+
+impl ChallengeSolver for HttpChallengeServer {
+  fn challenge_type(&self) -> AcmeChallengeType {
+    AcmeChallengeType::HTTP
+  }
+
+  fn present<'a>(
+    &'a self,
+    identifier: &'a AcmeIdentifier,
+    token: &'a str,
+    key_authorization: &'a str,
+  ) -> BoxFuture<'a, Result<()>> {
+    ChallengeSolver::present(&self.store, identifier, token, key_authorization)
+  }
+
+  fn cleanup<'a>(
+    &'a self,
+    identifier: &'a AcmeIdentifier,
+    token: &'a str,
+    key_authorization: &'a str,
+  ) -> BoxFuture<'a, Result<()>> {
+    ChallengeSolver::cleanup(&self.store, identifier, token, key_authorization)
+  }
+}
+
+impl Drop for HttpChallengeServer {
+  fn drop(&mut self) {
+    self.task.abort();
+  }
+}
+
+async fn serve(listener: TcpListener, store: HttpTokenStore) {
+  loop {
+    let Ok((stream, _)) = listener.accept().await else {
+      tokio::time::sleep(ACCEPT_BACKOFF).await;
+      continue;
+    };
+    let store = store.clone();
+    tokio::spawn(async move {
+      let _ = tokio::time::timeout(READ_TIMEOUT, handle(stream, store)).await;
+    });
+  }
+}
+
+async fn handle(mut stream: TcpStream, store: HttpTokenStore) -> std::io::Result<()> {
+  let mut req = vec![];
+  let mut buf = [0u8; 1024];
+  while !req.windows(4).any(|w| w == b"\r\n\r\n") {
+    let len = stream.read(&mut buf).await?;
+    if len == 0 || req.len() + len > MAX_REQUEST_SIZE {
+      return Ok(());
+    }
+    req.extend_from_slice(&buf[..len]);
+  }
+  let req = String::from_utf8_lossy(&req);
+  let mut parts = req.lines().next().unwrap_or_default().split(' ');
+  let (method, path) = (
+    parts.next().unwrap_or_default(),
+    parts.next().unwrap_or_default(),
+  );
+  let key_authorization = match method {
+    "GET" | "HEAD" => store.get_by_path(path),
+    _ => None,
+  };
+  let (status, body) = match key_authorization {
+    Some(key_authorization) => ("200 OK", key_authorization),
+    None => ("404 Not Found", String::new()),
+  };
+  let mut res = format!(
+    "HTTP/1.1 {}\r\ncontent-type: text/plain\r\ncontent-length: {}\r\nconnection: close\r\n\r\n",
+    status,
+    body.len()
+  );
+  if method != "HEAD" {
+    res.push_str(&body);
+  }
+  stream.write_all(res.as_bytes()).await?;
+  stream.shutdown().await
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  async fn get(addr: SocketAddr, path: &str) -> String {
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+    let req = format!("GET {} HTTP/1.1\r\nhost: example.com\r\n\r\n", path);
+    stream.write_all(req.as_bytes()).await.unwrap();
+    let mut res = String::new();
+    stream.read_to_string(&mut res).await.unwrap();
+    res
+  }
+
+  #[tokio::test]
+  async fn serve_and_shutdown() {
+    let addr = SocketAddr::from(([127, 0, 0, 1], 0));
+    let server = HttpChallengeServer::bind(addr, HttpTokenStore::new())
+      .await
+      .unwrap();
+    let addr = server.local_addr();
+    server.present("token", "token.thumbprint");
+    let res = get(addr, "/.well-known/acme-challenge/token").await;
+    assert!(res.starts_with("HTTP/1.1 200"));
+    assert!(res.ends_with("\r\n\r\ntoken.thumbprint"));
+
+    server.cleanup("token");
+    let res = get(addr, "/.well-known/acme-challenge/token").await;
+    assert!(res.starts_with("HTTP/1.1 404"));
+
+    server.shutdown().await;
+    assert!(TcpListener::bind(addr).await.is_ok());
+  }
+}
@@ -0,0 +1,120 @@
+use std::task::{Context, Poll};
+
+use futures_util::future::{Either, Ready, ready};
+use http::{HeaderValue, Method, Request, Response, StatusCode, header::CONTENT_TYPE};
+use tower_layer::Layer;
+use tower_service::Service;
+
+use crate::challenge::http::{CHALLENGE_PATH, HttpTokenStore};
+
+/// Layer wraps service with [`HttpChallengeService`]
+#[derive(Debug, Clone)]
+pub struct HttpChallengeLayer {
+  store: HttpTokenStore,
+}
+
+impl HttpChallengeLayer {
+  /// Create layer answering challenges with key authorizations in `store`
+  pub fn new(store: HttpTokenStore) -> Self {
+    HttpChallengeLayer { store }
+  }
+}
+
+impl<S> Layer<S> for HttpChallengeLayer {
+  type Service = HttpChallengeService<S>;
+
+  fn layer(&self, inner: S) -> Self::Service {
+    HttpChallengeService {
+      inner,
+      store: self.store.clone(),
+    }
+  }
+}
+
+/// Service answers `GET /.well-known/acme-challenge/{token}` from store, unknown token is
+/// answered with `404`, and other requests are passed to inner service
+#[derive(Debug, Clone)]
+pub struct HttpChallengeService<S> {
+  inner: S,
+  store: HttpTokenStore,
+}
+
+impl<S, ReqBody, ResBody> Service<Request<ReqBody>> for HttpChallengeService<S>
+where
+  S: Service<Request<ReqBody>, Response = Response<ResBody>>,
+  ResBody: From<String>,
+{
+  type Response = Response<ResBody>;
+  type Error = S::Error;
+  type Future = Either<Ready<Result<Response<ResBody>, S::Error>>, S::Future>;
+
+  fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+    self.inner.poll_ready(cx)
+  }
+
+  fn call(&mut self, req: Request<ReqBody>) -> Self::Future {
+    let path = req.uri().path();
+    let is_challenge = (req.method() == Method::GET || req.method() == Method::HEAD)
+      && path.starts_with(CHALLENGE_PATH);
+    if !is_challenge {
+      return Either::Right(self.inner.call(req));
+    }
+    let (status, body) = match self.store.get_by_path(path) {
+      Some(key_authorization) => (StatusCode::OK, key_authorization),
+      None => (StatusCode::NOT_FOUND, String::new()),
+    };
+    let mut res = Response::new(ResBody::from(body));
+    *res.status_mut() = status;
+    res
+      .headers_mut()
+      .insert(CONTENT_TYPE, HeaderValue::from_static("text/plain"));
+    Either::Left(ready(Ok(res)))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::convert::Infallible;
+
+  use super::*;
+
+  /// Inner service answers every request with `inner`
+  #[derive(Clone)]
+  struct InnerService;
+
+  impl Service<Request<()>> for InnerService {
+    type Response = Response<String>;
+    type Error = Infallible;
+    type Future = Ready<Result<Response<String>, Infallible>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Infallible>> {
+      Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, _req: Request<()>) -> Self::Future {
+      ready(Ok(Response::new("inner".to_string())))
+    }
+  }
+
+  async fn get(service: &mut HttpChallengeService<InnerService>, path: &str) -> Response<String> {
+    let req = Request::get(path).body(()).unwrap();
+    service.call(req).await.unwrap()
+  }
+
+  #[tokio::test]
+  async fn answer_challenge_or_pass_through() {
+    let store = HttpTokenStore::new();
+    let mut service = HttpChallengeLayer::new(store.clone()).layer(InnerService);
+    store.insert("token", "token.thumbprint");
+
+    let res = get(&mut service, "/.well-known/acme-challenge/token").await;
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(res.body(), "token.thumbprint");
+
+    let res = get(&mut service, "/.well-known/acme-challenge/unknown").await;
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+
+    let res = get(&mut service, "/index.html").await;
+    assert_eq!(res.body(), "inner");
+  }
+}
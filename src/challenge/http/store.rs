@@ -0,0 +1,85 @@
+use std::{
+  collections::HashMap,
+  sync::{Arc, RwLock},
+};
+
+use futures_util::future::BoxFuture;
+
+use crate::{
+  acme::{AcmeChallengeType, AcmeIdentifier},
+  challenge::{ChallengeSolver, http::CHALLENGE_PATH},
+  errors::Result,
+};
+
+/// Key authorizations of pending challenges, cloned stores share the same tokens, so it can be
+/// filled by solver and read by server concurrently
+#[derive(Debug, Clone, Default)]
+pub struct HttpTokenStore {
+  tokens: Arc<RwLock<HashMap<String, String>>>,
+}
+
+impl HttpTokenStore {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Serve `key_authorization` for `token` until [`Self::remove`]
+  pub fn insert(&self, token: impl Into<String>, key_authorization: impl Into<String>) {
+    let mut tokens = self.tokens.write().unwrap_or_else(|e| e.into_inner());
+    tokens.insert(token.into(), key_authorization.into());
+  }
+
+  pub fn remove(&self, token: &str) -> Option<String> {
+    let mut tokens = self.tokens.write().unwrap_or_else(|e| e.into_inner());
+    tokens.remove(token)
+  }
+
+  /// Key authorization of `token`
+  pub fn get(&self, token: &str) -> Option<String> {
+    let tokens = self.tokens.read().unwrap_or_else(|e| e.into_inner());
+    tokens.get(token).cloned()
+  }
+
+  /// Key authorization of request `path`, for example, `/.well-known/acme-challenge/{token}`
+  pub fn get_by_path(&self, path: &str) -> Option<String> {
+    let path = path.split('?').next().unwrap_or_default();
+    self.get(path.strip_prefix(CHALLENGE_PATH)?)
+  }
+
+  pub fn is_empty(&self) -> bool {
+    let tokens = self.tokens.read().unwrap_or_else(|e| e.into_inner());
+    tokens.is_empty()
+  }
+}
+
+/// Serve key authorizations by [`HttpChallengeServer`](crate::challenge::http::HttpChallengeServer)
+/// or [`HttpChallengeService`](crate::challenge::http::HttpChallengeService) sharing this store
+impl ChallengeSolver for HttpTokenStore {
+  fn challenge_type(&self) -> AcmeChallengeType {
+    AcmeChallengeType::HTTP
+  }
+
+  fn present<'a>(
+    &'a self,
+    _identifier: &'a AcmeIdentifier,
+    token: &'a str,
+    key_authorization: &'a str,
+  ) -> BoxFuture<'a, Result<()>> {
+    Box::pin(async move {
+      self.insert(token, key_authorization);
+      Ok(())
+    })
+  }
+
+  fn cleanup<'a>(
+    &'a self,
+    _identifier: &'a AcmeIdentifier,
+    token: &'a str,
+    _key_authorization: &'a str,
+  ) -> BoxFuture<'a, Result<()>> {
+    Box::pin(async move {
+      self.remove(token);
+      Ok(())
+    })
+  }
+}
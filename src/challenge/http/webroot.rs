@@ -0,0 +1,269 @@
+use std::{
+  collections::HashMap,
+  fs::OpenOptions,
+  io::Write,
+  path::{Path, PathBuf},
+  sync::Mutex,
+  time::Duration,
+};
+
+use futures_util::future::BoxFuture;
+use http::header::HOST;
+use reqwest::{Client, ClientBuilder};
+use snafu::ResultExt;
+
+use crate::{
+  acme::{AcmeChallengeType, AcmeIdentifier},
+  challenge::{
+    ChallengeSolver,
+    http::{CHALLENGE_PATH, is_valid_token},
+  },
+  errors::{IoOperationSnafu, PlainTextSnafu, ReqwestClientSnafu, Result},
+  util::str_to_header_value,
+};
+
+/// Solve challenge by writing key authorization into webroot of a running http server, for
+/// example, nginx with `root /var/www/html`
+#[derive(Debug)]
+pub struct HttpWebrootSolver {
+  webroot: PathBuf,
+  verify_url: Option<String>,
+  client: Client,
+  files: Mutex<HashMap<String, HttpWebrootFile>>,
+}
+
+/// Challenge file written by [`HttpWebrootSolver::present`], it is removed when dropped
+#[derive(Debug)]
+pub struct HttpWebrootFile {
+  path: PathBuf,
+}
+
+impl HttpWebrootSolver {
+  /// Create solver with webroot directory, challenge files are written into
+  /// `<webroot>/.well-known/acme-challenge/`
+  pub fn new(webroot: impl Into<PathBuf>) -> Result<Self> {
+    let client = ClientBuilder::new()
+      .timeout(Duration::from_secs(5))
+      .build()
+      .context(ReqwestClientSnafu)?;
+    Ok(HttpWebrootSolver {
+      webroot: webroot.into(),
+      verify_url: Some("http://127.0.0.1".to_string()),
+      client,
+      files: Mutex::new(HashMap::new()),
+    })
+  }
+
+  /// Set base url of local http server to verify challenge file is reachable, requested with
+  /// `Host` header of the domain, `None` disables verification, default is `http://127.0.0.1`
+  pub fn verify_url(mut self, url: Option<impl Into<String>>) -> Self {
+    self.verify_url = url.map(|u| u.into().trim_end_matches('/').to_string());
+    self
+  }
+
+  /// Write `key_authorization` of `token` for `domain`, and verify it is reachable if enabled,
+  /// the file is removed if verification fails
+  pub async fn present(
+    &self,
+    domain: &str,
+    token: &str,
+    key_authorization: &str,
+  ) -> Result<HttpWebrootFile> {
+    if !is_valid_token(token) {
+      return PlainTextSnafu {
+        message: format!("HTTP Error: invalid challenge token {}", token),
+      }
+      .fail();
+    }
+    let dir = self.webroot.join(CHALLENGE_PATH.trim_matches('/'));
+    std::fs::create_dir_all(&dir).context(IoOperationSnafu)?;
+    let file = HttpWebrootFile {
+      path: dir.join(token),
+    };
+    write_file(&file.path, key_authorization)?;
+    if let Some(url) = &self.verify_url {
+      // file is removed by drop of guard on error
+      self.verify(url, domain, token, key_authorization).await?;
+    }
+    Ok(file)
+  }
+
+  async fn verify(
+    &self,
+    url: &str,
+    domain: &str,
+    token: &str,
+    key_authorization: &str,
+  ) -> Result<()> {
+    let url = format!("{}{}{}", url, CHALLENGE_PATH, token);
+    let res = self
+      .client
+      .get(&url)
+      .header(HOST, str_to_header_value(domain)?)
+      .send()
+      .await
+      .context(ReqwestClientSnafu)?;
+    let status = res.status();
+    let body = res.text().await.context(ReqwestClientSnafu)?;
+    if status.is_success() && body.trim() == key_authorization {
+      return Ok(());
+    }
+    PlainTextSnafu {
+      message: format!(
+        "HTTP Error: challenge file of {} is not reachable at {}, status: {}",
+        domain, url, status
+      ),
+    }
+    .fail()
+  }
+}
+
+/// Challenge files are kept by solver until [`ChallengeSolver::cleanup`]
+impl ChallengeSolver for HttpWebrootSolver {
+  fn challenge_type(&self) -> AcmeChallengeType {
+    AcmeChallengeType::HTTP
+  }
+
+  fn present<'a>(
+    &'a self,
+    identifier: &'a AcmeIdentifier,
+    token: &'a str,
+    key_authorization: &'a str,
+  ) -> BoxFuture<'a, Result<()>> {
+    Box::pin(async move {
+      let file =
+        HttpWebrootSolver::present(self, &identifier.value, token, key_authorization).await?;
+      let mut files = self.files.lock().unwrap_or_else(|e| e.into_inner());
+      files.insert(token.to_string(), file);
+      Ok(())
+    })
+  }
+
+  fn cleanup<'a>(
+    &'a self,
+    _identifier: &'a AcmeIdentifier,
+    token: &'a str,
+    _key_authorization: &'a str,
+  ) -> BoxFuture<'a, Result<()>> {
+    Box::pin(async move {
+      let file = {
+        let mut files = self.files.lock().unwrap_or_else(|e| e.into_inner());
+        files.remove(token)
+      };
+      match file {
+        Some(file) => file.cleanup(),
+        None => Ok(()),
+      }
+    })
+  }
+}
+
+impl HttpWebrootFile {
+  /// Path of challenge file
+  pub fn path(&self) -> &Path {
+    &self.path
+  }
+
+  /// Remove challenge file, it is also removed when dropped
+  pub fn cleanup(mut self) -> Result<()> {
+    let path = std::mem::take(&mut self.path);
+    match std::fs::remove_file(path) {
+      Err(err) if err.kind() != std::io::ErrorKind::NotFound => Err(err).context(IoOperationSnafu),
+      _ => Ok(()),
+    }
+  }
+}
+
+impl Drop for HttpWebrootFile {
+  fn drop(&mut self) {
+    if !self.path.as_os_str().is_empty() {
+      let _ = std::fs::remove_file(&self.path);
+    }
+  }
+}
+
+/// Write file readable by http server (mode `0644` on unix) but writable only by owner. Existing
+/// entry is removed first and the file is created exclusively, so symlink at `path` is replaced
+/// instead of followed
+fn write_file(path: &Path, content: &str) -> Result<()> {
+  match std::fs::remove_file(path) {
+    Err(err) if err.kind() != std::io::ErrorKind::NotFound => {
+      return Err(err).context(IoOperationSnafu);
+    }
+    _ => {}
+  }
+  let mut options = OpenOptions::new();
+  options.write(true).create_new(true);
+  #[cfg(unix)]
+  std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o644);
+  let mut file = options.open(path).context(IoOperationSnafu)?;
+  file.write_all(content.as_bytes()).context(IoOperationSnafu)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::test_util::{StubResponse, stub_server};
+
+  /// Stub http server serves files in `webroot`
+  async fn stub_webroot(webroot: PathBuf) -> String {
+    stub_server(move |req| {
+      let path = req.split(' ').nth(1).unwrap();
+      match std::fs::read_to_string(webroot.join(path.trim_start_matches('/'))) {
+        Ok(body) if req.contains("host: example.com") => {
+          StubResponse::ok(body).header("content-type", "text/plain")
+        }
+        _ => StubResponse::new("404 Not Found", ""),
+      }
+    })
+    .await
+  }
+
+  #[tokio::test]
+  async fn write_verify_and_remove() {
+    let webroot = std::env::temp_dir().join(format!("easy-acme-webroot-{}", rand::random::<u64>()));
+    let url = stub_webroot(webroot.clone()).await;
+    let solver = HttpWebrootSolver::new(&webroot)
+      .unwrap()
+      .verify_url(Some(&url));
+
+    let file = solver
+      .present("example.com", "token", "token.thumbprint")
+      .await
+      .unwrap();
+    let path = file.path().to_path_buf();
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "token.thumbprint");
+    #[cfg(unix)]
+    {
+      use std::os::unix::fs::PermissionsExt;
+      let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+      assert_eq!(mode & 0o777, 0o644);
+    }
+    file.cleanup().unwrap();
+    assert!(!path.exists());
+
+    let res = solver
+      .present("example.org", "other", "other.thumbprint")
+      .await;
+    assert!(res.unwrap_err().to_string().contains("not reachable"));
+    assert!(!path.with_file_name("other").exists());
+    std::fs::remove_dir_all(webroot).unwrap();
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn replace_symlink_instead_of_following() {
+    let dir = std::env::temp_dir().join(format!("easy-acme-symlink-{}", rand::random::<u64>()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let target = dir.join("target");
+    std::fs::write(&target, "secret").unwrap();
+    let path = dir.join("token");
+    std::os::unix::fs::symlink(&target, &path).unwrap();
+
+    write_file(&path, "token.thumbprint").unwrap();
+    assert_eq!(std::fs::read_to_string(&target).unwrap(), "secret");
+    assert!(!std::fs::symlink_metadata(&path).unwrap().is_symlink());
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "token.thumbprint");
+    std::fs::remove_dir_all(dir).unwrap();
+  }
+}
@@ -1,2 +1,40 @@
+use futures_util::future::BoxFuture;
+
+use crate::{
+  acme::{AcmeChallengeType, AcmeIdentifier},
+  errors::Result,
+};
+
 pub mod dns;
 pub mod http;
+pub mod tls_alpn;
+
+/// Solver of one challenge type, used by [`AcmeClient`](crate::acme::AcmeClient) to validate
+/// authorizations of order
+pub trait ChallengeSolver: Send + Sync {
+  /// Type of challenge this solver answers
+  fn challenge_type(&self) -> AcmeChallengeType;
+
+  /// Make challenge of `identifier` available, identifier of wildcard authorization is prefixed
+  /// with `*.`, for example, `*.example.com`
+  fn present<'a>(
+    &'a self,
+    identifier: &'a AcmeIdentifier,
+    token: &'a str,
+    key_authorization: &'a str,
+  ) -> BoxFuture<'a, Result<()>>;
+
+  /// Wait until presented challenges are visible to the CA, for example, propagation of DNS
+  /// records, returns immediately by default
+  fn wait_ready<'a>(&'a self) -> BoxFuture<'a, Result<()>> {
+    Box::pin(async move { Ok(()) })
+  }
+
+  /// Remove challenge made available by [`Self::present`]
+  fn cleanup<'a>(
+    &'a self,
+    identifier: &'a AcmeIdentifier,
+    token: &'a str,
+    key_authorization: &'a str,
+  ) -> BoxFuture<'a, Result<()>>;
+}
@@ -0,0 +1,32 @@
+use std::sync::Arc;
+
+use rcgen::{CertificateParams, CustomExtension, KeyPair};
+use rustls::{
+  crypto::ring::sign::any_supported_type,
+  pki_types::{PrivateKeyDer, PrivatePkcs8KeyDer},
+  sign::CertifiedKey,
+};
+use sha2::{Digest, Sha256};
+use snafu::ResultExt;
+
+use crate::errors::{GenerateCertificateSnafu, Result, TlsConfigSnafu};
+
+/// Self-signed validation certificate of identifier `value`, with critical `id-pe-acmeIdentifier`
+/// extension of `key_authorization`, a new key pair is generated for each certificate. IP address
+/// is encoded as `iPAddress` of subject alternative name
+pub fn tls_alpn_certificate(value: &str, key_authorization: &str) -> Result<Arc<CertifiedKey>> {
+  let digest = Sha256::digest(key_authorization.as_bytes());
+  let san = value.trim_matches(['[', ']']).to_string();
+  let mut params = CertificateParams::new(vec![san]).context(GenerateCertificateSnafu)?;
+  // `new_acme_identifier` marks the extension critical, as RFC 8737 requires
+  params
+    .custom_extensions
+    .push(CustomExtension::new_acme_identifier(&digest));
+  let key_pair = KeyPair::generate().context(GenerateCertificateSnafu)?;
+  let cert = params
+    .self_signed(&key_pair)
+    .context(GenerateCertificateSnafu)?;
+  let key = PrivateKeyDer::Pkcs8(PrivatePkcs8KeyDer::from(key_pair.serialize_der()));
+  let key = any_supported_type(&key).context(TlsConfigSnafu)?;
+  Ok(Arc::new(CertifiedKey::new(vec![cert.der().clone()], key)))
+}
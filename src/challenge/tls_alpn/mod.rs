@@ -0,0 +1,38 @@
+//! Implement of [TLS-ALPN-01](https://www.rfc-editor.org/rfc/rfc8737) challenge
+//!
+//! The CA connects to port 443 with ALPN protocol `acme-tls/1` and SNI of the domain, and expects
+//! a self-signed certificate with critical `id-pe-acmeIdentifier` extension, which contains
+//! SHA-256 digest of key authorization.
+
+use std::net::IpAddr;
+
+mod cert;
+pub use cert::tls_alpn_certificate;
+
+mod resolver;
+pub use resolver::TlsAlpnResolver;
+
+mod server;
+pub use server::TlsAlpnServer;
+
+/// ALPN protocol of challenge
+pub const ACME_TLS_ALPN: &[u8] = b"acme-tls/1";
+
+/// Reverse DNS name of IP address, used as SNI to validate IP identifier, see
+/// [RFC 8738](https://www.rfc-editor.org/rfc/rfc8738#section-6)
+pub fn reverse_dns_name(ip: IpAddr) -> String {
+  match ip {
+    IpAddr::V4(ip) => {
+      let [a, b, c, d] = ip.octets();
+      format!("{}.{}.{}.{}.in-addr.arpa", d, c, b, a)
+    }
+    IpAddr::V6(ip) => {
+      let mut name = String::new();
+      for byte in ip.octets().iter().rev() {
+        name.push_str(&format!("{:x}.{:x}.", byte & 0x0f, byte >> 4));
+      }
+      name.push_str("ip6.arpa");
+      name
+    }
+  }
+}
@@ -0,0 +1,105 @@
+use std::{
+  collections::HashMap,
+  net::IpAddr,
+  sync::{Arc, RwLock},
+};
+
+use futures_util::future::BoxFuture;
+use rustls::{
+  server::{ClientHello, ResolvesServerCert},
+  sign::CertifiedKey,
+};
+
+use crate::{
+  acme::{AcmeChallengeType, AcmeIdentifier},
+  challenge::{
+    ChallengeSolver,
+    tls_alpn::{ACME_TLS_ALPN, reverse_dns_name, tls_alpn_certificate},
+  },
+  errors::Result,
+};
+
+/// Certificate resolver of rustls, answers `acme-tls/1` handshakes with validation certificates,
+/// and other handshakes with fallback resolver, so it can be hooked into an existing TLS server.
+/// Cloned resolvers share the same certificates.
+#[derive(Debug, Clone, Default)]
+pub struct TlsAlpnResolver {
+  certs: Arc<RwLock<HashMap<String, Arc<CertifiedKey>>>>,
+  fallback: Option<Arc<dyn ResolvesServerCert>>,
+}
+
+impl TlsAlpnResolver {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Set resolver of handshakes other than `acme-tls/1`, default is `None` (handshake fails)
+  pub fn fallback(mut self, fallback: Arc<dyn ResolvesServerCert>) -> Self {
+    self.fallback = Some(fallback);
+    self
+  }
+
+  /// Serve validation certificate of `key_authorization` for identifier `value`, domain name is
+  /// matched with SNI directly, and IP address is matched with its reverse DNS name
+  pub fn present(&self, value: &str, key_authorization: &str) -> Result<()> {
+    let cert = tls_alpn_certificate(value, key_authorization)?;
+    let mut certs = self.certs.write().unwrap_or_else(|e| e.into_inner());
+    certs.insert(server_name(value), cert);
+    Ok(())
+  }
+
+  /// Stop serving validation certificate of identifier `value`
+  pub fn cleanup(&self, value: &str) {
+    let mut certs = self.certs.write().unwrap_or_else(|e| e.into_inner());
+    certs.remove(&server_name(value));
+  }
+}
+
+impl ChallengeSolver for TlsAlpnResolver {
+  fn challenge_type(&self) -> AcmeChallengeType {
+    AcmeChallengeType::TlsAlpn
+  }
+
+  fn present<'a>(
+    &'a self,
+    identifier: &'a AcmeIdentifier,
+    _token: &'a str,
+    key_authorization: &'a str,
+  ) -> BoxFuture<'a, Result<()>> {
+    Box::pin(async move { TlsAlpnResolver::present(self, &identifier.value, key_authorization) })
+  }
+
+  fn cleanup<'a>(
+    &'a self,
+    identifier: &'a AcmeIdentifier,
+    _token: &'a str,
+    _key_authorization: &'a str,
+  ) -> BoxFuture<'a, Result<()>> {
+    Box::pin(async move {
+      TlsAlpnResolver::cleanup(self, &identifier.value);
+      Ok(())
+    })
+  }
+}
+
+/// SNI of identifier `value`
+fn server_name(value: &str) -> String {
+  match value.trim_matches(['[', ']']).parse::<IpAddr>() {
+    Ok(ip) => reverse_dns_name(ip),
+    Err(_) => value.trim_end_matches('.').to_lowercase(),
+  }
+}
+
+impl ResolvesServerCert for TlsAlpnResolver {
+  fn resolve(&self, client_hello: ClientHello<'_>) -> Option<Arc<CertifiedKey>> {
+    let is_challenge = client_hello
+      .alpn()
+      .is_some_and(|mut protocols| protocols.any(|p| p == ACME_TLS_ALPN));
+    if !is_challenge {
+      return self.fallback.as_ref()?.resolve(client_hello);
+    }
+    let domain = client_hello.server_name()?.to_lowercase();
+    let certs = self.certs.read().unwrap_or_else(|e| e.into_inner());
+    certs.get(&domain).cloned()
+  }
+}
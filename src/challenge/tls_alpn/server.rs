@@ -0,0 +1,260 @@
+use std::{net::SocketAddr, sync::Arc, time::Duration};
+
+use futures_util::future::BoxFuture;
+use rustls::{ServerConfig, crypto::ring::default_provider};
+use snafu::ResultExt;
+use tokio::{io::AsyncWriteExt, net::TcpListener, task::JoinHandle};
+use tokio_rustls::TlsAcceptor;
+
+use crate::{
+  acme::{AcmeChallengeType, AcmeIdentifier},
+  challenge::{
+    ChallengeSolver,
+    tls_alpn::{ACME_TLS_ALPN, TlsAlpnResolver},
+  },
+  errors::{IoOperationSnafu, Result, TlsConfigSnafu},
+};
+
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+/// Wait before accepting again, so persistent errors like `EMFILE` do not spin the loop
+const ACCEPT_BACKOFF: Duration = Duration::from_millis(100);
+
+/// Standalone TLS listener only answers `acme-tls/1` handshakes with validation certificates,
+/// connection is closed after handshake
+#[derive(Debug)]
+pub struct TlsAlpnServer {
+  addr: SocketAddr,
+  resolver: TlsAlpnResolver,
+  task: JoinHandle<()>,
+}
+
+impl TlsAlpnServer {
+  /// Bind `addr` and start serving, for example, `0.0.0.0:443`, port `0` binds a random port
+  pub async fn bind(addr: SocketAddr, resolver: TlsAlpnResolver) -> Result<Self> {
+    let mut config = ServerConfig::builder_with_provider(Arc::new(default_provider()))
+      .with_safe_default_protocol_versions()
+      .context(TlsConfigSnafu)?
+      .with_no_client_auth()
+      .with_cert_resolver(Arc::new(resolver.clone()));
+    config.alpn_protocols = vec![ACME_TLS_ALPN.to_vec()];
+    let acceptor = TlsAcceptor::from(Arc::new(config));
+    let listener = TcpListener::bind(addr).await.context(IoOperationSnafu)?;
+    let addr = listener.local_addr().context(IoOperationSnafu)?;
+    let task = tokio::spawn(serve(listener, acceptor));
+    Ok(TlsAlpnServer {
+      addr,
+      resolver,
+      task,
+    })
+  }
+
+  /// Address the server is listening on
+  pub fn local_addr(&self) -> SocketAddr {
+    self.addr
+  }
+
+  /// Resolver of the server
+  pub fn resolver(&self) -> &TlsAlpnResolver {
+    &self.resolver
+  }
+
+  /// Serve validation certificate of `key_authorization` for identifier `value`, see
+  /// [`TlsAlpnResolver::present`]
+  pub fn present(&self, value: &str, key_authorization: &str) -> Result<()> {
+    self.resolver.present(value, key_authorization)
+  }
+
+  /// Stop serving validation certificate of identifier `value`
+  pub fn cleanup(&self, value: &str) {
+    self.resolver.cleanup(value);
+  }
+
+  /// Stop accepting connections and release the address
+  pub async fn shutdown(mut self) {
+    self.task.abort();
+    let _ = (&mut self.task).await;
+  }
+}
+
+impl ChallengeSolver for TlsAlpnServer {
+  fn challenge_type(&self) -> AcmeChallengeType {
+    AcmeChallengeType::TlsAlpn
+  }
+
+  fn present<'a>(
+    &'a self,
+    identifier: &'a AcmeIdentifier,
+    token: &'a str,
+    key_authorization: &'a str,
+  ) -> BoxFuture<'a, Result<()>> {
+    ChallengeSolver::present(&self.resolver, identifier, token, key_authorization)
+  }
+
+  fn cleanup<'a>(
+    &'a self,
+    identifier: &'a AcmeIdentifier,
+    token: &'a str,
+    key_authorization: &'a str,
+  ) -> BoxFuture<'a, Result<()>> {
+    ChallengeSolver::cleanup(&self.resolver, identifier, token, key_authorization)
+  }
+}
+
+impl Drop for TlsAlpnServer {
+  fn drop(&mut self) {
+    self.task.abort();
+  }
+}
+
+async fn serve(listener: TcpListener, acceptor: TlsAcceptor) {
+  loop {
+    let Ok((stream, _)) = listener.accept().await else {
+      tokio::time::sleep(ACCEPT_BACKOFF).await;
+      continue;
+    };
+    let acceptor = acceptor.clone();
+    tokio::spawn(async move {
+      let handshake = tokio::time::timeout(HANDSHAKE_TIMEOUT, acceptor.accept(stream));
+      if let Ok(Ok(mut stream)) = handshake.await {
+        let _ = stream.shutdown().await;
+      }
+    });
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use rustls::{
+    ClientConfig, DigitallySignedStruct, SignatureScheme,
+    client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier},
+    pki_types::{CertificateDer, ServerName, UnixTime},
+  };
+  use sha2::{Digest, Sha256};
+  use tokio::net::TcpStream;
+  use tokio_rustls::TlsConnector;
+
+  use super::*;
+
+  /// Verifier accepts any certificate, validation certificate is checked by test itself
+  #[derive(Debug)]
+  struct AcceptAnyCert;
+
+  impl ServerCertVerifier for AcceptAnyCert {
+    fn verify_server_cert(
+      &self,
+      _end_entity: &CertificateDer<'_>,
+      _intermediates: &[CertificateDer<'_>],
+      _server_name: &ServerName<'_>,
+      _ocsp_response: &[u8],
+      _now: UnixTime,
+    ) -> std::result::Result<ServerCertVerified, rustls::Error> {
+      Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+      &self,
+      _message: &[u8],
+      _cert: &CertificateDer<'_>,
+      _dss: &DigitallySignedStruct,
+    ) -> std::result::Result<HandshakeSignatureValid, rustls::Error> {
+      Ok(HandshakeSignatureValid::assertion())
+    }
+
+    fn verify_tls13_signature(
+      &self,
+      _message: &[u8],
+      _cert: &CertificateDer<'_>,
+      _dss: &DigitallySignedStruct,
+    ) -> std::result::Result<HandshakeSignatureValid, rustls::Error> {
+      Ok(HandshakeSignatureValid::assertion())
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+      default_provider()
+        .signature_verification_algorithms
+        .supported_schemes()
+    }
+  }
+
+  /// Connector offers `acme-tls/1` only, as the CA does
+  fn acme_tls_connector() -> TlsConnector {
+    let mut config = ClientConfig::builder_with_provider(Arc::new(default_provider()))
+      .with_safe_default_protocol_versions()
+      .unwrap()
+      .dangerous()
+      .with_custom_certificate_verifier(Arc::new(AcceptAnyCert))
+      .with_no_client_auth();
+    config.alpn_protocols = vec![ACME_TLS_ALPN.to_vec()];
+    TlsConnector::from(Arc::new(config))
+  }
+
+  #[tokio::test]
+  async fn handshake_with_validation_certificate() {
+    let addr = SocketAddr::from(([127, 0, 0, 1], 0));
+    let server = TlsAlpnServer::bind(addr, TlsAlpnResolver::new())
+      .await
+      .unwrap();
+    server.present("Example.com", "token.thumbprint").unwrap();
+
+    let connector = acme_tls_connector();
+    let stream = TcpStream::connect(server.local_addr()).await.unwrap();
+    let name = ServerName::try_from("example.com").unwrap();
+    let stream = connector.connect(name, stream).await.unwrap();
+    let (_, conn) = stream.get_ref();
+    assert_eq!(conn.alpn_protocol(), Some(ACME_TLS_ALPN));
+
+    // id-pe-acmeIdentifier (1.3.6.1.5.5.7.1.31), critical, OCTET STRING of SHA-256 digest
+    let mut extension = vec![0x06, 0x08, 0x2b, 0x06, 0x01, 0x05, 0x05, 0x07, 0x01, 0x1f];
+    extension.extend_from_slice(&[0x01, 0x01, 0xff, 0x04, 0x22, 0x04, 0x20]);
+    extension.extend_from_slice(&Sha256::digest(b"token.thumbprint"));
+    let cert = &conn.peer_certificates().unwrap()[0];
+    assert!(cert.windows(extension.len()).any(|w| w == extension));
+
+    server.cleanup("example.com");
+    let stream = TcpStream::connect(server.local_addr()).await.unwrap();
+    let name = ServerName::try_from("example.com").unwrap();
+    assert!(connector.connect(name, stream).await.is_err());
+    server.shutdown().await;
+  }
+
+  #[tokio::test]
+  async fn handshake_of_ip_identifier() {
+    let addr = SocketAddr::from(([127, 0, 0, 1], 0));
+    let server = TlsAlpnServer::bind(addr, TlsAlpnResolver::new())
+      .await
+      .unwrap();
+    let identifier = AcmeIdentifier::ip("192.0.2.1".parse().unwrap());
+    ChallengeSolver::present(&server, &identifier, "token", "token.thumbprint")
+      .await
+      .unwrap();
+
+    // the CA sends reverse DNS name as SNI, RFC 8738 section 6
+    let name = identifier.tls_alpn_name();
+    assert_eq!(name, "1.2.0.192.in-addr.arpa");
+    let stream = TcpStream::connect(server.local_addr()).await.unwrap();
+    let server_name = ServerName::try_from(name.clone()).unwrap();
+    let stream = acme_tls_connector()
+      .connect(server_name, stream)
+      .await
+      .unwrap();
+    let (_, conn) = stream.get_ref();
+    assert_eq!(conn.alpn_protocol(), Some(ACME_TLS_ALPN));
+    // subject alternative name is iPAddress (tag 7) of the address
+    let cert = &conn.peer_certificates().unwrap()[0];
+    let san = [0x87, 0x04, 192, 0, 2, 1];
+    assert!(cert.windows(san.len()).any(|w| w == san));
+
+    ChallengeSolver::cleanup(&server, &identifier, "token", "token.thumbprint")
+      .await
+      .unwrap();
+    let stream = TcpStream::connect(server.local_addr()).await.unwrap();
+    let server_name = ServerName::try_from(name).unwrap();
+    assert!(
+      acme_tls_connector()
+        .connect(server_name, stream)
+        .await
+        .is_err()
+    );
+    server.shutdown().await;
+  }
+}
@@ -26,6 +26,22 @@ pub enum Error {
     location: Location,
   },
 
+  SerializeXml {
+    #[snafu(source)]
+    source: quick_xml::SeError,
+
+    #[snafu(implicit)]
+    location: Location,
+  },
+
+  DeserializeXml {
+    #[snafu(source)]
+    source: quick_xml::DeError,
+
+    #[snafu(implicit)]
+    location: Location,
+  },
+
   SerializeUrl {
     #[snafu(source)]
     source: serde_urlencoded::ser::Error,
@@ -34,6 +50,14 @@ pub enum Error {
     location: Location,
   },
 
+  DecodeBase64 {
+    #[snafu(source)]
+    source: base64ct::Error,
+
+    #[snafu(implicit)]
+    location: Location,
+  },
+
   InvalidHmacKey {
     #[snafu(source)]
     source: crypto_common::InvalidLength,
@@ -58,13 +82,53 @@ pub enum Error {
     location: Location,
   },
   
+  IoOperation {
+    #[snafu(source)]
+    source: std::io::Error,
+
+    #[snafu(implicit)]
+    location: Location,
+  },
+
   P256Signature {
     #[snafu(source)]
     source: signature::Error,
 
     #[snafu(implicit)]
     location: Location,
-  }
+  },
+
+  RsaPrivateKey {
+    #[snafu(source)]
+    source: rsa::pkcs8::Error,
+
+    #[snafu(implicit)]
+    location: Location,
+  },
+
+  RsaSignature {
+    #[snafu(source)]
+    source: signature::Error,
+
+    #[snafu(implicit)]
+    location: Location,
+  },
+
+  GenerateCertificate {
+    #[snafu(source)]
+    source: rcgen::Error,
+
+    #[snafu(implicit)]
+    location: Location,
+  },
+
+  TlsConfig {
+    #[snafu(source)]
+    source: rustls::Error,
+
+    #[snafu(implicit)]
+    location: Location,
+  },
 }
 
 #[derive(Debug)]
@@ -2,7 +2,9 @@ mod authority;
 pub mod challenge;
 mod errors;
 mod util;
-mod acme;
+pub mod acme;
+#[cfg(test)]
+mod test_util;
 
 pub fn add(left: u64, right: u64) -> u64 {
   left + right
@@ -0,0 +1,162 @@
+use std::net::SocketAddr;
+
+use tokio::{
+  io::{AsyncReadExt, AsyncWriteExt},
+  net::{TcpListener, TcpStream, UdpSocket},
+};
+
+use crate::challenge::dns::{
+  RecordType,
+  resolver::message::{CLASS_IN, DnsMessage, DnsRecord, RecordData},
+};
+
+/// Response of stub http server, content type is `application/json` unless set by `header`
+pub(crate) struct StubResponse {
+  status: String,
+  headers: Vec<(String, String)>,
+  body: String,
+}
+
+impl StubResponse {
+  pub(crate) fn new(status: impl Into<String>, body: impl Into<String>) -> Self {
+    Self {
+      status: status.into(),
+      headers: vec![],
+      body: body.into(),
+    }
+  }
+
+  pub(crate) fn ok(body: impl Into<String>) -> Self {
+    Self::new("200 OK", body)
+  }
+
+  pub(crate) fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+    self.headers.push((name.into(), value.into()));
+    self
+  }
+
+  fn to_http(&self) -> String {
+    let mut head = format!("HTTP/1.1 {}\r\n", self.status);
+    if !self
+      .headers
+      .iter()
+      .any(|(name, _)| name.eq_ignore_ascii_case("content-type"))
+    {
+      head.push_str("content-type: application/json\r\n");
+    }
+    for (name, value) in &self.headers {
+      head.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    format!(
+      "{}content-length: {}\r\nconnection: close\r\n\r\n{}",
+      head,
+      self.body.len(),
+      self.body
+    )
+  }
+}
+
+/// Read one http request, header names are kept as sent, reqwest sends them in lowercase
+pub(crate) async fn read_request(stream: &mut TcpStream) -> String {
+  let mut req = vec![];
+  let mut buf = [0u8; 4096];
+  loop {
+    let len = stream.read(&mut buf).await.unwrap();
+    req.extend_from_slice(&buf[..len]);
+    let text = String::from_utf8_lossy(&req).to_string();
+    if let Some((head, body)) = text.split_once("\r\n\r\n") {
+      let length = head
+        .lines()
+        .find_map(|l| {
+          l.to_lowercase()
+            .strip_prefix("content-length: ")?
+            .parse()
+            .ok()
+        })
+        .unwrap_or(0usize);
+      if body.len() >= length || len == 0 {
+        return text;
+      }
+    }
+  }
+}
+
+/// Body of http request read by [`read_request`]
+pub(crate) fn request_body(req: &str) -> &str {
+  req
+    .split_once("\r\n\r\n")
+    .map(|(_, body)| body)
+    .unwrap_or("")
+}
+
+/// Start stub http server answering every request with `handler`, returns its base url
+pub(crate) async fn stub_server<F>(mut handler: F) -> String
+where
+  F: FnMut(&str) -> StubResponse + Send + 'static,
+{
+  let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+  let addr = listener.local_addr().unwrap();
+  tokio::spawn(async move {
+    loop {
+      let (mut stream, _) = listener.accept().await.unwrap();
+      let req = read_request(&mut stream).await;
+      let res = handler(&req).to_http();
+      stream.write_all(res.as_bytes()).await.unwrap();
+    }
+  });
+  format!("http://{}", addr)
+}
+
+/// Record in class IN with ttl of 60 seconds
+pub(crate) fn dns_record(name: &str, rtype: RecordType, data: RecordData) -> DnsRecord {
+  DnsRecord {
+    name: name.to_string(),
+    rtype: rtype.code(),
+    class: CLASS_IN,
+    ttl: 60,
+    data,
+  }
+}
+
+/// Start stub nameserver over UDP, `handler` turns the request into response, the response flag is
+/// set before sending
+pub(crate) async fn dns_stub_server<F>(mut handler: F) -> SocketAddr
+where
+  F: FnMut(DnsMessage) -> DnsMessage + Send + 'static,
+{
+  let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+  let addr = socket.local_addr().unwrap();
+  tokio::spawn(async move {
+    let mut buf = [0u8; 1024];
+    loop {
+      let (len, peer) = socket.recv_from(&mut buf).await.unwrap();
+      let mut res = handler(DnsMessage::decode(&buf[..len]).unwrap());
+      res.flags |= 0x8000;
+      socket.send_to(&res.encode().unwrap(), peer).await.unwrap();
+    }
+  });
+  addr
+}
+
+mod tests {
+  use super::*;
+
+  #[tokio::test]
+  async fn stub_server_reads_whole_body() {
+    let base = stub_server(|req| {
+      let body = request_body(req).to_uppercase();
+      StubResponse::ok(body).header("content-type", "text/plain")
+    })
+    .await;
+    // longer than the read buffer, so the body arrives in several reads
+    let body = "a".repeat(10000);
+    let res = reqwest::Client::new()
+      .post(&base)
+      .body(body.clone())
+      .send()
+      .await
+      .unwrap();
+    assert_eq!(res.headers()["content-type"], "text/plain");
+    assert_eq!(res.text().await.unwrap(), body.to_uppercase());
+  }
+}
@@ -1,11 +1,16 @@
+use std::collections::BTreeMap;
+
 use base64ct::Encoding;
 use hmac::{Hmac, Mac};
-use http::HeaderValue;
+use http::{HeaderMap, HeaderValue};
 use serde::Serialize;
-use sha2::Sha256;
+use sha2::{Digest, Sha256, Sha512};
 use snafu::ResultExt;
 
-use crate::errors::{EnvironmentVarSnafu, InvalidHeader, InvalidHeaderSnafu, InvalidHmacKeySnafu, Result, SerializeJsonSnafu};
+use crate::errors::{
+  DecodeBase64Snafu, EnvironmentVarSnafu, InvalidHeader, InvalidHeaderSnafu, InvalidHmacKeySnafu,
+  Result, SerializeJsonSnafu,
+};
 
 pub fn str_to_header_value(value: impl AsRef<str>) -> Result<HeaderValue> {
   let value = value.as_ref();
@@ -22,12 +27,93 @@ pub fn sha2_hmac(key: impl AsRef<[u8]>, data: &[u8]) -> Result<Vec<u8>> {
   Ok(hmac.finalize().into_bytes().to_vec())
 }
 
+type HmacSha512 = Hmac<Sha512>;
+pub fn sha512_hmac(key: impl AsRef<[u8]>, data: &[u8]) -> Result<Vec<u8>> {
+  let mut hmac = HmacSha512::new_from_slice(key.as_ref()).context(InvalidHmacKeySnafu)?;
+  hmac.update(data);
+  Ok(hmac.finalize().into_bytes().to_vec())
+}
+
+pub fn sha2_hex(data: impl AsRef<[u8]>) -> String {
+  hex::encode(Sha256::digest(data.as_ref()))
+}
+
+/// Percent-encode as RFC 3986, only unreserved characters are kept
+pub fn uri_encode(data: impl AsRef<str>) -> String {
+  let mut encoded = String::new();
+  for byte in data.as_ref().bytes() {
+    match byte {
+      b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+        encoded.push(byte as char)
+      }
+      _ => encoded.push_str(&format!("%{:02X}", byte)),
+    }
+  }
+  encoded
+}
+
+/// Canonical request shared by AWS-like signatures, `canonical_uri` should be encoded already
+///
+/// `return`: canonical request and signed header names
+pub fn canonical_request(
+  method: &str,
+  canonical_uri: &str,
+  query: &[(&str, &str)],
+  headers: &HeaderMap,
+  payload: &[u8],
+) -> (String, String) {
+  let mut query = query
+    .iter()
+    .map(|(k, v)| (uri_encode(k), uri_encode(v)))
+    .collect::<Vec<_>>();
+  query.sort();
+  let canonical_query = query
+    .iter()
+    .map(|(k, v)| format!("{}={}", k, v))
+    .collect::<Vec<_>>()
+    .join("&");
+  let mut canonical_headers = BTreeMap::new();
+  for (name, value) in headers {
+    let value = String::from_utf8_lossy(value.as_bytes()).trim().to_string();
+    canonical_headers
+      .entry(name.as_str().to_lowercase())
+      .and_modify(|v: &mut String| {
+        v.push(',');
+        v.push_str(&value)
+      })
+      .or_insert(value);
+  }
+  let signed_headers = canonical_headers
+    .keys()
+    .cloned()
+    .collect::<Vec<_>>()
+    .join(";");
+  let mut canonical_request = format!("{}\n{}\n{}\n", method, canonical_uri, canonical_query);
+  for (name, value) in &canonical_headers {
+    canonical_request.push_str(&format!("{}:{}\n", name, value));
+  }
+  canonical_request.push_str(&format!("\n{}\n{}", signed_headers, sha2_hex(payload)));
+  (canonical_request, signed_headers)
+}
+
+/// Host of `url` with port if present, for example, `alidns.aliyuncs.com` of
+/// `https://alidns.aliyuncs.com/`
+pub fn url_host(url: &str) -> &str {
+  let host = url.split_once("://").map_or(url, |(_, host)| host);
+  host.split('/').next().unwrap_or(host)
+}
+
 pub fn json_serialize(data: &impl Serialize) -> Result<String> {
   serde_json::to_string(data).context(SerializeJsonSnafu)
 }
 
-pub fn base64_url_str(data: impl AsRef<[u8]>) -> String {
-  base64ct::Base64Url::encode_string(data.as_ref())
+/// Base64url without padding, JWS (RFC 7515) and ACME (RFC 8555) forbid the trailing `=`
+pub fn base64_url_unpadded_str(data: impl AsRef<[u8]>) -> String {
+  base64ct::Base64UrlUnpadded::encode_string(data.as_ref())
+}
+
+pub fn base64_decode(data: impl AsRef<str>) -> Result<Vec<u8>> {
+  base64ct::Base64::decode_vec(data.as_ref().trim()).context(DecodeBase64Snafu)
 }
 
 /// __keys.len must > 0__
@@ -45,3 +131,46 @@ pub fn env_single_var<'a>(keys: impl AsRef<[&'a str]> + 'a) -> Result<String> {
   }
   first_err
 }
+
+#[cfg(test)]
+mod tests {
+  use http::header::HOST;
+
+  use super::*;
+
+  #[test]
+  fn base64_url_unpadded_of_jws_example() {
+    // header and payload of JWS example in RFC 7515 appendix A.1
+    assert_eq!(
+      base64_url_unpadded_str("{\"typ\":\"JWT\",\r\n \"alg\":\"HS256\"}"),
+      "eyJ0eXAiOiJKV1QiLA0KICJhbGciOiJIUzI1NiJ9"
+    );
+    assert_eq!(
+      base64_url_unpadded_str(
+        "{\"iss\":\"joe\",\r\n \"exp\":1300819380,\r\n \"http://example.com/is_root\":true}"
+      ),
+      "eyJpc3MiOiJqb2UiLA0KICJleHAiOjEzMDA4MTkzODAsDQogImh0dHA6Ly9leGFtcGxlLmNvbS9pc19yb290Ijp0cnVlfQ"
+    );
+  }
+
+  #[test]
+  fn canonical_request_of_sigv4_suite() {
+    // get-vanilla-query-order-key-case of AWS Signature Version 4 test suite
+    let mut headers = HeaderMap::new();
+    headers.insert(HOST, HeaderValue::from_static("example.amazonaws.com"));
+    headers.insert("X-Amz-Date", HeaderValue::from_static("20150830T123600Z"));
+    let query = [("Param2", "value2"), ("Param1", "value1")];
+    let (request, signed_headers) = canonical_request("GET", "/", &query, &headers, b"");
+    assert_eq!(
+      request,
+      "GET\n/\nParam1=value1&Param2=value2\nhost:example.amazonaws.com\n\
+       x-amz-date:20150830T123600Z\n\nhost;x-amz-date\n\
+       e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+    );
+    assert_eq!(signed_headers, "host;x-amz-date");
+    assert_eq!(
+      sha2_hex(request),
+      "816cd5b414d056048ba4f7c5386d6e0533120fb1fcfa93762cf0fc39e2cf19e0"
+    );
+  }
+}